@@ -0,0 +1,90 @@
+//! Compares the size of tilemaps encoded with the compact binary format to
+//! the size of the same tilemaps serialized with serde and bincode.
+//!
+//! Run with `cargo bench --bench codec_size --features serialize`.
+
+use bevy::{asset::HandleId, prelude::*};
+use bevy_tilemap::prelude::*;
+use rand::Rng;
+
+/// The amount of tiles in each direction from the center that are filled.
+const RADIUS: i32 = 64;
+
+fn new_tilemap() -> Tilemap {
+    let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    Tilemap::builder()
+        .texture_atlas(texture_atlas_handle)
+        .add_layer(LayerKind::Dense, 0)
+        .add_layer(LayerKind::Sparse, 1)
+        .auto_chunk()
+        .finish()
+        .unwrap()
+}
+
+fn points() -> impl Iterator<Item = (i32, i32)> {
+    (-RADIUS..RADIUS).flat_map(|y| (-RADIUS..RADIUS).map(move |x| (x, y)))
+}
+
+/// A floor of a single tile, which is the best case for run-length encoding.
+fn uniform_floor() -> Tilemap {
+    let mut tilemap = new_tilemap();
+    tilemap
+        .insert_tiles(points().map(|point| Tile::new(point, 0)))
+        .unwrap();
+    tilemap
+}
+
+/// A floor with walls around rooms and a few scattered objects.
+fn dungeon() -> Tilemap {
+    let mut tilemap = new_tilemap();
+    let mut rng = rand::thread_rng();
+    let mut tiles = Vec::new();
+    for (x, y) in points() {
+        let wall = x % 16 == 0 || y % 16 == 0;
+        tiles.push(Tile::new((x, y), if wall { 1 } else { 0 }));
+        if !wall && rng.gen_ratio(1, 50) {
+            tiles.push(Tile::with_z_order((x, y), rng.gen_range(2, 6), 1));
+        }
+    }
+    tilemap.insert_tiles(tiles).unwrap();
+    tilemap
+}
+
+/// A floor of random tiles and tints, which is the worst case for the
+/// compact format.
+fn noise() -> Tilemap {
+    let mut tilemap = new_tilemap();
+    let mut rng = rand::thread_rng();
+    let tiles: Vec<Tile> = points()
+        .map(|point| {
+            let tint = Color::rgba(rng.gen(), rng.gen(), rng.gen(), 1.0);
+            Tile::with_tint(point, rng.gen_range(0, 64), tint)
+        })
+        .collect();
+    tilemap.insert_tiles(tiles).unwrap();
+    tilemap
+}
+
+fn main() {
+    println!(
+        "{:<16} {:>12} {:>12} {:>8}",
+        "tilemap", "serde", "compact", "ratio"
+    );
+    for (name, tilemap) in [
+        ("uniform floor", uniform_floor()),
+        ("dungeon", dungeon()),
+        ("noise", noise()),
+    ]
+    .iter()
+    {
+        let serde = bincode::serialize(tilemap).unwrap().len();
+        let compact = tilemap.to_bytes().unwrap().len();
+        println!(
+            "{:<16} {:>12} {:>12} {:>7.1}x",
+            name,
+            serde,
+            compact,
+            serde as f64 / compact as f64
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Animated tiles which cycle through sprites over time.
+//!
+//! An animation is defined once on the tilemap with [`Tilemap::add_animation`]
+//! which returns an ID. Tiles are attached to the animation when they are
+//! inserted with [`Tile::with_animation`]. All tiles which share an animation
+//! are always on the same frame, even across chunks.
+//!
+//! Only the chunk layers which contain animated tiles are updated when an
+//! animation changes frame.
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle);
+//!
+//! // Water which alternates between sprites 4 and 5 every half a second.
+//! let water = tilemap.add_animation(TileAnimation::new(vec![(4, 0.5), (5, 0.5)]));
+//!
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile::with_animation((1, 1), water, 0)).unwrap();
+//!
+//! assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 4);
+//! ```
+//!
+//! [`Tilemap::add_animation`]: crate::tilemap::Tilemap::add_animation
+//! [`Tile::with_animation`]: crate::tile::Tile::with_animation
+
+use crate::{lib::*, tilemap::Tilemap};
+
+/// A single frame of an animation.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AnimationFrame {
+    /// The sprites index in the texture atlas.
+    pub sprite_index: usize,
+    /// How long the frame is shown for in seconds.
+    pub duration: f32,
+}
+
+/// The definition of an animation, a list of sprites each with a duration.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileAnimation {
+    /// The frames of the animation in order.
+    frames: Vec<AnimationFrame>,
+}
+
+impl TileAnimation {
+    /// Constructs a new animation from sprite indexes paired with how long
+    /// they are shown for in seconds.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let animation = TileAnimation::new(vec![(0, 0.25), (1, 0.25), (2, 0.5)]);
+    ///
+    /// assert_eq!(animation.frames().len(), 3);
+    /// ```
+    pub fn new<I: IntoIterator<Item = (usize, f32)>>(frames: I) -> TileAnimation {
+        TileAnimation {
+            frames: frames
+                .into_iter()
+                .map(|(sprite_index, duration)| AnimationFrame {
+                    sprite_index,
+                    duration,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the frames of the animation.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let animation = TileAnimation::new(vec![(7, 1.0)]);
+    ///
+    /// assert_eq!(animation.frames()[0].sprite_index, 7);
+    /// ```
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    /// The total duration of a single loop of the animation in seconds.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let animation = TileAnimation::new(vec![(0, 0.25), (1, 0.75)]);
+    ///
+    /// assert_eq!(animation.duration(), 1.0);
+    /// ```
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+}
+
+/// The playing state of an animation.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct AnimationState {
+    /// The definition of the animation.
+    animation: TileAnimation,
+    /// The current frame of the animation.
+    frame: usize,
+    /// The elapsed time in seconds in the current loop of the animation.
+    elapsed: f32,
+}
+
+impl AnimationState {
+    /// Constructs a new animation state starting at the first frame.
+    pub(crate) fn new(animation: TileAnimation) -> AnimationState {
+        AnimationState {
+            animation,
+            frame: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advances the animation by a delta in seconds and returns `true` if the
+    /// frame has changed.
+    pub(crate) fn tick(&mut self, delta: f32) -> bool {
+        let duration = self.animation.duration();
+        if duration <= 0.0 {
+            return false;
+        }
+        self.elapsed = (self.elapsed + delta) % duration;
+
+        let previous_frame = self.frame;
+        let mut time = self.elapsed;
+        for (frame, animation_frame) in self.animation.frames.iter().enumerate() {
+            self.frame = frame;
+            if time < animation_frame.duration {
+                break;
+            }
+            time -= animation_frame.duration;
+        }
+
+        self.frame != previous_frame
+    }
+
+    /// Returns the definition of the animation.
+    pub(crate) fn animation(&self) -> &TileAnimation {
+        &self.animation
+    }
+
+    /// Returns the sprite index of the current frame.
+    pub(crate) fn sprite_index(&self) -> Option<usize> {
+        self.animation
+            .frames
+            .get(self.frame)
+            .map(|frame| frame.sprite_index)
+    }
+}
+
+/// Advances all the animations of every tilemap and flags the chunk layers
+/// with animated tiles that need to be updated.
+pub(crate) fn tile_animation_system(time: Res<Time>, mut query: Query<&mut Tilemap>) {
+    for mut tilemap in query.iter_mut() {
+        tilemap.update_animations(time.delta_seconds);
+    }
+}
@@ -0,0 +1,251 @@
+//! Automatic tiling of terrains based on their neighbours.
+//!
+//! A tile can be given a terrain with [`Tile::with_terrain`]. If the tilemap
+//! has an [`AutoTileset`] registered for that terrain, the sprite of the tile
+//! is picked from a bitmask of its neighbours that share the same terrain.
+//! Whenever a tile is inserted or cleared its neighbours are updated as well,
+//! even if they live in another chunk.
+//!
+//! There are two kinds of auto tilesets.
+//!
+//! * [`AutoTileKind::Bitmask4`] only looks at the 4 direct neighbours and
+//! requires 16 sprites.
+//! * [`AutoTileKind::Blob47`] looks at all 8 neighbours and requires 47 sprites.
+//! Corners are only counted if both edges beside it share the terrain, which
+//! is what reduces the 256 possible combinations down to 47.
+//!
+//! # Bitmask
+//!
+//! The 4-bit mask uses `N = 1`, `E = 2`, `S = 4` and `W = 8`, the sprites are
+//! indexed by the mask value directly.
+//!
+//! The 8-bit mask goes clockwise starting from north with `N = 1`, `NE = 2`,
+//! `E = 4`, `SE = 8`, `S = 16`, `SW = 32`, `W = 64` and `NW = 128`. The 47
+//! sprites are expected in the ascending order of the reduced masks listed in
+//! [`BLOB47_MASKS`].
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let wall = 1;
+//! let mut sprites = [0; 16];
+//! for (mask, sprite) in sprites.iter_mut().enumerate() {
+//!     *sprite = 10 + mask;
+//! }
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .auto_chunk()
+//!     .auto_tile(wall, AutoTileset::bitmask4(sprites))
+//!     .finish()
+//!     .unwrap();
+//!
+//! let tiles = vec![
+//!     Tile::with_terrain((0, 0), wall, 0),
+//!     Tile::with_terrain((1, 0), wall, 0),
+//! ];
+//! tilemap.insert_tiles(tiles).unwrap();
+//!
+//! // The left wall has a neighbour to the east, the right one to the west.
+//! assert_eq!(tilemap.get_tile((0, 0), 0).unwrap().index, 12);
+//! assert_eq!(tilemap.get_tile((1, 0), 0).unwrap().index, 18);
+//! ```
+//!
+//! [`Tile::with_terrain`]: crate::tile::Tile::with_terrain
+//! [`AutoTileset`]: AutoTileset
+//! [`AutoTileKind::Bitmask4`]: AutoTileKind::Bitmask4
+//! [`AutoTileKind::Blob47`]: AutoTileKind::Blob47
+//! [`BLOB47_MASKS`]: BLOB47_MASKS
+
+use crate::lib::*;
+
+/// The neighbour to the north.
+const NORTH: u8 = 0b0000_0001;
+/// The neighbour to the north east.
+const NORTH_EAST: u8 = 0b0000_0010;
+/// The neighbour to the east.
+const EAST: u8 = 0b0000_0100;
+/// The neighbour to the south east.
+const SOUTH_EAST: u8 = 0b0000_1000;
+/// The neighbour to the south.
+const SOUTH: u8 = 0b0001_0000;
+/// The neighbour to the south west.
+const SOUTH_WEST: u8 = 0b0010_0000;
+/// The neighbour to the west.
+const WEST: u8 = 0b0100_0000;
+/// The neighbour to the north west.
+const NORTH_WEST: u8 = 0b1000_0000;
+
+/// The offsets of all 8 neighbours paired with their bit in the mask.
+pub(crate) const NEIGHBOUR_OFFSETS: [((i32, i32), u8); 8] = [
+    ((0, 1), NORTH),
+    ((1, 1), NORTH_EAST),
+    ((1, 0), EAST),
+    ((1, -1), SOUTH_EAST),
+    ((0, -1), SOUTH),
+    ((-1, -1), SOUTH_WEST),
+    ((-1, 0), WEST),
+    ((-1, 1), NORTH_WEST),
+];
+
+/// All 47 reduced 8-bit masks in ascending order.
+///
+/// The sprites given to [`AutoTileset::blob47`] must follow this order.
+///
+/// [`AutoTileset::blob47`]: AutoTileset::blob47
+pub const BLOB47_MASKS: [u8; 47] = [
+    0, 1, 4, 5, 7, 16, 17, 20, 21, 23, 28, 29, 31, 64, 65, 68, 69, 71, 80, 81, 84, 85, 87, 92, 93,
+    95, 112, 113, 116, 117, 119, 124, 125, 127, 193, 197, 199, 209, 213, 215, 221, 223, 241, 245,
+    247, 253, 255,
+];
+
+/// The kind of bitmask that an auto tileset uses.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum AutoTileKind {
+    /// Only the 4 direct neighbours are considered, 16 sprites are required.
+    Bitmask4,
+    /// All 8 neighbours are considered, 47 sprites are required.
+    Blob47,
+}
+
+/// A set of sprites for a terrain which are picked from by a neighbour mask.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct AutoTileset {
+    /// The kind of mask used to pick the sprites.
+    kind: AutoTileKind,
+    /// The sprite indexes in the texture atlas, in the order of the masks.
+    sprites: Vec<usize>,
+}
+
+impl AutoTileset {
+    /// Constructs a new auto tileset which uses the 4 direct neighbours.
+    ///
+    /// The sprites are indexed by the 4-bit mask of `N = 1`, `E = 2`, `S = 4`
+    /// and `W = 8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let tileset = AutoTileset::bitmask4([0; 16]);
+    ///
+    /// assert_eq!(tileset.kind(), AutoTileKind::Bitmask4);
+    /// ```
+    pub fn bitmask4(sprites: [usize; 16]) -> AutoTileset {
+        AutoTileset {
+            kind: AutoTileKind::Bitmask4,
+            sprites: sprites.to_vec(),
+        }
+    }
+
+    /// Constructs a new auto tileset which uses all 8 neighbours.
+    ///
+    /// The sprites must be in the order of [`BLOB47_MASKS`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let tileset = AutoTileset::blob47([0; 47]);
+    ///
+    /// assert_eq!(tileset.kind(), AutoTileKind::Blob47);
+    /// ```
+    ///
+    /// [`BLOB47_MASKS`]: BLOB47_MASKS
+    pub fn blob47(sprites: [usize; 47]) -> AutoTileset {
+        AutoTileset {
+            kind: AutoTileKind::Blob47,
+            sprites: sprites.to_vec(),
+        }
+    }
+
+    /// Returns the kind of mask the auto tileset uses.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let tileset = AutoTileset::bitmask4([0; 16]);
+    ///
+    /// assert_eq!(tileset.kind(), AutoTileKind::Bitmask4);
+    /// ```
+    pub fn kind(&self) -> AutoTileKind {
+        self.kind
+    }
+
+    /// Returns the sprite index for a full 8-bit neighbour mask.
+    ///
+    /// The mask is reduced to fit the kind of tileset first.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let mut sprites = [0; 47];
+    /// for (n, sprite) in sprites.iter_mut().enumerate() {
+    ///     *sprite = n;
+    /// }
+    /// let tileset = AutoTileset::blob47(sprites);
+    ///
+    /// // Only north and north east are set, north east is not counted without
+    /// // east also being set.
+    /// assert_eq!(tileset.sprite(0b0000_0011), 1);
+    /// assert_eq!(tileset.sprite(0b1111_1111), 46);
+    /// ```
+    pub fn sprite(&self, mask: u8) -> usize {
+        let index = match self.kind {
+            AutoTileKind::Bitmask4 => reduce_bitmask4(mask) as usize,
+            AutoTileKind::Blob47 => {
+                let mask = reduce_blob47(mask);
+                BLOB47_MASKS
+                    .binary_search(&mask)
+                    .expect("Reduced mask is always in the blob masks.")
+            }
+        };
+        self.sprites[index]
+    }
+}
+
+/// Reduces an 8-bit neighbour mask into a 4-bit mask of the direct
+/// neighbours.
+fn reduce_bitmask4(mask: u8) -> u8 {
+    let mut reduced = 0;
+    if mask & NORTH != 0 {
+        reduced |= 0b0001;
+    }
+    if mask & EAST != 0 {
+        reduced |= 0b0010;
+    }
+    if mask & SOUTH != 0 {
+        reduced |= 0b0100;
+    }
+    if mask & WEST != 0 {
+        reduced |= 0b1000;
+    }
+    reduced
+}
+
+/// Reduces an 8-bit neighbour mask so that corners are only kept if both
+/// edges beside them are set.
+fn reduce_blob47(mask: u8) -> u8 {
+    let mut reduced = mask & (NORTH | EAST | SOUTH | WEST);
+    for &(corner, a, b) in &[
+        (NORTH_EAST, NORTH, EAST),
+        (SOUTH_EAST, SOUTH, EAST),
+        (SOUTH_WEST, SOUTH, WEST),
+        (NORTH_WEST, NORTH, WEST),
+    ] {
+        if mask & corner != 0 && mask & a != 0 && mask & b != 0 {
+            reduced |= corner;
+        }
+    }
+    reduced
+}
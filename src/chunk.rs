@@ -54,7 +54,17 @@
 //! let z_order = 1;
 //! tilemap.add_layer_with_kind(LayerKind::Sparse, 1);
 //! ```
-use crate::{entity::DirtyLayer, lib::*, mesh::ChunkMesh, tile::RawTile, tilemap::Tilemap};
+use crate::{
+    entity::DirtyLayers,
+    fog::{FogState, FogTints},
+    lib::*,
+    mesh::ChunkMesh,
+    tile::{RawTile, TileAttributes, TileFlip},
+    tilemap::Tilemap,
+};
+
+#[cfg(feature = "serialize")]
+use crate::codec::{self, CodecResult, Decoder, Encoder};
 
 /// Common methods for layers in a chunk.
 pub(crate) trait Layer: 'static {
@@ -74,7 +84,7 @@ pub(crate) trait Layer: 'static {
     fn get_tile_mut(&mut self, index: usize) -> Option<&mut RawTile>;
 
     /// Takes all the tiles in the layer and returns attributes for the renderer.
-    fn tiles_to_attributes(&self, area: usize) -> (Vec<f32>, Vec<[f32; 4]>);
+    fn tiles_to_attributes(&self, area: usize) -> TileAttributes;
 }
 
 /// A layer with dense sprite tiles.
@@ -127,7 +137,7 @@ impl Layer for DenseLayer {
         })
     }
 
-    fn tiles_to_attributes(&self, _area: usize) -> (Vec<f32>, Vec<[f32; 4]>) {
+    fn tiles_to_attributes(&self, _area: usize) -> TileAttributes {
         crate::tile::dense_tiles_to_attributes(&self.tiles)
     }
 }
@@ -163,8 +173,10 @@ impl Layer for SparseLayer {
     }
 
     fn set_raw_tile(&mut self, index: usize, tile: RawTile) {
+        // A fully transparent tile clears the tile instead of being stored.
         if tile.color.a() == 0.0 {
             self.tiles.remove(&index);
+            return;
         }
         self.tiles.insert(index, tile);
     }
@@ -177,7 +189,7 @@ impl Layer for SparseLayer {
         self.tiles.get_mut(&index)
     }
 
-    fn tiles_to_attributes(&self, area: usize) -> (Vec<f32>, Vec<[f32; 4]>) {
+    fn tiles_to_attributes(&self, area: usize) -> TileAttributes {
         crate::tile::sparse_tiles_to_attributes(area, &self.tiles)
     }
 }
@@ -192,8 +204,98 @@ impl SparseLayer {
     }
 }
 
+/// A layer with the fog of war state of every tile instead of sprites.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct FogLayer {
+    /// A mesh handle.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    mesh: Handle<Mesh>,
+    /// The tints that the states are rendered with.
+    tints: FogTints,
+    /// The fog state of every tile in the chunk.
+    states: Vec<FogState>,
+}
+
+impl Layer for FogLayer {
+    fn mesh(&self) -> &Handle<Mesh> {
+        &self.mesh
+    }
+
+    fn set_mesh(&mut self, mesh: Handle<Mesh>) {
+        self.mesh = mesh;
+    }
+
+    // Sprites are rejected by the tilemap before they reach a fog layer.
+    fn set_raw_tile(&mut self, _index: usize, _tile: RawTile) {}
+
+    fn get_tile(&self, _index: usize) -> Option<&RawTile> {
+        None
+    }
+
+    fn get_tile_mut(&mut self, _index: usize) -> Option<&mut RawTile> {
+        None
+    }
+
+    fn tiles_to_attributes(&self, _area: usize) -> TileAttributes {
+        let capacity = self.states.len() * 4;
+        // A negative index renders the tile as its color without a sprite.
+        let tile_indexes = vec![-1.0; capacity];
+        let mut tile_colors: Vec<[f32; 4]> = Vec::with_capacity(capacity);
+        for state in self.states.iter() {
+            tile_colors.extend([self.tints.tint(*state).into(); 4].iter());
+        }
+        (tile_indexes, tile_colors, vec![0.0; capacity])
+    }
+}
+
+impl FogLayer {
+    /// Constructs a new fog layer with states.
+    pub(crate) fn new(tints: FogTints, states: Vec<FogState>) -> FogLayer {
+        FogLayer {
+            mesh: Default::default(),
+            tints,
+            states,
+        }
+    }
+
+    /// Returns the state of a tile by an index.
+    pub(crate) fn get_state(&self, index: usize) -> Option<FogState> {
+        self.states.get(index).copied()
+    }
+
+    /// Sets the state of a tile by an index, returning `true` if it changed.
+    pub(crate) fn set_state(&mut self, index: usize, state: FogState) -> bool {
+        match self.states.get_mut(index) {
+            Some(old_state) if *old_state != state => {
+                *old_state = state;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Sets every visible tile that is not in the given indexes to explored,
+    /// returning `true` if any changed.
+    pub(crate) fn hide_visible(&mut self, keep: &HashSet<usize>) -> bool {
+        let mut changed = false;
+        for (index, state) in self.states.iter_mut().enumerate() {
+            if *state == FogState::Visible && !keep.contains(&index) {
+                *state = FogState::Explored;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Sets the tints that the states are rendered with.
+    pub(crate) fn set_tints(&mut self, tints: FogTints) {
+        self.tints = tints;
+    }
+}
+
 /// Specifies which kind of layer to construct, either a dense or a sparse
-/// sprite layer.
+/// sprite layer, or a fog layer.
 ///
 /// The difference between a dense and sparse layer is namely the storage kind.
 /// A dense layer uses a vector and must fully contain tiles. This is ideal for
@@ -202,6 +304,11 @@ impl SparseLayer {
 ///
 /// It is highly recommended to adhere to the above principles to get the lowest
 /// amount of byte usage.
+///
+/// A fog layer does not hold sprites at all, but the [`FogState`] of every
+/// tile which is rendered with the given tints over the layers below it.
+///
+/// [`FogState`]: crate::fog::FogState
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum LayerKind {
@@ -209,6 +316,22 @@ pub enum LayerKind {
     Dense,
     /// Specifies the tilemap to add a sparse sprite layer.
     Sparse,
+    /// Specifies the tilemap to add a fog of war layer with the tints of its
+    /// states.
+    Fog(FogTints),
+}
+
+impl LayerKind {
+    /// Returns the kind of sprite layer that suits a layer with an amount of
+    /// tiles in an area, which is dense if it is at least half filled.
+    #[cfg(any(feature = "tiled", feature = "ldtk"))]
+    pub(crate) fn for_tile_count(tiles: usize, area: usize) -> LayerKind {
+        if tiles * 2 >= area {
+            LayerKind::Dense
+        } else {
+            LayerKind::Sparse
+        }
+    }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -219,6 +342,8 @@ enum LayerKindInner {
     Dense(DenseLayer),
     /// Inner sparse layer storage.
     Sparse(SparseLayer),
+    /// Inner fog layer storage.
+    Fog(FogLayer),
 }
 
 impl AsRef<dyn Layer> for LayerKindInner {
@@ -226,6 +351,7 @@ impl AsRef<dyn Layer> for LayerKindInner {
         match self {
             LayerKindInner::Dense(s) => s,
             LayerKindInner::Sparse(s) => s,
+            LayerKindInner::Fog(s) => s,
         }
     }
 }
@@ -235,6 +361,7 @@ impl AsMut<dyn Layer> for LayerKindInner {
         match self {
             LayerKindInner::Dense(s) => s,
             LayerKindInner::Sparse(s) => s,
+            LayerKindInner::Fog(s) => s,
         }
     }
 }
@@ -248,6 +375,12 @@ pub(crate) struct SpriteLayer {
     #[cfg_attr(feature = "serde", serde(skip))]
     /// Contains an entity if the layer had been spawned.
     entity: Option<Entity>,
+    /// A map of tile indexes to the terrain of the tile, used for auto tiling.
+    #[cfg_attr(feature = "serde", serde(default))]
+    terrains: HashMap<usize, u32>,
+    /// A map of tile indexes to the ID of the animation of the tile.
+    #[cfg_attr(feature = "serde", serde(default))]
+    animations: HashMap<usize, usize>,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -257,6 +390,9 @@ pub(crate) struct SpriteLayer {
 pub struct Chunk {
     point: Point2,
     sprite_layers: Vec<Option<SpriteLayer>>,
+    /// If the chunk had been modified since it was created or loaded.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    modified: bool,
 }
 
 impl Chunk {
@@ -269,6 +405,7 @@ impl Chunk {
         let mut chunk = Chunk {
             point,
             sprite_layers: vec![None; layers.len()],
+            modified: false,
         };
         for (z_order, kind) in layers.iter().enumerate() {
             if let Some(kind) = kind {
@@ -286,7 +423,8 @@ impl Chunk {
                 let tiles = vec![
                     RawTile {
                         index: 0,
-                        color: Color::rgba(0.0, 0.0, 0.0, 0.0)
+                        color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                        flip: TileFlip::empty(),
                     };
                     dimensions.area() as usize
                 ];
@@ -294,6 +432,8 @@ impl Chunk {
                     *layer = Some(SpriteLayer {
                         inner: LayerKindInner::Dense(DenseLayer::new(tiles)),
                         entity: None,
+                        terrains: HashMap::default(),
+                        animations: HashMap::default(),
                     });
                 } // TODO: Else statement with an ERR log when released
             }
@@ -302,6 +442,19 @@ impl Chunk {
                     *layer = Some(SpriteLayer {
                         inner: LayerKindInner::Sparse(SparseLayer::new(HashMap::default())),
                         entity: None,
+                        terrains: HashMap::default(),
+                        animations: HashMap::default(),
+                    });
+                } // TODO: Else statement with an ERR log when released
+            }
+            LayerKind::Fog(tints) => {
+                let states = vec![FogState::Unexplored; dimensions.area() as usize];
+                if let Some(layer) = self.sprite_layers.get_mut(z) {
+                    *layer = Some(SpriteLayer {
+                        inner: LayerKindInner::Fog(FogLayer::new(*tints, states)),
+                        entity: None,
+                        terrains: HashMap::default(),
+                        animations: HashMap::default(),
                     });
                 } // TODO: Else statement with an ERR log when released
             }
@@ -313,6 +466,151 @@ impl Chunk {
         self.point
     }
 
+    /// Returns `true` if the chunk had been modified since it was created or
+    /// loaded.
+    ///
+    /// This is only read when chunks are stored, which requires the
+    /// `serialize` feature.
+    #[cfg_attr(not(feature = "serialize"), allow(dead_code))]
+    pub(crate) fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Sets if the chunk had been modified since it was created or loaded.
+    pub(crate) fn set_modified(&mut self, modified: bool) {
+        self.modified = modified;
+    }
+
+    /// Writes the chunk in the compact binary format.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_signed(i64::from(self.point.x));
+        encoder.write_signed(i64::from(self.point.y));
+        encoder.write_varint(self.sprite_layers.len() as u64);
+        for layer in self.sprite_layers.iter() {
+            let layer = match layer {
+                Some(layer) => layer,
+                None => {
+                    encoder.write_u8(0);
+                    continue;
+                }
+            };
+            match &layer.inner {
+                LayerKindInner::Dense(dense) => {
+                    encoder.write_u8(1);
+                    codec::encode_dense_tiles(encoder, &dense.tiles);
+                }
+                LayerKindInner::Sparse(sparse) => {
+                    encoder.write_u8(2);
+                    codec::encode_sparse_tiles(encoder, &sparse.tiles);
+                }
+                LayerKindInner::Fog(fog) => {
+                    encoder.write_u8(3);
+                    for tint in fog.tints.to_bytes().iter() {
+                        for component in tint.iter() {
+                            encoder.write_u8(*component);
+                        }
+                    }
+                    let states: Vec<u8> = fog.states.iter().map(|state| *state as u8).collect();
+                    codec::encode_runs(encoder, &states);
+                }
+            }
+            codec::encode_index_map(
+                encoder,
+                layer
+                    .terrains
+                    .iter()
+                    .map(|(index, terrain)| (*index, u64::from(*terrain))),
+            );
+            codec::encode_index_map(
+                encoder,
+                layer
+                    .animations
+                    .iter()
+                    .map(|(index, animation)| (*index, *animation as u64)),
+            );
+        }
+    }
+
+    /// Reads a chunk with the given dimensions from the compact binary format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes do not contain a valid chunk.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn decode(decoder: &mut Decoder, dimensions: Dimension2) -> CodecResult<Chunk> {
+        let x = decoder.read_signed()?;
+        let y = decoder.read_signed()?;
+        if x < i64::from(i32::MIN)
+            || x > i64::from(i32::MAX)
+            || y < i64::from(i32::MIN)
+            || y > i64::from(i32::MAX)
+        {
+            return Err(codec::invalid_data("chunk point out of bounds"));
+        }
+        let area = codec::chunk_area(dimensions)?;
+
+        let layer_count = decoder.read_usize()?;
+        let mut sprite_layers = Vec::new();
+        for _ in 0..layer_count {
+            let inner = match decoder.read_u8()? {
+                0 => {
+                    sprite_layers.push(None);
+                    continue;
+                }
+                1 => LayerKindInner::Dense(DenseLayer::new(codec::decode_dense_tiles(
+                    decoder, area,
+                )?)),
+                2 => LayerKindInner::Sparse(SparseLayer::new(codec::decode_sparse_tiles(
+                    decoder, area,
+                )?)),
+                3 => {
+                    let mut tints = [[0; 4]; 3];
+                    for tint in tints.iter_mut() {
+                        for component in tint.iter_mut() {
+                            *component = decoder.read_u8()?;
+                        }
+                    }
+                    let states = codec::decode_runs(decoder, area)?
+                        .into_iter()
+                        .map(|state| {
+                            FogState::from_u8(state)
+                                .ok_or_else(|| codec::invalid_data("unknown fog state"))
+                        })
+                        .collect::<CodecResult<Vec<FogState>>>()?;
+                    LayerKindInner::Fog(FogLayer::new(FogTints::from_bytes(tints), states))
+                }
+                _ => return Err(codec::invalid_data("unknown layer kind")),
+            };
+            let mut terrains = HashMap::default();
+            for (index, terrain) in codec::decode_index_map(decoder)?.into_iter() {
+                if terrain > u64::from(u32::MAX) {
+                    return Err(codec::invalid_data("terrain out of bounds"));
+                }
+                terrains.insert(index, terrain as u32);
+            }
+            let mut animations = HashMap::default();
+            for (index, animation) in codec::decode_index_map(decoder)?.into_iter() {
+                if animation > usize::MAX as u64 {
+                    return Err(codec::invalid_data("animation out of bounds"));
+                }
+                animations.insert(index, animation as usize);
+            }
+            sprite_layers.push(Some(SpriteLayer {
+                inner,
+                entity: None,
+                terrains,
+                animations,
+            }));
+        }
+
+        Ok(Chunk {
+            point: Point2::new(x as i32, y as i32),
+            sprite_layers,
+            modified: false,
+        })
+    }
+
     /// Moves a layer from a z layer to another.
     pub(crate) fn move_layer(&mut self, from_z: usize, to_z: usize) {
         // TODO: rename to swap and include it in the greater api
@@ -328,6 +626,13 @@ impl Chunk {
         self.sprite_layers.get_mut(z_order).take();
     }
 
+    /// Gets the mesh of a chunk layer, if it exists.
+    pub(crate) fn get_mesh(&self, z_order: usize) -> Option<&Handle<Mesh>> {
+        self.sprite_layers
+            .get(z_order)
+            .and_then(|layer| layer.as_ref().map(|layer| layer.inner.as_ref().mesh()))
+    }
+
     /// Sets the mesh for the chunk layer to use.
     pub(crate) fn set_mesh(&mut self, z_order: usize, mesh: Handle<Mesh>) {
         if let Some(layer) = self.sprite_layers.get_mut(z_order) {
@@ -339,6 +644,7 @@ impl Chunk {
 
     /// Sets a single raw tile to be added to a z layer and index.
     pub(crate) fn set_raw_tile(&mut self, z_order: usize, index: usize, raw_tile: RawTile) {
+        self.modified = true;
         if let Some(layer) = self.sprite_layers.get_mut(z_order) {
             if let Some(layer) = layer.as_mut() {
                 layer.inner.as_mut().set_raw_tile(index, raw_tile);
@@ -350,6 +656,104 @@ impl Chunk {
         }
     }
 
+    /// Sets or removes the terrain of a tile at a z layer and index, returning
+    /// the previous terrain if there was one.
+    pub(crate) fn set_terrain(
+        &mut self,
+        z_order: usize,
+        index: usize,
+        terrain: Option<u32>,
+    ) -> Option<u32> {
+        let layer = self.sprite_layers.get_mut(z_order)?.as_mut()?;
+        match terrain {
+            Some(terrain) => layer.terrains.insert(index, terrain),
+            None => layer.terrains.remove(&index),
+        }
+    }
+
+    /// Gets the terrain of a tile at a z layer and index, if it has one.
+    pub(crate) fn get_terrain(&self, z_order: usize, index: usize) -> Option<u32> {
+        self.sprite_layers
+            .get(z_order)
+            .and_then(|layer| layer.as_ref())
+            .and_then(|layer| layer.terrains.get(&index).cloned())
+    }
+
+    /// Gets the animation ID of a tile at a z layer and index, if it has one.
+    pub(crate) fn get_animation(&self, z_order: usize, index: usize) -> Option<usize> {
+        self.sprite_layers
+            .get(z_order)
+            .and_then(|layer| layer.as_ref())
+            .and_then(|layer| layer.animations.get(&index).cloned())
+    }
+
+    /// Sets or removes the animation of a tile at a z layer and index.
+    pub(crate) fn set_animation(&mut self, z_order: usize, index: usize, animation: Option<usize>) {
+        if let Some(layer) = self.sprite_layers.get_mut(z_order) {
+            if let Some(layer) = layer.as_mut() {
+                match animation {
+                    Some(animation) => layer.animations.insert(index, animation),
+                    None => layer.animations.remove(&index),
+                };
+            } // TODO: Bevy log error when implemented
+        } // TODO: Bevy log error when implemented
+    }
+
+    /// Sets the sprite of every animated tile to the sprites given by
+    /// animation ID and returns the z layers that had changed.
+    pub(crate) fn update_animations(&mut self, sprites: &HashMap<usize, usize>) -> Vec<usize> {
+        let mut changed_layers = Vec::new();
+        for (z_order, layer) in self.sprite_layers.iter_mut().enumerate() {
+            let layer = match layer {
+                Some(layer) => layer,
+                None => continue,
+            };
+            let mut changed = false;
+            for (index, animation) in layer.animations.iter() {
+                if let Some(sprite_index) = sprites.get(animation) {
+                    if let Some(tile) = layer.inner.as_mut().get_tile_mut(*index) {
+                        tile.index = *sprite_index;
+                        changed = true;
+                    }
+                }
+            }
+            if changed {
+                changed_layers.push(z_order);
+            }
+        }
+        changed_layers
+    }
+
+    /// Replaces every layer which differs between an old and a new version of
+    /// the chunk with the layer of the new version, keeping the entities and
+    /// meshes. Returns the z layers that had been replaced.
+    ///
+    /// Layers that did not change between the versions are left untouched, so
+    /// that any changes made to them at runtime are kept.
+    #[cfg(feature = "loader")]
+    pub(crate) fn reload_layers(&mut self, old: Option<&Chunk>, new: &Chunk) -> Vec<usize> {
+        let mut changed_layers = Vec::new();
+        for (z_order, new_layer) in new.sprite_layers.iter().enumerate() {
+            let old_layer = old.and_then(|old| old.sprite_layers.get(z_order));
+            if old_layer == Some(new_layer) {
+                continue;
+            }
+            let layer = match self.sprite_layers.get_mut(z_order) {
+                Some(Some(layer)) => layer,
+                _ => continue,
+            };
+            if let Some(new_layer) = new_layer {
+                let mut inner = new_layer.inner.clone();
+                inner.as_mut().set_mesh(layer.inner.as_ref().mesh().clone());
+                layer.inner = inner;
+                layer.terrains = new_layer.terrains.clone();
+                layer.animations = new_layer.animations.clone();
+                changed_layers.push(z_order);
+            }
+        }
+        changed_layers
+    }
+
     /// Adds an entity to a z layer, always when it is spawned.
     pub(crate) fn add_entity(&mut self, z_order: usize, entity: Entity) {
         if let Some(layer) = self.sprite_layers.get_mut(z_order) {
@@ -367,11 +771,11 @@ impl Chunk {
     }
 
     /// Gets all the layers entities for use with bulk despawning.
-    pub(crate) fn get_entities(&self) -> Vec<Entity> {
+    pub(crate) fn remove_entities(&mut self) -> Vec<Entity> {
         let mut entities = Vec::new();
-        for sprite_layer in &self.sprite_layers {
+        for sprite_layer in self.sprite_layers.iter_mut() {
             if let Some(layer) = sprite_layer {
-                if let Some(entity) = layer.entity {
+                if let Some(entity) = layer.entity.take() {
                     entities.push(entity);
                 }
             }
@@ -388,13 +792,45 @@ impl Chunk {
         })
     }
 
-    /// Gets a mutable reference to a tile from a provided z order and index.
-    pub(crate) fn get_tile_mut(&mut self, z_order: usize, index: usize) -> Option<&mut RawTile> {
-        self.sprite_layers.get_mut(z_order).and_then(|layer| {
-            layer
-                .as_mut()
-                .and_then(|layer| layer.inner.as_mut().get_tile_mut(index))
-        })
+    /// Gets a reference to the fog layer at a z order, if it is one.
+    pub(crate) fn get_fog_layer(&self, z_order: usize) -> Option<&FogLayer> {
+        match self.sprite_layers.get(z_order) {
+            Some(Some(SpriteLayer {
+                inner: LayerKindInner::Fog(fog),
+                ..
+            })) => Some(fog),
+            _ => None,
+        }
+    }
+
+    /// Gets a mutable reference to the fog layer at a z order, if it is one.
+    pub(crate) fn get_fog_layer_mut(&mut self, z_order: usize) -> Option<&mut FogLayer> {
+        match self.sprite_layers.get_mut(z_order) {
+            Some(Some(SpriteLayer {
+                inner: LayerKindInner::Fog(fog),
+                ..
+            })) => Some(fog),
+            _ => None,
+        }
+    }
+
+    /// Gets a mutable reference to a tile from a provided z order and index,
+    /// along with the modified flag of the chunk which must be set if the tile
+    /// is changed.
+    pub(crate) fn get_tile_mut(
+        &mut self,
+        z_order: usize,
+        index: usize,
+    ) -> Option<(&mut RawTile, &mut bool)> {
+        let modified = &mut self.modified;
+        self.sprite_layers
+            .get_mut(z_order)
+            .and_then(|layer| {
+                layer
+                    .as_mut()
+                    .and_then(|layer| layer.inner.as_mut().get_tile_mut(index))
+            })
+            .map(|tile| (tile, modified))
     }
 
     /// At the given z layer, changes the tiles into attributes for use with
@@ -405,7 +841,7 @@ impl Chunk {
         &self,
         z: usize,
         dimensions: Dimension2,
-    ) -> Option<(Vec<f32>, Vec<[f32; 4]>)> {
+    ) -> Option<TileAttributes> {
         let area = dimensions.area() as usize;
         self.sprite_layers.get(z).and_then(|o| {
             o.as_ref()
@@ -420,20 +856,25 @@ pub(crate) fn chunk_update_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     map_query: Query<&Tilemap>,
-    chunk_query: Query<(Entity, &Parent, &Point2, &Handle<Mesh>, &DirtyLayer)>,
+    chunk_query: Query<(Entity, &Parent, &Point2, &DirtyLayers)>,
 ) {
-    for (entity, parent, point, mesh_handle, dirty_layer) in chunk_query.iter() {
+    for (entity, parent, point, dirty_layers) in chunk_query.iter() {
         let tilemap = map_query.get(**parent).expect("`Tilemap` missing");
         let chunk = tilemap.get_chunk(point).expect("`Chunk` is missing");
-        let mesh = meshes.get_mut(mesh_handle).expect("`Mesh` is missing");
 
-        let (indexes, colors) = chunk
-            .tiles_to_renderer_parts(dirty_layer.0, tilemap.chunk_dimensions())
-            .expect("Tiles missing.");
+        for layer in dirty_layers.0.iter() {
+            let mesh_handle = chunk.get_mesh(*layer).expect("`Mesh` handle is missing");
+            let mesh = meshes.get_mut(mesh_handle).expect("`Mesh` is missing");
+
+            let (indexes, colors, flips) = chunk
+                .tiles_to_renderer_parts(*layer, tilemap.chunk_dimensions())
+                .expect("Tiles missing.");
 
-        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, indexes.into());
-        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, colors.into());
+            mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, indexes.into());
+            mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, colors.into());
+            mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_FLIP, flips.into());
+        }
 
-        commands.remove_one::<DirtyLayer>(entity);
+        commands.remove_one::<DirtyLayers>(entity);
     }
 }
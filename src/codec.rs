@@ -0,0 +1,698 @@
+//! A compact binary format for tilemaps and chunks.
+//!
+//! Serializing a tilemap with serde writes every tile of a dense layer with its
+//! full color and index, which makes the output grow with the area of the
+//! chunks no matter how many tiles are actually in use. The compact format
+//! used by [`Tilemap::to_bytes`] instead stores:
+//!
+//! - dense layers as a palette of the distinct tiles in the layer, followed by
+//!   runs of tiles with the same palette entry.
+//! - sparse layers as a palette followed by the tiles sorted by their index,
+//!   with every index stored as the difference to the one before.
+//! - every integer as a variable length integer, so that small numbers only
+//!   take up a single byte.
+//!
+//! The format starts with a magic number and a version, which is checked when
+//! decoding. Decoding never loses information, the decoded tilemap is exactly
+//! the same as the one that was encoded.
+//!
+//! The versions of the format are:
+//!
+//! 1. The first version of the format.
+//! 2. The flip flags of every tile in the palettes. Tiles of version 1 are
+//! decoded without flip flags.
+//!
+//! The chunks written by a [`ChunkStore`] use the same format.
+//!
+//! This module requires the **"serialize"** feature.
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::builder()
+//!     .texture_atlas(texture_atlas_handle.clone())
+//!     .auto_chunk()
+//!     .finish()
+//!     .unwrap();
+//! tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+//!
+//! let bytes = tilemap.to_bytes().unwrap();
+//!
+//! let mut decoded = Tilemap::from_bytes(&bytes).unwrap();
+//! decoded.set_texture_atlas(texture_atlas_handle);
+//!
+//! assert_eq!(decoded.get_tile((1, 1), 0).unwrap().index, 3);
+//! ```
+//!
+//! [`Tilemap::to_bytes`]: crate::tilemap::Tilemap::to_bytes
+//! [`ChunkStore`]: crate::store::ChunkStore
+
+use crate::{
+    lib::*,
+    tile::{RawTile, TileFlip},
+};
+
+/// The magic number that the compact format starts with.
+const MAGIC: [u8; 4] = *b"BTMC";
+
+/// The current version of the compact format.
+pub const CODEC_VERSION: u8 = 2;
+
+/// The largest amount of tiles in a chunk that is decoded, which keeps bytes
+/// that were crafted or corrupted from allocating huge chunks.
+const MAX_CHUNK_AREA: u32 = 1 << 20;
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+/// The kinds of errors that can occur.
+enum ErrorKind {
+    /// The bytes ended before decoding was finished.
+    UnexpectedEnd,
+    /// The bytes do not start with the magic number of the format.
+    InvalidMagic,
+    /// The version of the format is not supported.
+    UnsupportedVersion(u8),
+    /// The bytes do not contain valid data.
+    InvalidData(&'static str),
+    /// The tilemap settings could not be (de)serialized.
+    Settings(String),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use ErrorKind::*;
+        match self {
+            UnexpectedEnd => write!(f, "unexpected end of the bytes"),
+            InvalidMagic => write!(f, "the bytes are not in the compact tilemap format"),
+            UnsupportedVersion(version) => write!(
+                f,
+                "version {} of the compact format is not supported, the latest is {}",
+                version, CODEC_VERSION
+            ),
+            InvalidData(what) => write!(f, "invalid data: {}", what),
+            Settings(err) => write!(f, "failed to (de)serialize tilemap settings: {}", err),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+/// The error type for encoding and decoding the compact format.
+pub struct CodecError(Box<ErrorKind>);
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for CodecError {}
+
+impl From<ErrorKind> for CodecError {
+    fn from(kind: ErrorKind) -> CodecError {
+        CodecError(Box::new(kind))
+    }
+}
+
+impl From<bincode::Error> for CodecError {
+    fn from(err: bincode::Error) -> CodecError {
+        CodecError(Box::new(ErrorKind::Settings(err.to_string())))
+    }
+}
+
+/// A result of encoding or decoding.
+pub type CodecResult<T> = Result<T, CodecError>;
+
+/// Writes values into bytes of the compact format.
+#[derive(Default)]
+pub(crate) struct Encoder {
+    /// The bytes written so far.
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    /// Constructs a new encoder which starts with the magic number and the
+    /// version.
+    pub(crate) fn new() -> Encoder {
+        let mut encoder = Encoder::default();
+        encoder.bytes.extend(MAGIC.iter());
+        encoder.write_u8(CODEC_VERSION);
+        encoder
+    }
+
+    /// Returns the written bytes.
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Writes a single byte.
+    pub(crate) fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    /// Writes an unsigned integer as a variable length integer.
+    pub(crate) fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    /// Writes a signed integer as a zigzag encoded variable length integer.
+    pub(crate) fn write_signed(&mut self, value: i64) {
+        self.write_varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    /// Writes a length prefixed slice of bytes.
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_varint(bytes.len() as u64);
+        self.bytes.extend(bytes.iter());
+    }
+
+    /// Writes a color as its exact components.
+    fn write_color(&mut self, color: Color) {
+        let components: [f32; 4] = color.into();
+        for component in components.iter() {
+            self.bytes.extend(component.to_bits().to_le_bytes().iter());
+        }
+    }
+
+    /// Writes a palette of tiles.
+    fn write_palette(&mut self, palette: &[RawTile]) {
+        self.write_varint(palette.len() as u64);
+        for tile in palette.iter() {
+            self.write_varint(tile.index as u64);
+            self.write_color(tile.color);
+            self.write_u8(tile.flip.bits());
+        }
+    }
+}
+
+/// Reads values from bytes of the compact format.
+pub(crate) struct Decoder<'a> {
+    /// The bytes that are left to read.
+    bytes: &'a [u8],
+    /// The version of the format the bytes are in.
+    version: u8,
+}
+
+impl<'a> Decoder<'a> {
+    /// Returns `true` if the bytes start with the magic number of the compact
+    /// format, so that bytes in an older format can be told apart.
+    pub(crate) fn is_compact(bytes: &[u8]) -> bool {
+        bytes.starts_with(&MAGIC)
+    }
+
+    /// Constructs a new decoder, checking the magic number and the version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not in the compact format or the
+    /// version is not supported.
+    pub(crate) fn new(bytes: &'a [u8]) -> CodecResult<Decoder<'a>> {
+        if bytes.len() < MAGIC.len() || bytes[..MAGIC.len()] != MAGIC {
+            return Err(ErrorKind::InvalidMagic.into());
+        }
+        let mut decoder = Decoder {
+            bytes: &bytes[MAGIC.len()..],
+            version: 0,
+        };
+        let version = decoder.read_u8()?;
+        if version == 0 || version > CODEC_VERSION {
+            return Err(ErrorKind::UnsupportedVersion(version).into());
+        }
+        decoder.version = version;
+        Ok(decoder)
+    }
+
+    /// Checks that all the bytes had been read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are bytes left.
+    pub(crate) fn finish(self) -> CodecResult<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorKind::InvalidData("trailing bytes").into())
+        }
+    }
+
+    /// Reads a single byte.
+    pub(crate) fn read_u8(&mut self) -> CodecResult<u8> {
+        let (first, rest) = self.bytes.split_first().ok_or(ErrorKind::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    /// Reads a variable length unsigned integer.
+    pub(crate) fn read_varint(&mut self) -> CodecResult<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift == 63 && byte > 1 {
+                return Err(ErrorKind::InvalidData("integer overflow").into());
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads a variable length unsigned integer that must fit into `usize`.
+    pub(crate) fn read_usize(&mut self) -> CodecResult<usize> {
+        let value = self.read_varint()?;
+        if value > usize::MAX as u64 {
+            return Err(ErrorKind::InvalidData("integer overflow").into());
+        }
+        Ok(value as usize)
+    }
+
+    /// Reads a zigzag encoded variable length signed integer.
+    pub(crate) fn read_signed(&mut self) -> CodecResult<i64> {
+        let value = self.read_varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    /// Reads a length prefixed slice of bytes.
+    pub(crate) fn read_bytes(&mut self) -> CodecResult<&'a [u8]> {
+        let len = self.read_usize()?;
+        if len > self.bytes.len() {
+            return Err(ErrorKind::UnexpectedEnd.into());
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    /// Reads a color from its exact components.
+    fn read_color(&mut self) -> CodecResult<Color> {
+        let mut components = [0.0; 4];
+        for component in components.iter_mut() {
+            if self.bytes.len() < 4 {
+                return Err(ErrorKind::UnexpectedEnd.into());
+            }
+            let (bits, rest) = self.bytes.split_at(4);
+            *component = f32::from_bits(u32::from_le_bytes([bits[0], bits[1], bits[2], bits[3]]));
+            self.bytes = rest;
+        }
+        Ok(Color::rgba(
+            components[0],
+            components[1],
+            components[2],
+            components[3],
+        ))
+    }
+
+    /// Reads a palette of tiles.
+    fn read_palette(&mut self) -> CodecResult<Vec<RawTile>> {
+        let len = self.read_usize()?;
+        let mut palette = Vec::new();
+        for _ in 0..len {
+            let index = self.read_usize()?;
+            let color = self.read_color()?;
+            let flip = if self.version >= 2 {
+                TileFlip::from_bits(self.read_u8()?)
+                    .ok_or(ErrorKind::InvalidData("unknown flip flags"))?
+            } else {
+                TileFlip::empty()
+            };
+            palette.push(RawTile { index, color, flip });
+        }
+        Ok(palette)
+    }
+
+    /// Reads an index into a palette and returns the tile of it.
+    fn read_palette_tile(&mut self, palette: &[RawTile]) -> CodecResult<RawTile> {
+        let index = self.read_usize()?;
+        palette
+            .get(index)
+            .copied()
+            .ok_or_else(|| ErrorKind::InvalidData("palette index out of bounds").into())
+    }
+}
+
+/// Builds palettes of distinct tiles.
+#[derive(Default)]
+struct PaletteBuilder {
+    /// The distinct tiles in order of first appearance.
+    palette: Vec<RawTile>,
+    /// The palette index of every tile, by its sprite index, color bits and
+    /// flip flags.
+    indices: HashMap<(usize, [u32; 4], u8), usize>,
+}
+
+impl PaletteBuilder {
+    /// Returns the palette index of a tile, adding it to the palette if it is
+    /// new.
+    fn index_of(&mut self, tile: &RawTile) -> usize {
+        let components: [f32; 4] = tile.color.into();
+        let key = (
+            tile.index,
+            [
+                components[0].to_bits(),
+                components[1].to_bits(),
+                components[2].to_bits(),
+                components[3].to_bits(),
+            ],
+            tile.flip.bits(),
+        );
+        let palette = &mut self.palette;
+        *self.indices.entry(key).or_insert_with(|| {
+            palette.push(*tile);
+            palette.len() - 1
+        })
+    }
+}
+
+/// Encodes the tiles of a dense layer as a palette followed by runs of the
+/// same tile.
+pub(crate) fn encode_dense_tiles(encoder: &mut Encoder, tiles: &[RawTile]) {
+    let mut builder = PaletteBuilder::default();
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for tile in tiles.iter() {
+        let index = builder.index_of(tile);
+        match runs.last_mut() {
+            Some((run_index, len)) if *run_index == index => *len += 1,
+            _ => runs.push((index, 1)),
+        }
+    }
+
+    encoder.write_palette(&builder.palette);
+    encoder.write_varint(runs.len() as u64);
+    for (index, len) in runs.into_iter() {
+        encoder.write_varint(len as u64);
+        encoder.write_varint(index as u64);
+    }
+}
+
+/// Decodes the tiles of a dense layer with an exact amount of tiles.
+///
+/// # Errors
+///
+/// Returns an error if the runs do not add up to the amount of tiles.
+pub(crate) fn decode_dense_tiles(decoder: &mut Decoder, len: usize) -> CodecResult<Vec<RawTile>> {
+    let palette = decoder.read_palette()?;
+    let run_count = decoder.read_usize()?;
+    let mut tiles = Vec::new();
+    for _ in 0..run_count {
+        let run_len = decoder.read_usize()?;
+        let tile = decoder.read_palette_tile(&palette)?;
+        if run_len > len - tiles.len() {
+            return Err(ErrorKind::InvalidData("too many dense tiles").into());
+        }
+        tiles.extend((0..run_len).map(|_| tile));
+    }
+    if tiles.len() != len {
+        return Err(ErrorKind::InvalidData("too few dense tiles").into());
+    }
+    Ok(tiles)
+}
+
+/// Encodes bytes as runs of the same byte.
+pub(crate) fn encode_runs(encoder: &mut Encoder, bytes: &[u8]) {
+    let mut runs: Vec<(u8, usize)> = Vec::new();
+    for byte in bytes.iter() {
+        match runs.last_mut() {
+            Some((run_byte, len)) if run_byte == byte => *len += 1,
+            _ => runs.push((*byte, 1)),
+        }
+    }
+
+    encoder.write_varint(runs.len() as u64);
+    for (byte, len) in runs.into_iter() {
+        encoder.write_varint(len as u64);
+        encoder.write_u8(byte);
+    }
+}
+
+/// Decodes runs of the same byte with an exact amount of bytes.
+///
+/// # Errors
+///
+/// Returns an error if the runs do not add up to the amount of bytes.
+pub(crate) fn decode_runs(decoder: &mut Decoder, len: usize) -> CodecResult<Vec<u8>> {
+    let run_count = decoder.read_usize()?;
+    let mut bytes = Vec::new();
+    for _ in 0..run_count {
+        let run_len = decoder.read_usize()?;
+        let byte = decoder.read_u8()?;
+        if run_len > len - bytes.len() {
+            return Err(ErrorKind::InvalidData("too many runs").into());
+        }
+        bytes.extend((0..run_len).map(|_| byte));
+    }
+    if bytes.len() != len {
+        return Err(ErrorKind::InvalidData("too few runs").into());
+    }
+    Ok(bytes)
+}
+
+/// Encodes the tiles of a sparse layer as a palette followed by the tiles in
+/// order of their index, each index stored as the difference to the previous.
+pub(crate) fn encode_sparse_tiles(encoder: &mut Encoder, tiles: &HashMap<usize, RawTile>) {
+    let mut sorted: Vec<(&usize, &RawTile)> = tiles.iter().collect();
+    sorted.sort_unstable_by_key(|(index, _)| **index);
+
+    let mut builder = PaletteBuilder::default();
+    let palette_indices: Vec<usize> = sorted
+        .iter()
+        .map(|(_, tile)| builder.index_of(tile))
+        .collect();
+
+    encoder.write_palette(&builder.palette);
+    encoder.write_varint(sorted.len() as u64);
+    let mut previous = 0;
+    for ((index, _), palette_index) in sorted.into_iter().zip(palette_indices.into_iter()) {
+        encoder.write_varint((*index - previous) as u64);
+        encoder.write_varint(palette_index as u64);
+        previous = *index;
+    }
+}
+
+/// Decodes the tiles of a sparse layer, every index must be lower than `len`.
+///
+/// # Errors
+///
+/// Returns an error if an index is out of bounds.
+pub(crate) fn decode_sparse_tiles(
+    decoder: &mut Decoder,
+    len: usize,
+) -> CodecResult<HashMap<usize, RawTile>> {
+    let palette = decoder.read_palette()?;
+    let count = decoder.read_usize()?;
+    let mut tiles = HashMap::default();
+    let mut index = 0;
+    for n in 0..count {
+        let delta = decoder.read_usize()?;
+        // Every index after the first must be higher than the previous one.
+        if n > 0 && delta == 0 {
+            return Err(ErrorKind::InvalidData("duplicate sparse tile").into());
+        }
+        index = match index.checked_add(delta) {
+            Some(index) if index < len => index,
+            _ => return Err(ErrorKind::InvalidData("sparse tile out of bounds").into()),
+        };
+        let tile = decoder.read_palette_tile(&palette)?;
+        tiles.insert(index, tile);
+    }
+    Ok(tiles)
+}
+
+/// Encodes a map of tile indexes to values, in order of the indexes which are
+/// each stored as the difference to the previous.
+pub(crate) fn encode_index_map<I>(encoder: &mut Encoder, entries: I)
+where
+    I: IntoIterator<Item = (usize, u64)>,
+{
+    let mut sorted: Vec<(usize, u64)> = entries.into_iter().collect();
+    sorted.sort_unstable_by_key(|(index, _)| *index);
+
+    encoder.write_varint(sorted.len() as u64);
+    let mut previous = 0;
+    for (index, value) in sorted.into_iter() {
+        encoder.write_varint((index - previous) as u64);
+        encoder.write_varint(value);
+        previous = index;
+    }
+}
+
+/// Decodes a map of tile indexes to values.
+///
+/// # Errors
+///
+/// Returns an error if an index overflows.
+pub(crate) fn decode_index_map(decoder: &mut Decoder) -> CodecResult<Vec<(usize, u64)>> {
+    let count = decoder.read_usize()?;
+    let mut entries = Vec::new();
+    let mut index: usize = 0;
+    for _ in 0..count {
+        let delta = decoder.read_usize()?;
+        index = index
+            .checked_add(delta)
+            .ok_or(ErrorKind::InvalidData("index overflow"))?;
+        entries.push((index, decoder.read_varint()?));
+    }
+    Ok(entries)
+}
+
+/// Returns the amount of tiles in a chunk of the dimensions that were decoded.
+///
+/// # Errors
+///
+/// Returns an error if the chunk is empty or has more than
+/// [`MAX_CHUNK_AREA`] tiles.
+pub(crate) fn chunk_area(dimensions: Dimension2) -> CodecResult<usize> {
+    match dimensions.width.checked_mul(dimensions.height) {
+        Some(area) if area > 0 && area <= MAX_CHUNK_AREA => Ok(area as usize),
+        _ => Err(ErrorKind::InvalidData("chunk dimensions out of bounds").into()),
+    }
+}
+
+/// Returns an error for invalid data.
+pub(crate) fn invalid_data(what: &'static str) -> CodecError {
+    ErrorKind::InvalidData(what).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(index: usize, alpha: f32) -> RawTile {
+        RawTile {
+            index,
+            color: Color::rgba(1.0, 0.5, 0.25, alpha),
+            flip: TileFlip::empty(),
+        }
+    }
+
+    #[test]
+    fn varints_round_trip() {
+        let unsigned = [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX];
+        let signed = [0, 1, -1, 63, -64, i64::from(i32::MIN), i64::MAX, i64::MIN];
+        let mut encoder = Encoder::new();
+        for value in unsigned.iter() {
+            encoder.write_varint(*value);
+        }
+        for value in signed.iter() {
+            encoder.write_signed(*value);
+        }
+
+        let bytes = encoder.finish();
+        let mut decoder = Decoder::new(&bytes).unwrap();
+        for value in unsigned.iter() {
+            assert_eq!(decoder.read_varint().unwrap(), *value);
+        }
+        for value in signed.iter() {
+            assert_eq!(decoder.read_signed().unwrap(), *value);
+        }
+        decoder.finish().unwrap();
+    }
+
+    #[test]
+    fn dense_tiles_round_trip() {
+        let mut tiles = vec![tile(0, 0.0); 1024];
+        tiles[10] = tile(3, 1.0);
+        tiles[11] = tile(3, 1.0);
+        tiles[500] = tile(7, 0.5);
+        tiles[501] = RawTile {
+            flip: TileFlip::ROTATE_90,
+            ..tile(7, 0.5)
+        };
+        let mut encoder = Encoder::new();
+        encode_dense_tiles(&mut encoder, &tiles);
+
+        let bytes = encoder.finish();
+        // A handful of runs instead of 1024 full tiles.
+        assert!(bytes.len() < 100);
+        let mut decoder = Decoder::new(&bytes).unwrap();
+        assert_eq!(decode_dense_tiles(&mut decoder, 1024).unwrap(), tiles);
+        decoder.finish().unwrap();
+    }
+
+    #[test]
+    fn dense_tiles_with_wrong_length() {
+        let tiles = vec![tile(1, 1.0); 16];
+        let mut encoder = Encoder::new();
+        encode_dense_tiles(&mut encoder, &tiles);
+
+        let bytes = encoder.finish();
+        assert!(decode_dense_tiles(&mut Decoder::new(&bytes).unwrap(), 15).is_err());
+        assert!(decode_dense_tiles(&mut Decoder::new(&bytes).unwrap(), 17).is_err());
+    }
+
+    #[test]
+    fn sparse_tiles_round_trip() {
+        let mut tiles = HashMap::default();
+        tiles.insert(4, tile(1, 1.0));
+        tiles.insert(1000, tile(2, 1.0));
+        tiles.insert(1001, tile(1, 1.0));
+        let mut encoder = Encoder::new();
+        encode_sparse_tiles(&mut encoder, &tiles);
+
+        let bytes = encoder.finish();
+        let mut decoder = Decoder::new(&bytes).unwrap();
+        assert_eq!(decode_sparse_tiles(&mut decoder, 1024).unwrap(), tiles);
+        decoder.finish().unwrap();
+
+        let mut decoder = Decoder::new(&bytes).unwrap();
+        assert!(decode_sparse_tiles(&mut decoder, 1001).is_err());
+    }
+
+    #[test]
+    fn index_map_round_trip() {
+        let entries = vec![(9, 1), (0, 5), (300, u64::from(u32::MAX))];
+        let mut encoder = Encoder::new();
+        encode_index_map(&mut encoder, entries.clone());
+
+        let bytes = encoder.finish();
+        let mut decoder = Decoder::new(&bytes).unwrap();
+        let mut decoded = decode_index_map(&mut decoder).unwrap();
+        decoded.sort_unstable();
+        let mut expected = entries;
+        expected.sort_unstable();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn header_is_checked() {
+        assert!(Decoder::new(b"").is_err());
+        assert!(Decoder::new(b"nope").is_err());
+
+        let bytes = [MAGIC[0], MAGIC[1], MAGIC[2], MAGIC[3], CODEC_VERSION + 1];
+        let err = Decoder::new(&bytes).err().unwrap();
+        assert_eq!(
+            err,
+            CodecError::from(ErrorKind::UnsupportedVersion(CODEC_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn version_1_palettes_have_no_flips() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([1, 1, 5].iter());
+        for component in [1.0f32, 1.0, 1.0, 1.0].iter() {
+            bytes.extend(component.to_bits().to_le_bytes().iter());
+        }
+
+        let mut decoder = Decoder::new(&bytes).unwrap();
+        let palette = decoder.read_palette().unwrap();
+        assert_eq!(
+            palette,
+            vec![RawTile {
+                index: 5,
+                color: Color::WHITE,
+                flip: TileFlip::empty(),
+            }]
+        );
+        decoder.finish().unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{lib::*, render::CHUNK_SQUARE_PIPELINE, Tilemap};
 
-/// A component that is used as a flag for dirty chunks that need updating.
-pub(crate) struct DirtyLayer(pub(crate) usize);
+/// A component that is used as a flag for dirty chunks that need updating,
+/// containing every z layer that had been modified.
+pub(crate) struct DirtyLayers(pub(crate) HashSet<usize>);
 
 /// A component bundle for `Chunk` entities.
 #[derive(Bundle)]
@@ -0,0 +1,177 @@
+//! Fog of war over the tiles of a tilemap.
+//!
+//! A fog layer is added with [`LayerKind::Fog`] and keeps a [`FogState`] for
+//! every tile instead of a sprite, which takes a single byte per tile. Every
+//! state is rendered as a solid tint over the layers below it, as set by the
+//! [`FogTints`] of the layer. By default unexplored tiles are black, explored
+//! tiles are darkened and visible tiles are clear.
+//!
+//! The states are changed in bulk with [`Tilemap::set_fog_states`] or
+//! [`Tilemap::update_fog`], which only re-renders the chunks that changed.
+//! The set of points returned by [`field_of_view`] can be given directly to
+//! [`Tilemap::update_fog`].
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::fov::field_of_view;
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let fog = 3;
+//! let mut tilemap = Tilemap::builder()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .add_layer(LayerKind::Dense, 0)
+//!     .add_layer(LayerKind::Fog(FogTints::default()), fog)
+//!     .finish()
+//!     .unwrap();
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! let visible = field_of_view(&tilemap, (0, 0), 3, |_, _| false);
+//! tilemap.update_fog(fog, visible).unwrap();
+//! assert_eq!(tilemap.get_fog((3, 0), fog), Some(FogState::Visible));
+//!
+//! // Once the origin moves, what is left behind stays explored.
+//! let visible = field_of_view(&tilemap, (5, 0), 3, |_, _| false);
+//! tilemap.update_fog(fog, visible).unwrap();
+//! assert_eq!(tilemap.get_fog((0, 0), fog), Some(FogState::Explored));
+//! assert_eq!(tilemap.get_fog((3, 0), fog), Some(FogState::Visible));
+//! assert_eq!(tilemap.get_fog((-5, 0), fog), Some(FogState::Unexplored));
+//! ```
+//!
+//! [`LayerKind::Fog`]: crate::chunk::LayerKind::Fog
+//! [`FogState`]: FogState
+//! [`FogTints`]: FogTints
+//! [`Tilemap::set_fog_states`]: crate::tilemap::Tilemap::set_fog_states
+//! [`Tilemap::update_fog`]: crate::tilemap::Tilemap::update_fog
+//! [`field_of_view`]: crate::fov::field_of_view
+
+use crate::lib::*;
+
+/// The state of the fog of war over a tile.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[repr(u8)]
+pub enum FogState {
+    /// The tile has never been seen.
+    Unexplored = 0,
+    /// The tile has been seen before, but can not be seen now.
+    Explored = 1,
+    /// The tile can be seen now.
+    Visible = 2,
+}
+
+impl Default for FogState {
+    fn default() -> Self {
+        FogState::Unexplored
+    }
+}
+
+impl FogState {
+    /// Returns the state of a byte, if it is one.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn from_u8(value: u8) -> Option<FogState> {
+        match value {
+            0 => Some(FogState::Unexplored),
+            1 => Some(FogState::Explored),
+            2 => Some(FogState::Visible),
+            _ => None,
+        }
+    }
+}
+
+/// The tints that the states of a fog layer are rendered with.
+///
+/// The tints are kept with 8 bits for every component.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct FogTints {
+    /// The tint of unexplored tiles.
+    unexplored: [u8; 4],
+    /// The tint of explored tiles.
+    explored: [u8; 4],
+    /// The tint of visible tiles.
+    visible: [u8; 4],
+}
+
+impl Default for FogTints {
+    fn default() -> Self {
+        FogTints::new(
+            Color::BLACK,
+            Color::rgba(0.0, 0.0, 0.0, 0.5),
+            Color::rgba(0.0, 0.0, 0.0, 0.0),
+        )
+    }
+}
+
+impl FogTints {
+    /// Constructs new fog tints from the tint of every state.
+    ///
+    /// A tint with an alpha of 0 is not rendered at all.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::prelude::*;
+    ///
+    /// let tints = FogTints::new(
+    ///     Color::BLACK,
+    ///     Color::rgba(0.0, 0.0, 0.2, 0.6),
+    ///     Color::rgba(0.0, 0.0, 0.0, 0.0),
+    /// );
+    ///
+    /// assert_eq!(tints.tint(FogState::Unexplored), Color::BLACK);
+    /// ```
+    pub fn new(unexplored: Color, explored: Color, visible: Color) -> FogTints {
+        FogTints {
+            unexplored: color_to_bytes(unexplored),
+            explored: color_to_bytes(explored),
+            visible: color_to_bytes(visible),
+        }
+    }
+
+    /// Returns the tint of a state.
+    pub fn tint(&self, state: FogState) -> Color {
+        let [r, g, b, a] = match state {
+            FogState::Unexplored => self.unexplored,
+            FogState::Explored => self.explored,
+            FogState::Visible => self.visible,
+        };
+        Color::rgba(
+            f32::from(r) / 255.0,
+            f32::from(g) / 255.0,
+            f32::from(b) / 255.0,
+            f32::from(a) / 255.0,
+        )
+    }
+
+    /// Returns the components of every tint, in the order of the states.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn to_bytes(&self) -> [[u8; 4]; 3] {
+        [self.unexplored, self.explored, self.visible]
+    }
+
+    /// Constructs fog tints from the components of every tint, in the order of
+    /// the states.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn from_bytes([unexplored, explored, visible]: [[u8; 4]; 3]) -> FogTints {
+        FogTints {
+            unexplored,
+            explored,
+            visible,
+        }
+    }
+}
+
+/// Returns the components of a color with 8 bits each.
+fn color_to_bytes(color: Color) -> [u8; 4] {
+    let components: [f32; 4] = color.into();
+    let mut bytes = [0; 4];
+    for (byte, component) in bytes.iter_mut().zip(components.iter()) {
+        *byte = (component.max(0.0).min(1.0) * 255.0).round() as u8;
+    }
+    bytes
+}
@@ -0,0 +1,471 @@
+//! Field of view and line of sight over the tiles of a tilemap.
+//!
+//! Both are decided by an opacity function that is called with every tile at a
+//! point and its z order. A point is opaque if any of its tiles is opaque and
+//! points without tiles are transparent. Points in chunks that are not loaded
+//! are opaque and are never visible.
+//!
+//! [`field_of_view`] uses shadowcasting to find every point that can be seen
+//! from an origin. On square grids this is done octant by octant, on hex grids
+//! ring by ring around the origin. [`line_of_sight`] tests if a single point
+//! can be seen from another by following the [`line`] between them.
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::fov::field_of_view;
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! let floor = 0;
+//! let wall = 1;
+//! let mut tiles = Vec::new();
+//! for y in -8..8 {
+//!     for x in -8..8 {
+//!         let index = if x == 2 && y.abs() < 2 { wall } else { floor };
+//!         tiles.push(Tile::with_tint((x, y), index, Color::rgb(0.5, 0.5, 0.5)));
+//!     }
+//! }
+//! tilemap.insert_tiles(tiles).unwrap();
+//!
+//! let visible = field_of_view(&tilemap, (0, 0), 6, |tile, _| tile.index == wall);
+//!
+//! assert!(visible.contains(&Point2::new(2, 0)));
+//! assert!(!visible.contains(&Point2::new(4, 0)));
+//!
+//! // Light up everything that can be seen in a single batch.
+//! let lit: Vec<Tile> = visible
+//!     .iter()
+//!     .map(|point| {
+//!         let index = tilemap.get_tile(*point, 0).unwrap().index;
+//!         Tile::with_tint(*point, index, Color::WHITE)
+//!     })
+//!     .collect();
+//! tilemap.insert_tiles(lit).unwrap();
+//! ```
+//!
+//! [`field_of_view`]: field_of_view
+//! [`line_of_sight`]: line_of_sight
+//! [`line`]: crate::topology::line
+
+use crate::{
+    lib::*,
+    render::GridTopology,
+    tile::RawTile,
+    tilemap::Tilemap,
+    topology::{self, from_axial, to_axial},
+};
+
+/// The transforms of the first octant into every octant of a square grid.
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+/// The directions in axial coordinates that are followed around a ring of
+/// hexes.
+const AXIAL_DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// Looks up and remembers which points of a tilemap are opaque.
+struct Opacity<'a, F> {
+    /// The tilemap that is looked at.
+    tilemap: &'a Tilemap,
+    /// If a tile is opaque.
+    opaque: F,
+    /// The points that were looked up.
+    points: HashMap<Point2, Option<bool>>,
+}
+
+impl<'a, F> Opacity<'a, F>
+where
+    F: FnMut(&RawTile, usize) -> bool,
+{
+    /// Constructs a new lookup of opaque points.
+    fn new(tilemap: &'a Tilemap, opaque: F) -> Opacity<'a, F> {
+        Opacity {
+            tilemap,
+            opaque,
+            points: HashMap::default(),
+        }
+    }
+
+    /// Returns if a point is opaque, or `None` if it is not loaded.
+    fn get(&mut self, point: Point2) -> Option<bool> {
+        if let Some(opaque) = self.points.get(&point) {
+            return *opaque;
+        }
+        let tilemap = self.tilemap;
+        let opaque = &mut self.opaque;
+        let opaque = tilemap.chunk_and_index(point).map(|(chunk, index)| {
+            (0..tilemap.layer_count()).any(|z_order| {
+                chunk
+                    .get_tile(z_order, index)
+                    .map_or(false, |tile| opaque(tile, z_order))
+            })
+        });
+        self.points.insert(point, opaque);
+        opaque
+    }
+}
+
+/// Casts shadows over the octants of a square grid.
+struct SquareCaster<'a, 'b, F> {
+    /// The opacity of the points.
+    opacity: &'b mut Opacity<'a, F>,
+    /// The points which were found to be visible.
+    visible: &'b mut HashSet<Point2>,
+    /// The point that is seen from.
+    origin: Point2,
+    /// The distance that can be seen.
+    radius: i32,
+}
+
+impl<'a, 'b, F> SquareCaster<'a, 'b, F>
+where
+    F: FnMut(&RawTile, usize) -> bool,
+{
+    /// Casts light into the rows of an octant from a row on, between a start
+    /// and end slope.
+    fn cast(&mut self, octant: (i32, i32, i32, i32), row: i32, mut start: f64, end: f64) {
+        if start < end {
+            return;
+        }
+        let (xx, xy, yx, yy) = octant;
+        let mut next_start = start;
+        for distance in row..=self.radius {
+            let dy = -distance;
+            let mut blocked = false;
+            for dx in -distance..=0 {
+                let left = (dx as f64 - 0.5) / (dy as f64 + 0.5);
+                let right = (dx as f64 + 0.5) / (dy as f64 - 0.5);
+                if start < right {
+                    continue;
+                } else if end > left {
+                    break;
+                }
+
+                let point = Point2::new(
+                    self.origin.x + dx * xx + dy * xy,
+                    self.origin.y + dx * yx + dy * yy,
+                );
+                let opaque = self.opacity.get(point);
+                if opaque.is_some() && dx * dx + dy * dy <= self.radius * self.radius {
+                    self.visible.insert(point);
+                }
+                let opaque = opaque != Some(false);
+                if blocked {
+                    if opaque {
+                        next_start = right;
+                    } else {
+                        blocked = false;
+                        start = next_start;
+                    }
+                } else if opaque && distance < self.radius {
+                    blocked = true;
+                    self.cast(octant, distance + 1, start, left);
+                    next_start = right;
+                }
+            }
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the set of points that can be seen from an origin within a radius.
+///
+/// The opacity function is called with every tile at a point and its z order,
+/// and returns `true` if the tile can not be seen through. The origin is always
+/// visible and opaque points that are seen are visible as well.
+///
+/// On square grids the radius is a circle around the origin, on hex grids it is
+/// the amount of steps from the origin.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::fov::field_of_view;
+/// use bevy_tilemap::prelude::*;
+/// use bevy::asset::HandleId;
+/// use bevy::prelude::*;
+///
+/// // In production use a strong handle from an actual source.
+/// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+///
+/// let mut tilemap = Tilemap::builder()
+///     .texture_atlas(texture_atlas_handle)
+///     .topology(GridTopology::HexY)
+///     .finish()
+///     .unwrap();
+/// tilemap.insert_chunk((0, 0)).unwrap();
+///
+/// let visible = field_of_view(&tilemap, (0, 0), 2, |_, _| false);
+///
+/// assert_eq!(visible.len(), 19);
+/// ```
+pub fn field_of_view<P, F>(tilemap: &Tilemap, origin: P, radius: u32, opaque: F) -> HashSet<Point2>
+where
+    P: Into<Point2>,
+    F: FnMut(&RawTile, usize) -> bool,
+{
+    let origin = origin.into();
+    let mut opacity = Opacity::new(tilemap, opaque);
+    let mut visible = HashSet::default();
+    if opacity.get(origin).is_some() {
+        visible.insert(origin);
+    }
+
+    let topology = tilemap.topology();
+    if topology == GridTopology::Square {
+        let mut caster = SquareCaster {
+            opacity: &mut opacity,
+            visible: &mut visible,
+            origin,
+            radius: radius as i32,
+        };
+        for octant in OCTANTS.iter() {
+            caster.cast(*octant, 1, 1.0, 0.0);
+        }
+    } else {
+        cast_hex(&mut opacity, &mut visible, topology, origin, radius as i32);
+    }
+
+    visible
+}
+
+/// Casts shadows over the rings of hexes around an origin.
+///
+/// Every hex of a ring covers an equal part of the circle around the origin.
+/// A hex is visible if its center is not covered by the shadows of the opaque
+/// hexes of the rings before it.
+fn cast_hex<F>(
+    opacity: &mut Opacity<'_, F>,
+    visible: &mut HashSet<Point2>,
+    topology: GridTopology,
+    origin: Point2,
+    radius: i32,
+) where
+    F: FnMut(&RawTile, usize) -> bool,
+{
+    let (origin_q, origin_r) = to_axial(topology, origin);
+    let mut shadows: Vec<(f64, f64)> = Vec::new();
+    for ring in 1..=radius {
+        let width = 1.0 / (6 * ring) as f64;
+        let (start_q, start_r) = AXIAL_DIRECTIONS[4];
+        let (mut q, mut r) = (origin_q + start_q * ring, origin_r + start_r * ring);
+        let mut ring_shadows = Vec::new();
+        for index in 0..6 * ring {
+            let center = index as f64 * width;
+            if !in_shadow(&shadows, center) {
+                let point = from_axial(topology, (q, r));
+                let opaque = opacity.get(point);
+                if opaque.is_some() {
+                    visible.insert(point);
+                }
+                if opaque != Some(false) {
+                    ring_shadows.push((center - width / 2.0, center + width / 2.0));
+                }
+            }
+            let (dq, dr) = AXIAL_DIRECTIONS[(index / ring) as usize];
+            q += dq;
+            r += dr;
+        }
+
+        shadows.extend(ring_shadows);
+        merge_shadows(&mut shadows);
+        if shadows.iter().any(|(start, end)| end - start >= 1.0) {
+            break;
+        }
+    }
+}
+
+/// Returns `true` if a part of the circle is covered by a shadow.
+///
+/// Shadows can reach past either end of the circle, which wraps around.
+fn in_shadow(shadows: &[(f64, f64)], center: f64) -> bool {
+    shadows.iter().any(|(start, end)| {
+        [center - 1.0, center, center + 1.0]
+            .iter()
+            .any(|center| start < center && center < end)
+    })
+}
+
+/// Sorts shadows and merges the ones that touch into one.
+fn merge_shadows(shadows: &mut Vec<(f64, f64)>) {
+    shadows.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut merged: Vec<(f64, f64)> = Vec::with_capacity(shadows.len());
+    for &(start, end) in shadows.iter() {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1e-9 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    *shadows = merged;
+}
+
+/// Returns `true` if a point can be seen from another.
+///
+/// The opacity function is called with every tile at a point and its z order,
+/// and returns `true` if the tile can not be seen through. Only the points in
+/// between are tested, so opaque points can be seen but can not see through
+/// themselves.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::fov::line_of_sight;
+/// use bevy_tilemap::prelude::*;
+/// use bevy_tilemap::tile::RawTile;
+/// use bevy::asset::HandleId;
+/// use bevy::prelude::*;
+///
+/// // In production use a strong handle from an actual source.
+/// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+///
+/// let mut tilemap = Tilemap::new(texture_atlas_handle);
+/// tilemap.insert_chunk((0, 0)).unwrap();
+///
+/// let wall = 1;
+/// tilemap.insert_tile(Tile::new((2, 0), wall)).unwrap();
+///
+/// let opaque = |tile: &RawTile, _| tile.index == wall;
+/// assert!(line_of_sight(&tilemap, (0, 0), (2, 0), opaque));
+/// assert!(!line_of_sight(&tilemap, (0, 0), (4, 0), opaque));
+/// assert!(line_of_sight(&tilemap, (0, 0), (4, 3), opaque));
+/// ```
+pub fn line_of_sight<P, F>(tilemap: &Tilemap, from: P, to: P, opaque: F) -> bool
+where
+    P: Into<Point2>,
+    F: FnMut(&RawTile, usize) -> bool,
+{
+    let points = topology::line(tilemap.topology(), from.into(), to.into());
+    let mut opacity = Opacity::new(tilemap, opaque);
+    points
+        .iter()
+        .skip(1)
+        .take(points.len().saturating_sub(2))
+        .all(|point| opacity.get(*point) == Some(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+
+    fn new_tilemap(topology: GridTopology, walls: &[(i32, i32)]) -> Tilemap {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .topology(topology)
+            .chunk_dimensions(8, 8)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        let mut tiles = Vec::new();
+        for y in -10..=10 {
+            for x in -10..=10 {
+                let index = if walls.contains(&(x, y)) { 1 } else { 0 };
+                tiles.push(Tile::new((x, y), index));
+            }
+        }
+        tilemap.insert_tiles(tiles).unwrap();
+        tilemap
+    }
+
+    fn wall(tile: &RawTile, _: usize) -> bool {
+        tile.index == 1
+    }
+
+    #[test]
+    fn square_open_floor() {
+        let tilemap = new_tilemap(GridTopology::Square, &[]);
+        let visible = field_of_view(&tilemap, (1, 1), 4, wall);
+        for y in -6..=8 {
+            for x in -6..=8 {
+                let (dx, dy) = (x - 1, y - 1);
+                assert_eq!(
+                    visible.contains(&Point2::new(x, y)),
+                    dx * dx + dy * dy <= 16,
+                    "({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn square_walls_cast_shadows() {
+        let tilemap = new_tilemap(GridTopology::Square, &[(2, 0), (0, -2), (-1, 1)]);
+        let visible = field_of_view(&tilemap, (0, 0), 6, wall);
+
+        for &point in [(2, 0), (0, -2), (-1, 1), (2, 1), (3, 2)].iter() {
+            assert!(visible.contains(&point.into()), "{:?}", point);
+        }
+        for &point in [(3, 0), (5, 0), (0, -3), (0, -6), (-2, 2), (-4, 4)].iter() {
+            assert!(!visible.contains(&point.into()), "{:?}", point);
+        }
+
+        assert!(line_of_sight(&tilemap, (0, 0), (2, 1), wall));
+        assert!(!line_of_sight(&tilemap, (0, 0), (4, 0), wall));
+        assert!(!line_of_sight(&tilemap, (0, 0), (-3, 3), wall));
+    }
+
+    #[test]
+    fn hex_open_floor_and_shadows() {
+        use GridTopology::*;
+        for &topology in [HexY, HexX, HexEvenRows, HexOddRows, HexEvenCols, HexOddCols].iter() {
+            let origin = Point2::new(0, 1);
+            let tilemap = new_tilemap(topology, &[]);
+            let visible = field_of_view(&tilemap, origin, 3, wall);
+            assert_eq!(visible.len(), 37, "{:?}", topology);
+            for point in visible.iter() {
+                assert!(topology::distance(topology, false, origin, *point) <= 3);
+            }
+
+            for neighbour in topology::neighbours(topology, origin, false) {
+                let (q, r) = to_axial(topology, origin);
+                let (nq, nr) = to_axial(topology, neighbour);
+                let behind = from_axial(topology, (2 * nq - q, 2 * nr - r));
+                let far_behind = from_axial(topology, (3 * nq - 2 * q, 3 * nr - 2 * r));
+
+                let tilemap = new_tilemap(topology, &[(neighbour.x, neighbour.y)]);
+                let visible = field_of_view(&tilemap, origin, 4, wall);
+                assert!(visible.contains(&neighbour), "{:?}", topology);
+                assert!(!visible.contains(&behind), "{:?}", topology);
+                assert!(!visible.contains(&far_behind), "{:?}", topology);
+                // The wall hides 1 hex of the second ring and 3 hexes of the
+                // third and fourth rings.
+                assert_eq!(visible.len(), 61 - 1 - 3 - 3, "{:?}", topology);
+
+                assert!(!line_of_sight(&tilemap, origin, behind, wall));
+                assert!(line_of_sight(&tilemap, origin, neighbour, wall));
+            }
+        }
+    }
+
+    #[test]
+    fn unloaded_chunks_are_opaque() {
+        let mut tilemap = new_tilemap(GridTopology::Square, &[]);
+        // The chunk (1, 0) holds the points from (4, -4) to (11, 3).
+        tilemap.remove_chunk((1, 0)).unwrap();
+        let visible = field_of_view(&tilemap, (0, 0), 8, wall);
+
+        assert!(visible.contains(&Point2::new(3, 0)));
+        assert!(!visible.contains(&Point2::new(4, 0)));
+        assert!(!visible.contains(&Point2::new(6, 4)));
+        assert!(!line_of_sight(&tilemap, (0, 0), (6, 4), wall));
+        assert!(line_of_sight(&tilemap, (0, 0), (3, 5), wall));
+    }
+}
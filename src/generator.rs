@@ -0,0 +1,238 @@
+//! Procedural generation of the contents of new chunks.
+//!
+//! A [`ChunkGenerator`] can be set on a tilemap with
+//! [`TilemapBuilder::generator`] or [`Tilemap::set_generator`]. Every chunk
+//! that is newly created is then filled with the tiles it returns. This
+//! happens when a chunk is inserted with [`Tilemap::insert_chunk`], when a
+//! chunk is automatically created by [`Tilemap::insert_tiles`] or when a chunk
+//! that does not exist is spawned with [`Tilemap::spawn_chunk`].
+//!
+//! Only a chunk that does not exist when it is spawned is generated in the
+//! background on the [`AsyncComputeTaskPool`], so that large chunks do not
+//! block the frame. Until the chunk is generated it is pending, it is already
+//! contained in the tilemap and spawning it again does nothing. Tiles that are
+//! inserted into a pending chunk are queued and inserted on top of the
+//! generated tiles once they are ready. Chunks that are inserted with
+//! [`Tilemap::insert_chunk`] or created by [`Tilemap::insert_tiles`] are still
+//! generated right away on the calling thread, as the tiles are expected to
+//! be there when they return.
+//!
+//! Generated tiles outside of their chunk or with a layer or animation that
+//! does not exist are dropped, the same goes for queued tiles whose layer or
+//! animation no longer exists once the chunk is generated.
+//!
+//! Generators must be deterministic, generating the same chunk twice must
+//! return the same tiles. This allows a chunk to be removed and generated
+//! again later, exactly as it was. The [`chunk_seed`] function can be used to
+//! derive a seed for each chunk from a single world seed.
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy_tilemap::generator::ChunkGenerator;
+//! use bevy_tilemap_types::{dimension::Dimension2, point::Point2};
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! /// A generator which fills the bottom layer with grass.
+//! struct Grass;
+//!
+//! impl ChunkGenerator for Grass {
+//!     fn generate(
+//!         &self,
+//!         _point: Point2,
+//!         dimensions: Dimension2,
+//!         _layers: &[Option<LayerKind>],
+//!     ) -> Vec<Tile> {
+//!         let mut tiles = Vec::new();
+//!         for y in 0..dimensions.height as i32 {
+//!             for x in 0..dimensions.width as i32 {
+//!                 tiles.push(Tile::new((x, y), 1));
+//!             }
+//!         }
+//!         tiles
+//!     }
+//! }
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .generator(Grass)
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.insert_chunk((1, 1)).unwrap();
+//!
+//! assert_eq!(tilemap.get_tile((32, 32), 0).unwrap().index, 1);
+//! ```
+//!
+//! [`ChunkGenerator`]: ChunkGenerator
+//! [`TilemapBuilder::generator`]: crate::tilemap::TilemapBuilder::generator
+//! [`Tilemap::set_generator`]: crate::tilemap::Tilemap::set_generator
+//! [`Tilemap::insert_chunk`]: crate::tilemap::Tilemap::insert_chunk
+//! [`Tilemap::insert_tiles`]: crate::tilemap::Tilemap::insert_tiles
+//! [`Tilemap::spawn_chunk`]: crate::tilemap::Tilemap::spawn_chunk
+//! [`chunk_seed`]: chunk_seed
+//! [`AsyncComputeTaskPool`]: bevy::tasks::AsyncComputeTaskPool
+
+use crate::{chunk::LayerKind, lib::*, tile::Tile};
+
+/// A generator of the tiles of new chunks.
+pub trait ChunkGenerator: Send + Sync + 'static {
+    /// Generates the tiles of a new chunk.
+    ///
+    /// The chunk point, the dimensions of a chunk in tiles and the layers of
+    /// the tilemap are given. The points of the returned tiles are local to
+    /// the chunk, from `(0, 0)` at the bottom left to one less than the width
+    /// and height at the top right. Any tile outside of the chunk is ignored.
+    ///
+    /// This must always return the same tiles for the same point.
+    fn generate(
+        &self,
+        point: Point2,
+        dimensions: Dimension2,
+        layers: &[Option<LayerKind>],
+    ) -> Vec<Tile>;
+}
+
+/// Returns a seed for a chunk which is derived from a world seed.
+///
+/// The same world seed and point always returns the same chunk seed, while
+/// neighbouring chunks get seeds that are unrelated to each other.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::generator::chunk_seed;
+/// use bevy_tilemap_types::point::Point2;
+///
+/// let seed = 42;
+///
+/// assert_eq!(chunk_seed(seed, Point2::new(1, 2)), chunk_seed(seed, Point2::new(1, 2)));
+/// assert_ne!(chunk_seed(seed, Point2::new(1, 2)), chunk_seed(seed, Point2::new(2, 1)));
+/// ```
+pub fn chunk_seed(seed: u64, point: Point2) -> u64 {
+    // SplitMix64 finalizer over the seed mixed with the point.
+    let point = (u64::from(point.x as u32) << 32) | u64::from(point.y as u32);
+    let mut z = (seed ^ point).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A shared chunk generator that is stored in a tilemap.
+#[derive(Clone)]
+pub(crate) struct Generator(pub(crate) Arc<dyn ChunkGenerator>);
+
+impl Debug for Generator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("Generator")
+    }
+}
+
+impl PartialEq for Generator {
+    fn eq(&self, other: &Generator) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A chunk that had finished generating with the tiles that were queued for
+/// it while it was pending.
+pub(crate) struct GeneratedChunk {
+    /// The point of the chunk.
+    pub(crate) point: Point2,
+    /// The tiles returned by the generator, local to the chunk.
+    pub(crate) tiles: Vec<Tile>,
+    /// The tiles inserted while the chunk was pending, in global points.
+    pub(crate) queued_tiles: Vec<Tile>,
+}
+
+/// The chunks which are being generated in the background.
+#[derive(Default, Debug)]
+pub(crate) struct PendingChunks {
+    /// The points of all the pending chunks.
+    points: HashSet<Point2>,
+    /// The points of the chunks that still need a task to be started.
+    requests: Vec<Point2>,
+    /// The tiles inserted into pending chunks.
+    queued_tiles: HashMap<Point2, Vec<Tile>>,
+    /// The chunks that had finished generating, shared with the tasks.
+    finished: Arc<Mutex<Vec<(Point2, Vec<Tile>)>>>,
+}
+
+impl PendingChunks {
+    /// Returns `true` if the chunk is pending.
+    pub(crate) fn contains(&self, point: &Point2) -> bool {
+        self.points.contains(point)
+    }
+
+    /// Requests a chunk to be generated, if it is not already pending.
+    pub(crate) fn request(&mut self, point: Point2) {
+        if self.points.insert(point) {
+            self.requests.push(point);
+        }
+    }
+
+    /// Queues a tile to be inserted once the chunk is generated.
+    pub(crate) fn queue_tile(&mut self, point: Point2, tile: Tile) {
+        self.queued_tiles
+            .entry(point)
+            .or_insert_with(Vec::new)
+            .push(tile);
+    }
+
+    /// Cancels a pending chunk, its result is discarded once it finishes.
+    pub(crate) fn cancel(&mut self, point: &Point2) {
+        self.points.remove(point);
+        self.queued_tiles.remove(point);
+    }
+
+    /// Starts a task on the task pool for every requested chunk.
+    pub(crate) fn start(
+        &mut self,
+        task_pool: &TaskPool,
+        generator: &Generator,
+        dimensions: Dimension2,
+        layers: &[Option<LayerKind>],
+    ) {
+        for point in self.requests.drain(..) {
+            let generator = generator.clone();
+            let layers = layers.to_vec();
+            let finished = self.finished.clone();
+            task_pool
+                .spawn(async move {
+                    let tiles = generator.0.generate(point, dimensions, &layers);
+                    finished
+                        .lock()
+                        .expect("Generated chunks lock is poisoned.")
+                        .push((point, tiles));
+                })
+                .detach();
+        }
+    }
+
+    /// Takes every chunk that had finished generating and is still pending.
+    pub(crate) fn take_finished(&mut self) -> Vec<GeneratedChunk> {
+        let finished: Vec<(Point2, Vec<Tile>)> = self
+            .finished
+            .lock()
+            .expect("Generated chunks lock is poisoned.")
+            .drain(..)
+            .collect();
+
+        let mut chunks = Vec::with_capacity(finished.len());
+        for (point, tiles) in finished.into_iter() {
+            // The chunk was cancelled while it was generating.
+            if !self.points.remove(&point) {
+                continue;
+            }
+            chunks.push(GeneratedChunk {
+                point,
+                tiles,
+                queued_tiles: self.queued_tiles.remove(&point).unwrap_or_default(),
+            });
+        }
+        chunks
+    }
+}
@@ -0,0 +1,1074 @@
+//! Importing of projects made with the [LDtk] level editor.
+//!
+//! A [`LdtkProject`] is read from a `.ldtk` file and holds every level and
+//! tileset of the project. Each [`LdtkLevel`] can construct its own tilemap
+//! with [`LdtkLevel::to_tilemap`].
+//!
+//! A project is a single JSON file with the definitions of its tilesets and
+//! the layer instances of every level, levels that are saved in separate
+//! files are not supported. LDtk lists the layers of a level from the top
+//! down, IntGrid, AutoLayer and Tiles layers become z layers from the bottom
+//! up so the top layer in LDtk is the highest z layer.
+//!
+//! The tiles of a layer are its grid tiles or the tiles its auto rules
+//! produced. Each tile refers to its sprite by the pixel position of the
+//! sprite in the tileset image, which becomes the sprite index of the row
+//! major [`LdtkTileset::texture_atlas`], and its `f` bits become its
+//! [`TileFlip`]. IntGrid layers keep their values as the terrain of each tile
+//! so they can be used with [`Tilemap::get_terrain`] or auto tiling. IntGrid
+//! layers without a tileset only have values and no sprites, so their tiles
+//! are not inserted into the tilemap. The values of every IntGrid layer are
+//! available as a [`TileData`] with [`LdtkLevel::int_grid`] instead. A layer
+//! that is at least half filled becomes a [`LayerKind::Dense`] layer,
+//! otherwise it becomes a [`LayerKind::Sparse`] layer.
+//!
+//! The tilesets of a project are turned into texture atlases with
+//! [`LdtkProject::load_texture_atlases`]. A tilemap only has a single texture
+//! atlas, so all the layers of a level must use the same tileset. Entities are
+//! not spawned, they are kept in [`LdtkLevel::entities`] with their fields for
+//! the game to spawn from.
+//!
+//! LDtk positions cells, entities and levels in pixels with y pointing down
+//! from the top left, while the tilemap points y up. The cell in column `x`
+//! and row `y` of a layer is placed at the point `(x, height - 1 - y)`, and
+//! the world position of a level has its y negated.
+//!
+//! This module requires the **"ldtk"** feature.
+//!
+//! # Examples
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_tilemap::ldtk::LdtkProject;
+//! use bevy_tilemap::prelude::*;
+//! use std::path::Path;
+//!
+//! fn setup(
+//!     mut commands: Commands,
+//!     asset_server: Res<AssetServer>,
+//!     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+//! ) {
+//!     let project = LdtkProject::from_path("assets/maps/world.ldtk").unwrap();
+//!     let atlases =
+//!         project.load_texture_atlases(Path::new("maps"), &asset_server, &mut texture_atlases);
+//!
+//!     for level in project.levels.iter() {
+//!         let tilemap = level.to_tilemap(&atlases).unwrap();
+//!         commands.spawn(TilemapComponents {
+//!             tilemap,
+//!             transform: Default::default(),
+//!             global_transform: Default::default(),
+//!         });
+//!     }
+//! }
+//! ```
+//!
+//! [LDtk]: https://ldtk.io/
+//! [`LdtkProject`]: LdtkProject
+//! [`LdtkLevel`]: LdtkLevel
+//! [`LdtkLevel::to_tilemap`]: LdtkLevel::to_tilemap
+//! [`LdtkLevel::entities`]: LdtkLevel::entities
+//! [`LdtkLevel::int_grid`]: LdtkLevel::int_grid
+//! [`TileData`]: crate::tile_data::TileData
+//! [`LdtkProject::load_texture_atlases`]: LdtkProject::load_texture_atlases
+//! [`LdtkTileset::texture_atlas`]: LdtkTileset::texture_atlas
+//! [`Tilemap::get_terrain`]: crate::tilemap::Tilemap::get_terrain
+//! [`LayerKind::Dense`]: crate::chunk::LayerKind::Dense
+//! [`LayerKind::Sparse`]: crate::chunk::LayerKind::Sparse
+//! [`TileFlip`]: crate::tile::TileFlip
+
+use crate::{
+    chunk::LayerKind,
+    lib::{serde_json::Value, *},
+    tile::{Tile, TileFlip},
+    tile_data::TileData,
+    tilemap::{Tilemap, TilemapBuilder, TilemapError},
+};
+
+#[derive(Debug)]
+/// The kinds of errors that can occur when importing.
+enum ErrorKind {
+    /// A file could not be read.
+    Io(io::Error),
+    /// The JSON is malformed or is not an LDtk project.
+    Json(String),
+    /// The level is saved in a separate file.
+    ExternalLevel(String),
+    /// No layer of the level has a tileset.
+    NoTileset(String),
+    /// A layer uses a tileset which does not exist or has no texture atlas.
+    MissingTileset(i32),
+    /// The layers of a level use more than one tileset.
+    MixedTilesets(String),
+    /// A tileset has a grid size of zero.
+    ZeroGridSize(String),
+    /// The tilemap could not be constructed.
+    Tilemap(TilemapError),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use ErrorKind::*;
+        match self {
+            Io(err) => write!(f, "could not read the project: {}", err),
+            Json(err) => write!(f, "the project is malformed: {}", err),
+            ExternalLevel(level) => write!(
+                f,
+                "level `{}` is saved in a separate file, which is not supported",
+                level
+            ),
+            NoTileset(level) => write!(f, "no layer of level `{}` has a tileset", level),
+            MissingTileset(uid) => write!(
+                f,
+                "tileset {} does not exist or has no texture atlas, try `load_texture_atlases` first",
+                uid
+            ),
+            MixedTilesets(level) => write!(
+                f,
+                "the layers of level `{}` use more than one tileset",
+                level
+            ),
+            ZeroGridSize(tileset) => write!(f, "tileset `{}` has a grid size of zero", tileset),
+            Tilemap(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The error type for importing an LDtk project.
+pub struct LdtkError(Box<ErrorKind>);
+
+impl Display for LdtkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for LdtkError {}
+
+impl From<ErrorKind> for LdtkError {
+    fn from(kind: ErrorKind) -> LdtkError {
+        LdtkError(Box::new(kind))
+    }
+}
+
+impl From<io::Error> for LdtkError {
+    fn from(err: io::Error) -> LdtkError {
+        LdtkError(Box::new(ErrorKind::Io(err)))
+    }
+}
+
+impl From<TilemapError> for LdtkError {
+    fn from(err: TilemapError) -> LdtkError {
+        LdtkError(Box::new(ErrorKind::Tilemap(err)))
+    }
+}
+
+/// An LDtk import result.
+pub type LdtkResult<T> = Result<T, LdtkError>;
+
+/// A tile of an LDtk layer.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct LdtkTile {
+    /// The point of the tile in the tilemap.
+    pub point: Point2,
+    /// The sprites index in the texture atlas of the tileset.
+    pub sprite_index: usize,
+    /// The IntGrid value of the cell, if the layer is an IntGrid layer.
+    pub value: Option<u32>,
+    /// If the tile is flipped horizontally.
+    pub flip_x: bool,
+    /// If the tile is flipped vertically.
+    pub flip_y: bool,
+}
+
+impl LdtkTile {
+    /// Returns the flip flags of the tile.
+    pub fn flip(&self) -> TileFlip {
+        let mut flip = TileFlip::empty();
+        flip.set(TileFlip::HORIZONTAL, self.flip_x);
+        flip.set(TileFlip::VERTICAL, self.flip_y);
+        flip
+    }
+}
+
+/// A tile layer of an LDtk level.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct LdtkLayer {
+    /// The identifier of the layer.
+    pub identifier: String,
+    /// The kind of layer that suits the amount of tiles in it.
+    pub kind: LayerKind,
+    /// The UID of the tileset of the layer, if it has one.
+    pub tileset: Option<i32>,
+    /// If the layer is visible.
+    pub visible: bool,
+    /// The tint of the tiles, with the opacity of the layer in the alpha.
+    pub tint: Color,
+    /// The tiles of the layer.
+    pub tiles: Vec<LdtkTile>,
+}
+
+/// An entity of an LDtk level, which can be spawned by the game.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct LdtkEntity {
+    /// The identifier of the entity definition.
+    pub identifier: String,
+    /// The identifier of the layer that the entity is in.
+    pub layer: String,
+    /// The point of the cell the entity is in.
+    pub point: Point2,
+    /// The position of the entity in pixels from the bottom left of the level.
+    pub position: Vec2,
+    /// The width of the entity in pixels.
+    pub width: u32,
+    /// The height of the entity in pixels.
+    pub height: u32,
+    /// The values of the fields of the entity by their identifier.
+    pub fields: HashMap<String, Value>,
+}
+
+/// A level of an LDtk project.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct LdtkLevel {
+    /// The identifier of the level.
+    pub identifier: String,
+    /// The unique ID of the level.
+    pub uid: i32,
+    /// The position of the level in the world in pixels.
+    pub world_position: Point2,
+    /// The size of a cell in pixels.
+    pub grid_size: u32,
+    /// The tile layers of the level, from the bottom to the top.
+    pub layers: Vec<LdtkLayer>,
+    /// The entities of all the entity layers of the level.
+    pub entities: Vec<LdtkEntity>,
+}
+
+/// A tileset of an LDtk project.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct LdtkTileset {
+    /// The identifier of the tileset.
+    pub identifier: String,
+    /// The unique ID of the tileset.
+    pub uid: i32,
+    /// The path of the image relative to the project, if it has one.
+    pub path: Option<PathBuf>,
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The size of a tile in pixels.
+    pub grid_size: u32,
+    /// The space between tiles in pixels.
+    pub spacing: u32,
+    /// The space around the edge of the image in pixels.
+    pub padding: u32,
+}
+
+/// A project that was made with LDtk.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct LdtkProject {
+    /// The levels of the project.
+    pub levels: Vec<LdtkLevel>,
+    /// The tilesets of the project.
+    pub tilesets: Vec<LdtkTileset>,
+}
+
+impl LdtkProject {
+    /// Reads an LDtk project from a `.ldtk` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or is not a valid LDtk
+    /// project, or if a level is saved in a separate file.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    ///
+    /// let project = LdtkProject::from_path("assets/maps/world.ldtk").unwrap();
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> LdtkResult<LdtkProject> {
+        LdtkProject::from_reader(fs::File::open(path)?)
+    }
+
+    /// Reads an LDtk project from a reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the project could not be read or is not valid, or
+    /// if a level is saved in a separate file.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    ///
+    /// let ldtk = r#"{
+    ///     "defs": { "tilesets": [] },
+    ///     "levels": [{ "identifier": "Level_0", "uid": 0, "layerInstances": [] }]
+    /// }"#;
+    ///
+    /// let project = LdtkProject::from_reader(ldtk.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(project.levels[0].identifier, "Level_0");
+    /// ```
+    pub fn from_reader<R: io::Read>(reader: R) -> LdtkResult<LdtkProject> {
+        let raw: RawProject =
+            serde_json::from_reader(reader).map_err(|err| ErrorKind::Json(err.to_string()))?;
+
+        let mut tilesets = Vec::with_capacity(raw.defs.tilesets.len());
+        for tileset in raw.defs.tilesets.into_iter() {
+            // The grid size divides every pixel position of the tileset.
+            if tileset.tile_grid_size == 0 {
+                return Err(ErrorKind::ZeroGridSize(tileset.identifier).into());
+            }
+            tilesets.push(LdtkTileset {
+                identifier: tileset.identifier,
+                uid: tileset.uid,
+                path: tileset.rel_path.map(PathBuf::from),
+                width: tileset.px_wid,
+                height: tileset.px_hei,
+                grid_size: tileset.tile_grid_size,
+                spacing: tileset.spacing,
+                padding: tileset.padding,
+            });
+        }
+
+        let mut levels = Vec::with_capacity(raw.levels.len());
+        for level in raw.levels.into_iter() {
+            levels.push(LdtkLevel::from_raw(level, &tilesets)?);
+        }
+
+        Ok(LdtkProject { levels, tilesets })
+    }
+
+    /// Loads the image of every tileset and adds a texture atlas for each of
+    /// them, returning the handles by the UID of the tileset.
+    ///
+    /// The directory is the asset path of the directory of the project, the
+    /// paths of the images are relative to it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    /// use std::path::Path;
+    ///
+    /// fn setup(asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>) {
+    ///     let project = LdtkProject::from_path("assets/maps/world.ldtk").unwrap();
+    ///     let atlases =
+    ///         project.load_texture_atlases(Path::new("maps"), &asset_server, &mut texture_atlases);
+    /// }
+    /// ```
+    pub fn load_texture_atlases(
+        &self,
+        directory: &Path,
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlas>,
+    ) -> HashMap<i32, Handle<TextureAtlas>> {
+        let mut handles = HashMap::default();
+        for tileset in self.tilesets.iter() {
+            if let Some(path) = &tileset.path {
+                let texture = asset_server.load(directory.join(path).as_path());
+                let handle = texture_atlases.add(tileset.texture_atlas(texture));
+                handles.insert(tileset.uid, handle);
+            }
+        }
+        handles
+    }
+}
+
+impl LdtkTileset {
+    /// Returns the amount of columns of tiles in the image.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    ///
+    /// let ldtk = r#"{
+    ///     "defs": { "tilesets": [{
+    ///         "identifier": "Tiles", "uid": 1, "relPath": "tiles.png",
+    ///         "pxWid": 64, "pxHei": 32, "tileGridSize": 16
+    ///     }] },
+    ///     "levels": []
+    /// }"#;
+    ///
+    /// let project = LdtkProject::from_reader(ldtk.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(project.tilesets[0].columns(), 4);
+    /// ```
+    pub fn columns(&self) -> u32 {
+        self.tiles_along(self.width)
+    }
+
+    /// Returns the amount of rows of tiles in the image.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    ///
+    /// let ldtk = r#"{
+    ///     "defs": { "tilesets": [{
+    ///         "identifier": "Tiles", "uid": 1, "relPath": "tiles.png",
+    ///         "pxWid": 64, "pxHei": 32, "tileGridSize": 16
+    ///     }] },
+    ///     "levels": []
+    /// }"#;
+    ///
+    /// let project = LdtkProject::from_reader(ldtk.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(project.tilesets[0].rows(), 2);
+    /// ```
+    pub fn rows(&self) -> u32 {
+        self.tiles_along(self.height)
+    }
+
+    /// Constructs a texture atlas of the tiles in the image, ordered from the
+    /// top left to the bottom right.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    ///
+    /// let ldtk = r#"{
+    ///     "defs": { "tilesets": [{
+    ///         "identifier": "Tiles", "uid": 1, "relPath": "tiles.png",
+    ///         "pxWid": 64, "pxHei": 32, "tileGridSize": 16
+    ///     }] },
+    ///     "levels": []
+    /// }"#;
+    ///
+    /// let project = LdtkProject::from_reader(ldtk.as_bytes()).unwrap();
+    /// let texture_handle = Handle::weak(HandleId::random::<Texture>());
+    ///
+    /// let atlas = project.tilesets[0].texture_atlas(texture_handle);
+    ///
+    /// assert_eq!(atlas.len(), 8);
+    /// ```
+    pub fn texture_atlas(&self, texture: Handle<Texture>) -> TextureAtlas {
+        let mut atlas =
+            TextureAtlas::new_empty(texture, Vec2::new(self.width as f32, self.height as f32));
+        let step = self.grid_size.saturating_add(self.spacing) as f32;
+        let size = self.grid_size as f32;
+        for row in 0..self.rows() {
+            for column in 0..self.columns() {
+                let min = Vec2::new(
+                    self.padding as f32 + column as f32 * step,
+                    self.padding as f32 + row as f32 * step,
+                );
+                atlas.add_texture(Rect {
+                    min,
+                    max: min + Vec2::new(size, size),
+                });
+            }
+        }
+        atlas
+    }
+
+    /// Returns the amount of tiles that fit along a side of the image.
+    ///
+    /// A tileset with a grid size of zero has no tiles.
+    fn tiles_along(&self, pixels: u32) -> u32 {
+        pixels
+            .saturating_add(self.spacing)
+            .saturating_sub(self.padding.saturating_mul(2))
+            .checked_div(self.grid_size.saturating_add(self.spacing))
+            .unwrap_or(0)
+    }
+
+    /// Returns the sprite index of the tile at the pixel position in the image.
+    fn sprite_index(&self, src: [u32; 2]) -> usize {
+        let step = self.grid_size.saturating_add(self.spacing).max(1);
+        let column = src[0].saturating_sub(self.padding) / step;
+        let row = src[1].saturating_sub(self.padding) / step;
+        row as usize * self.columns() as usize + column as usize
+    }
+}
+
+impl LdtkLevel {
+    /// Constructs a tilemap with the layers of the level and inserts the tiles
+    /// of every visible layer that has a tileset.
+    ///
+    /// The texture atlas is looked up by the UID of the tileset that the
+    /// layers use, such as the ones returned by
+    /// [`LdtkProject::load_texture_atlases`]. The tilemap automatically creates
+    /// chunks, which allows it to hold levels of any size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no layer has a tileset, the layers use more than
+    /// one tileset, the tileset has no texture atlas or the tiles could not be
+    /// inserted into the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    /// use bevy_tilemap::tile::TileFlip;
+    ///
+    /// let ldtk = r#"{
+    ///     "defs": { "tilesets": [{
+    ///         "identifier": "Tiles", "uid": 1, "relPath": "tiles.png",
+    ///         "pxWid": 64, "pxHei": 32, "tileGridSize": 16
+    ///     }] },
+    ///     "levels": [{ "identifier": "Level_0", "uid": 0, "layerInstances": [{
+    ///         "__identifier": "Ground", "__type": "Tiles",
+    ///         "__cWid": 2, "__cHei": 2, "__gridSize": 16, "__tilesetDefUid": 1,
+    ///         "gridTiles": [{ "px": [0, 0], "src": [16, 16], "f": 1 }]
+    ///     }] }]
+    /// }"#;
+    ///
+    /// let project = LdtkProject::from_reader(ldtk.as_bytes()).unwrap();
+    ///
+    /// let mut atlases = HashMap::default();
+    /// atlases.insert(1, Handle::weak(HandleId::random::<TextureAtlas>()));
+    ///
+    /// let mut tilemap = project.levels[0].to_tilemap(&atlases).unwrap();
+    ///
+    /// // The top left cell of the level, flipped horizontally.
+    /// assert_eq!(tilemap.get_tile((0, 1), 0).unwrap().index, 5);
+    /// assert_eq!(tilemap.get_tile((0, 1), 0).unwrap().flip, TileFlip::HORIZONTAL);
+    /// ```
+    ///
+    /// [`LdtkProject::load_texture_atlases`]: LdtkProject::load_texture_atlases
+    pub fn to_tilemap(
+        &self,
+        texture_atlases: &HashMap<i32, Handle<TextureAtlas>>,
+    ) -> LdtkResult<Tilemap> {
+        let mut tileset = None;
+        for layer in self.layers.iter() {
+            match (tileset, layer.tileset) {
+                (Some(a), Some(b)) if a != b => {
+                    return Err(ErrorKind::MixedTilesets(self.identifier.clone()).into())
+                }
+                (None, Some(b)) => tileset = Some(b),
+                _ => {}
+            }
+        }
+        let tileset = tileset.ok_or_else(|| ErrorKind::NoTileset(self.identifier.clone()))?;
+        let texture_atlas = texture_atlases
+            .get(&tileset)
+            .ok_or(ErrorKind::MissingTileset(tileset))?
+            .clone();
+
+        let mut builder = TilemapBuilder::new()
+            .texture_atlas(texture_atlas)
+            .tile_dimensions(self.grid_size, self.grid_size)
+            .z_layers(self.layers.len())
+            .auto_chunk();
+        for (z_order, layer) in self.layers.iter().enumerate() {
+            builder = builder.add_layer(layer.kind, z_order);
+        }
+        let mut tilemap = builder.finish()?;
+
+        let tiles = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.visible && layer.tileset.is_some())
+            .flat_map(|(z_order, layer)| {
+                layer.tiles.iter().map(move |tile| Tile {
+                    point: tile.point,
+                    z_order,
+                    sprite_index: tile.sprite_index,
+                    tint: layer.tint,
+                    terrain: tile.value,
+                    flip: tile.flip(),
+                    ..Tile::default()
+                })
+            });
+        tilemap.insert_tiles(tiles)?;
+
+        Ok(tilemap)
+    }
+
+    /// Returns the values of the IntGrid layers of the level at the z order
+    /// of their layers, including the layers without a tileset or that are
+    /// hidden.
+    ///
+    /// The tilemap is the one constructed with [`to_tilemap`], which the data
+    /// shares its chunk dimensions with.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use bevy_tilemap::ldtk::LdtkProject;
+    ///
+    /// let ldtk = r#"{
+    ///     "defs": { "tilesets": [{
+    ///         "identifier": "Tiles", "uid": 1, "relPath": "tiles.png",
+    ///         "pxWid": 64, "pxHei": 32, "tileGridSize": 16
+    ///     }] },
+    ///     "levels": [{ "identifier": "Level_0", "uid": 0, "layerInstances": [
+    ///         {
+    ///             "__identifier": "Ground", "__type": "Tiles",
+    ///             "__cWid": 2, "__cHei": 2, "__gridSize": 16, "__tilesetDefUid": 1,
+    ///             "gridTiles": [{ "px": [0, 0], "src": [16, 16], "f": 0 }]
+    ///         },
+    ///         {
+    ///             "__identifier": "Collisions", "__type": "IntGrid",
+    ///             "__cWid": 2, "__cHei": 2, "__gridSize": 16,
+    ///             "intGridCsv": [0, 0, 3, 0]
+    ///         }
+    ///     ] }]
+    /// }"#;
+    ///
+    /// let project = LdtkProject::from_reader(ldtk.as_bytes()).unwrap();
+    ///
+    /// let mut atlases = HashMap::default();
+    /// atlases.insert(1, Handle::weak(HandleId::random::<TextureAtlas>()));
+    ///
+    /// let level = &project.levels[0];
+    /// let mut tilemap = level.to_tilemap(&atlases).unwrap();
+    /// let int_grid = level.int_grid(&tilemap);
+    ///
+    /// // The bottom left cell of the collisions, which is the lowest layer.
+    /// assert_eq!(int_grid.get((0, 0), 0), Some(&3));
+    /// assert_eq!(tilemap.get_tile((0, 0), 0), None);
+    /// ```
+    ///
+    /// [`to_tilemap`]: LdtkLevel::to_tilemap
+    pub fn int_grid(&self, tilemap: &Tilemap) -> TileData<u32> {
+        let mut int_grid = TileData::new(tilemap);
+        for (z_order, layer) in self.layers.iter().enumerate() {
+            for tile in layer.tiles.iter() {
+                if let Some(value) = tile.value {
+                    int_grid.insert(tile.point, z_order, value);
+                }
+            }
+        }
+        int_grid
+    }
+
+    /// Converts a level of the project file.
+    fn from_raw(raw: RawLevel, tilesets: &[LdtkTileset]) -> LdtkResult<LdtkLevel> {
+        let identifier = raw.identifier;
+        let layer_instances = match raw.layer_instances {
+            Some(layer_instances) => layer_instances,
+            None => return Err(ErrorKind::ExternalLevel(identifier).into()),
+        };
+
+        let mut level = LdtkLevel {
+            identifier,
+            uid: raw.uid,
+            world_position: Point2::new(raw.world_x, -raw.world_y),
+            grid_size: 0,
+            layers: Vec::new(),
+            entities: Vec::new(),
+        };
+
+        // LDtk lists the layers from the top to the bottom.
+        for layer in layer_instances.into_iter().rev() {
+            if level.grid_size == 0 {
+                level.grid_size = layer.grid_size;
+            }
+            let height = layer.c_hei as i32;
+            let flip_row = |x: i32, y: i32| Point2::new(x, height - 1 - y);
+
+            if layer.kind == "Entities" {
+                for entity in layer.entity_instances.into_iter() {
+                    level.entities.push(LdtkEntity {
+                        identifier: entity.identifier,
+                        layer: layer.identifier.clone(),
+                        point: flip_row(entity.grid[0], entity.grid[1]),
+                        position: Vec2::new(
+                            entity.px[0] as f32,
+                            (height * layer.grid_size as i32 - entity.px[1]) as f32,
+                        ),
+                        width: entity.width,
+                        height: entity.height,
+                        fields: entity
+                            .field_instances
+                            .into_iter()
+                            .map(|field| (field.identifier, field.value))
+                            .collect(),
+                    });
+                }
+                continue;
+            }
+
+            let tileset = layer
+                .tileset_def_uid
+                .map(|uid| {
+                    tilesets
+                        .iter()
+                        .find(|tileset| tileset.uid == uid)
+                        .ok_or(ErrorKind::MissingTileset(uid))
+                })
+                .transpose()?;
+            let grid_size = layer.grid_size.max(1) as i32;
+            let width = layer.c_wid.max(1) as i32;
+            let value_at = |point: Point2| -> Option<u32> {
+                let index = (height - 1 - point.y) * width + point.x;
+                layer
+                    .int_grid_csv
+                    .get(index as usize)
+                    .filter(|value| **value > 0)
+                    .map(|value| *value as u32)
+            };
+
+            let mut tiles: Vec<LdtkTile> = Vec::new();
+            if let Some(tileset) = tileset {
+                for tile in layer.grid_tiles.iter().chain(layer.auto_layer_tiles.iter()) {
+                    let point = flip_row(tile.px[0] / grid_size, tile.px[1] / grid_size);
+                    tiles.push(LdtkTile {
+                        point,
+                        sprite_index: tileset.sprite_index(tile.src),
+                        value: value_at(point),
+                        flip_x: tile.f & 1 != 0,
+                        flip_y: tile.f & 2 != 0,
+                    });
+                }
+            } else {
+                // An IntGrid layer without auto rules only has its values,
+                // which are kept in the tiles without a sprite.
+                for (index, value) in layer.int_grid_csv.iter().enumerate() {
+                    if *value > 0 {
+                        let index = index as i32;
+                        tiles.push(LdtkTile {
+                            point: flip_row(index % width, index / width),
+                            sprite_index: 0,
+                            value: Some(*value as u32),
+                            flip_x: false,
+                            flip_y: false,
+                        });
+                    }
+                }
+            }
+
+            let area = (layer.c_wid * layer.c_hei) as usize;
+            level.layers.push(LdtkLayer {
+                identifier: layer.identifier,
+                kind: LayerKind::for_tile_count(tiles.len(), area),
+                tileset: layer.tileset_def_uid,
+                visible: layer.visible,
+                tint: Color::rgba(1.0, 1.0, 1.0, layer.opacity),
+                tiles,
+            });
+        }
+
+        Ok(level)
+    }
+}
+
+/// Returns `true`, which is the default of a missing visibility.
+fn visible_by_default() -> bool {
+    true
+}
+
+/// Returns `1.0`, which is the default of a missing opacity.
+fn opaque_by_default() -> f32 {
+    1.0
+}
+
+/// The part of an LDtk project file that is imported.
+#[derive(Deserialize)]
+struct RawProject {
+    /// The definitions of the project.
+    defs: RawDefs,
+    /// The levels of the project.
+    levels: Vec<RawLevel>,
+}
+
+/// The definitions of an LDtk project.
+#[derive(Deserialize)]
+struct RawDefs {
+    /// The tileset definitions.
+    #[serde(default)]
+    tilesets: Vec<RawTileset>,
+}
+
+/// A tileset definition.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTileset {
+    /// The identifier of the tileset.
+    identifier: String,
+    /// The unique ID of the tileset.
+    uid: i32,
+    /// The path of the image relative to the project.
+    rel_path: Option<String>,
+    /// The width of the image in pixels.
+    #[serde(default)]
+    px_wid: u32,
+    /// The height of the image in pixels.
+    #[serde(default)]
+    px_hei: u32,
+    /// The size of a tile in pixels.
+    tile_grid_size: u32,
+    /// The space between tiles in pixels.
+    #[serde(default)]
+    spacing: u32,
+    /// The space around the edge of the image in pixels.
+    #[serde(default)]
+    padding: u32,
+}
+
+/// A level.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLevel {
+    /// The identifier of the level.
+    identifier: String,
+    /// The unique ID of the level.
+    uid: i32,
+    /// The horizontal position of the level in the world in pixels.
+    #[serde(default)]
+    world_x: i32,
+    /// The vertical position of the level in the world in pixels.
+    #[serde(default)]
+    world_y: i32,
+    /// The layers of the level from the top to the bottom, which is `null` if
+    /// the level is saved in a separate file.
+    layer_instances: Option<Vec<RawLayer>>,
+}
+
+/// A layer of a level.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLayer {
+    /// The identifier of the layer.
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    /// The type of the layer.
+    #[serde(rename = "__type")]
+    kind: String,
+    /// The width of the layer in cells.
+    #[serde(rename = "__cWid")]
+    c_wid: u32,
+    /// The height of the layer in cells.
+    #[serde(rename = "__cHei")]
+    c_hei: u32,
+    /// The size of a cell in pixels.
+    #[serde(rename = "__gridSize")]
+    grid_size: u32,
+    /// The opacity of the layer.
+    #[serde(rename = "__opacity", default = "opaque_by_default")]
+    opacity: f32,
+    /// The UID of the tileset of the layer.
+    #[serde(rename = "__tilesetDefUid")]
+    tileset_def_uid: Option<i32>,
+    /// If the layer is visible.
+    #[serde(default = "visible_by_default")]
+    visible: bool,
+    /// The IntGrid values of every cell, from the top left.
+    #[serde(default)]
+    int_grid_csv: Vec<i32>,
+    /// The tiles placed by the auto layer rules.
+    #[serde(default)]
+    auto_layer_tiles: Vec<RawTile>,
+    /// The tiles placed by hand.
+    #[serde(default)]
+    grid_tiles: Vec<RawTile>,
+    /// The entities of the layer.
+    #[serde(default)]
+    entity_instances: Vec<RawEntity>,
+}
+
+/// A tile of a layer.
+#[derive(Deserialize)]
+struct RawTile {
+    /// The position of the tile in the layer in pixels.
+    px: [i32; 2],
+    /// The position of the tile in the tileset image in pixels.
+    src: [u32; 2],
+    /// The flip bits, `1` is horizontal and `2` is vertical.
+    #[serde(default)]
+    f: u8,
+}
+
+/// An entity of a layer.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawEntity {
+    /// The identifier of the entity definition.
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    /// The cell of the entity.
+    #[serde(rename = "__grid")]
+    grid: [i32; 2],
+    /// The position of the entity in the layer in pixels.
+    px: [i32; 2],
+    /// The width of the entity in pixels.
+    #[serde(default)]
+    width: u32,
+    /// The height of the entity in pixels.
+    #[serde(default)]
+    height: u32,
+    /// The fields of the entity.
+    #[serde(default)]
+    field_instances: Vec<RawField>,
+}
+
+/// A field of an entity.
+#[derive(Deserialize)]
+struct RawField {
+    /// The identifier of the field.
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    /// The value of the field.
+    #[serde(rename = "__value")]
+    value: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = r#"{
+        "defs": { "tilesets": [{
+            "identifier": "Tiles", "uid": 1, "relPath": "tiles.png",
+            "pxWid": 36, "pxHei": 36, "tileGridSize": 16, "spacing": 2, "padding": 1
+        }] },
+        "levels": [{ "identifier": "Level_0", "uid": 7, "worldX": 64, "worldY": 32,
+            "layerInstances": [
+                {
+                    "__identifier": "Things", "__type": "Entities",
+                    "__cWid": 2, "__cHei": 2, "__gridSize": 16,
+                    "entityInstances": [{
+                        "__identifier": "Player", "__grid": [1, 0], "px": [24, 8],
+                        "width": 16, "height": 16,
+                        "fieldInstances": [{ "__identifier": "health", "__value": 3 }]
+                    }]
+                },
+                {
+                    "__identifier": "Walls", "__type": "IntGrid",
+                    "__cWid": 2, "__cHei": 2, "__gridSize": 16, "__tilesetDefUid": 1,
+                    "__opacity": 0.5,
+                    "intGridCsv": [0, 2, 0, 0],
+                    "autoLayerTiles": [{ "px": [16, 0], "src": [19, 1], "f": 1 }]
+                },
+                {
+                    "__identifier": "Collisions", "__type": "IntGrid",
+                    "__cWid": 2, "__cHei": 2, "__gridSize": 16, "visible": false,
+                    "intGridCsv": [1, 1, 1, 0]
+                }
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn layers_from_bottom_to_top() {
+        let project = LdtkProject::from_reader(PROJECT.as_bytes()).unwrap();
+        let level = &project.levels[0];
+
+        assert_eq!(level.world_position, Point2::new(64, -32));
+        assert_eq!(level.grid_size, 16);
+        assert_eq!(level.layers.len(), 2);
+        assert_eq!(level.layers[0].identifier, "Collisions");
+        assert_eq!(level.layers[1].identifier, "Walls");
+    }
+
+    #[test]
+    fn int_grid_without_tileset() {
+        let project = LdtkProject::from_reader(PROJECT.as_bytes()).unwrap();
+        let layer = &project.levels[0].layers[0];
+
+        assert_eq!(layer.kind, LayerKind::Dense);
+        assert!(!layer.visible);
+        assert_eq!(layer.tint, Color::rgba(1.0, 1.0, 1.0, 1.0));
+        let points: Vec<(Point2, Option<u32>)> = layer
+            .tiles
+            .iter()
+            .map(|tile| (tile.point, tile.value))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                (Point2::new(0, 1), Some(1)),
+                (Point2::new(1, 1), Some(1)),
+                (Point2::new(0, 0), Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn int_grid_values() {
+        let mut project = LdtkProject::from_reader(PROJECT.as_bytes()).unwrap();
+        project.levels[0].layers[0].visible = true;
+        let mut atlases = HashMap::default();
+        atlases.insert(1, Handle::weak(HandleId::random::<TextureAtlas>()));
+
+        let level = &project.levels[0];
+        let mut tilemap = level.to_tilemap(&atlases).unwrap();
+        let int_grid = level.int_grid(&tilemap);
+
+        // The values of the layer without a tileset are only in the data.
+        assert_eq!(tilemap.get_tile((0, 0), 0), None);
+        assert_eq!(int_grid.get((0, 0), 0), Some(&1));
+        assert_eq!(int_grid.get((1, 0), 0), None);
+        assert_eq!(tilemap.get_tile((1, 1), 1).unwrap().index, 1);
+        assert_eq!(tilemap.get_terrain((1, 1), 1), Some(2));
+        assert_eq!(int_grid.get((1, 1), 1), Some(&2));
+    }
+
+    #[test]
+    fn auto_layer_tiles() {
+        let project = LdtkProject::from_reader(PROJECT.as_bytes()).unwrap();
+        let layer = &project.levels[0].layers[1];
+
+        assert_eq!(layer.kind, LayerKind::Sparse);
+        assert_eq!(layer.tint, Color::rgba(1.0, 1.0, 1.0, 0.5));
+        assert_eq!(
+            layer.tiles,
+            vec![LdtkTile {
+                point: Point2::new(1, 1),
+                sprite_index: 1,
+                value: Some(2),
+                flip_x: true,
+                flip_y: false,
+            }]
+        );
+        assert_eq!(layer.tiles[0].flip(), TileFlip::HORIZONTAL);
+    }
+
+    #[test]
+    fn entities() {
+        let project = LdtkProject::from_reader(PROJECT.as_bytes()).unwrap();
+        let entity = &project.levels[0].entities[0];
+
+        assert_eq!(entity.identifier, "Player");
+        assert_eq!(entity.layer, "Things");
+        assert_eq!(entity.point, Point2::new(1, 1));
+        assert_eq!(entity.position, Vec2::new(24.0, 24.0));
+        assert_eq!(entity.fields.get("health"), Some(&Value::from(3)));
+    }
+
+    #[test]
+    fn mixed_tilesets() {
+        let mut project = LdtkProject::from_reader(PROJECT.as_bytes()).unwrap();
+        project.levels[0].layers[0].tileset = Some(2);
+
+        assert_eq!(
+            project.levels[0]
+                .to_tilemap(&HashMap::default())
+                .unwrap_err()
+                .to_string(),
+            "the layers of level `Level_0` use more than one tileset"
+        );
+    }
+
+    #[test]
+    fn zero_grid_size() {
+        let ldtk = PROJECT.replace(r#""tileGridSize": 16"#, r#""tileGridSize": 0"#);
+
+        assert_eq!(
+            LdtkProject::from_reader(ldtk.as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "tileset `Tiles` has a grid size of zero"
+        );
+    }
+}
@@ -33,6 +33,36 @@
 //! bevy_tilemap = { version = "0.2", features = ["serde"] }
 //! ```
 //!
+//! # Tiled support
+//!
+//! Maps made with the [Tiled] map editor can be imported with the **"tiled"**
+//! feature, see the [`tiled`] module.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.2", features = ["tiled"] }
+//! ```
+//!
+//! # LDtk support
+//!
+//! Projects made with the [LDtk] level editor can be imported with the
+//! **"ldtk"** feature, see the [`ldtk`] module.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.2", features = ["ldtk"] }
+//! ```
+//!
+//! # Asset loading
+//!
+//! Tilemaps can be loaded from `.tilemap` files with the asset server with
+//! the **"loader"** feature, see the [`loader`] module.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.2", features = ["loader"] }
+//! ```
+//!
 //! # Extra types feature
 //!
 //! Internally, the library uses [`Point2`], [`Point3`], [`Dimension2`] and
@@ -50,6 +80,11 @@
 //! bevy_tilemap = { version = "0.2", features = ["types"] }
 //! ```
 //!
+//! [Tiled]: https://www.mapeditor.org/
+//! [LDtk]: https://ldtk.io/
+//! [`ldtk`]: crate::ldtk
+//! [`loader`]: crate::loader
+//! [`tiled`]: crate::tiled
 //! [`Point2`]: crate::point::Point2
 //! [`Point3`]: crate::point::Point3
 //! [`Dimension2`]: crate::dimension::Dimension2
@@ -88,25 +123,48 @@ pub use bevy_tilemap_types::dimension;
 #[doc(inline)]
 pub use bevy_tilemap_types::point;
 
-pub mod default_plugin;
-// pub mod auto_tile;
+pub mod animation;
+pub mod auto_tile;
 pub mod chunk;
+#[cfg(feature = "serialize")]
+pub mod codec;
+pub mod default_plugin;
 /// Bundles of components for spawning entities.
 pub mod entity;
+pub mod fog;
+pub mod fov;
+pub mod generator;
+#[cfg(feature = "ldtk")]
+pub mod ldtk;
+#[cfg(feature = "loader")]
+pub mod loader;
 /// Meshes for rendering to vertices.
 mod mesh;
+pub mod pathfinding;
+pub mod picking;
 pub mod prelude;
+pub mod registry;
 /// Files and helpers for rendering.
 pub mod render;
+#[cfg(feature = "serialize")]
+pub mod save;
 /// The stages for the tilemap in the bevy app.
 pub mod stage {
     /// The tilemap stage, set to run before `POST_UPDATE` stage.
     pub const TILEMAP: &str = "tilemap";
 }
+pub mod stamp;
+#[cfg(feature = "serialize")]
+pub mod store;
+pub mod streamer;
 /// Tile traits to implement for a custom tile.
 pub mod tile;
+pub mod tile_data;
+#[cfg(feature = "tiled")]
+pub mod tiled;
 /// Map traits to implement for a custom map and a basic struct for use.
 pub mod tilemap;
+pub mod topology;
 
 use crate::{lib::*, render::TilemapRenderGraphBuilder, tilemap::Tilemap};
 
@@ -117,17 +175,35 @@ pub struct Tilemap2DPlugin;
 impl Plugin for Tilemap2DPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset::<Tilemap>()
+            .init_resource::<crate::registry::TileRegistry>()
             .add_stage_before(bevy::app::stage::POST_UPDATE, stage::TILEMAP)
             .add_system_to_stage(
                 stage::TILEMAP,
                 crate::tilemap::tilemap_auto_configure.system(),
             )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::streamer::chunk_streamer_system.system(),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::animation::tile_animation_system.system(),
+            )
             .add_system_to_stage(stage::TILEMAP, crate::tilemap::tilemap_system.system())
             .add_system_to_stage(
                 bevy::render::stage::DRAW,
                 crate::chunk::chunk_update_system.system(),
             );
 
+        #[cfg(feature = "loader")]
+        app.init_asset_loader::<crate::loader::TilemapLoader>()
+            .init_resource::<crate::loader::TilemapAssetVersions>()
+            .add_system_to_stage(stage::TILEMAP, crate::loader::tilemap_asset_system.system())
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::loader::tilemap_hot_reload_system.system(),
+            );
+
         let resources = app.resources_mut();
         let mut render_graph = resources
             .get_mut::<RenderGraph>()
@@ -138,26 +214,41 @@ impl Plugin for Tilemap2DPlugin {
 
 /// A custom prelude around everything that we only need to use.
 mod lib {
+    #[cfg(feature = "loader")]
+    pub extern crate anyhow;
+    #[cfg(feature = "tiled")]
+    pub extern crate base64;
     pub extern crate bevy;
     pub extern crate bevy_tilemap_types;
+    #[cfg(feature = "serialize")]
+    pub extern crate bincode;
     pub extern crate bitflags;
+    #[cfg(feature = "tiled")]
+    pub extern crate flate2;
+    #[cfg(feature = "loader")]
+    pub extern crate ron;
     #[cfg(feature = "serde")]
     pub extern crate serde;
+    #[cfg(feature = "ldtk")]
+    pub extern crate serde_json;
     pub extern crate std;
+    #[cfg(feature = "tiled")]
+    pub extern crate xml;
 
     // Having to add this is a bug which is fixed in next Bevy (v > 0.3)
     use bevy::{
         app as bevy_app, asset as bevy_asset, core as bevy_core, ecs as bevy_ecs,
-        math as bevy_math, render as bevy_render, sprite as bevy_sprite,
+        math as bevy_math, render as bevy_render, sprite as bevy_sprite, tasks as bevy_tasks,
         transform as bevy_transform, type_registry as bevy_type_registry, utils as bevy_utils,
     };
 
     pub use self::{
         bevy_app::{AppBuilder, Events, Plugin, PluginGroup, PluginGroupBuilder},
-        bevy_asset::{AddAsset, Assets, Handle, HandleId},
-        bevy_core::{Byteable, Bytes},
+        bevy_asset::{AddAsset, AssetServer, Assets, Handle, HandleId},
+        bevy_core::{Byteable, Bytes, Time},
         bevy_ecs::{
             Bundle, Changed, Commands, Entity, IntoQuerySystem, Query, Res, ResMut, Resources,
+            Without,
         },
         bevy_math::{Vec2, Vec3},
         bevy_render::{
@@ -178,7 +269,8 @@ mod lib {
             shader::{Shader, ShaderStage, ShaderStages},
             texture::{Texture, TextureFormat},
         },
-        bevy_sprite::TextureAtlas,
+        bevy_sprite::{Rect, TextureAtlas},
+        bevy_tasks::{AsyncComputeTaskPool, TaskPool},
         bevy_transform::{
             components::{GlobalTransform, Parent, Transform},
             hierarchy::BuildChildren,
@@ -200,20 +292,24 @@ mod lib {
     pub use std::{
         boxed::Box,
         clone::Clone,
-        cmp::Ord,
+        cmp::{Ord, PartialEq},
         convert::{AsMut, AsRef, From, Into},
         default::Default,
         error::Error,
         fmt::{Debug, Display, Formatter, Result as FmtResult},
+        fs, io,
         iter::{Extend, IntoIterator, Iterator},
-        ops::{FnMut, FnOnce},
+        ops::{Deref, DerefMut, Drop, FnMut, FnOnce},
         option::Option::{self, *},
+        path::{Path, PathBuf},
         result::Result::{self, *},
+        string::{String, ToString},
+        sync::{Arc, Mutex},
         vec::Vec,
     };
 
     // Macros
-    pub use std::{assert_eq, panic, vec, write};
+    pub use std::{assert, assert_eq, format, panic, vec, write};
 
     #[cfg(debug_assertions)]
     #[allow(unused_imports)]
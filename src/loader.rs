@@ -0,0 +1,311 @@
+//! Loading of tilemaps from files with the asset server.
+//!
+//! The native tilemap format is a [RON] file with the `.tilemap` extension,
+//! which is a serialized [`TilemapFile`]. It holds the tilemap with all of its
+//! chunks and tiles, and a [`TextureAtlasSource`] which references the texture
+//! atlas by asset path.
+//!
+//! ```ron
+//! (
+//!     texture_atlas: Grid(
+//!         texture: "textures/tiles.png",
+//!         tile_size: (16.0, 16.0),
+//!         columns: 8,
+//!         rows: 8,
+//!     ),
+//!     tilemap: (
+//!         // The serialized `Tilemap`.
+//!     ),
+//! )
+//! ```
+//!
+//! Loading a `.tilemap` file with the [`AssetServer`] results in a `Tilemap`
+//! asset. An entity which has a `Handle<Tilemap>` and a transform gets a copy
+//! of the tilemap inserted as a component once the asset is loaded, with all
+//! of its chunks spawned. A file with chunks outside of the dimensions of its
+//! tilemap fails to load.
+//!
+//! # Hot reloading
+//!
+//! If the asset server watches for changes, a tilemap file that is modified
+//! while the game runs is reloaded into every entity with its handle. The new
+//! version is compared to the previous version of the file and only the chunk
+//! layers that had changed are replaced and re-rendered. All other chunks and
+//! layers keep any changes made to them at runtime, and nothing is respawned
+//! unless the topology, dimensions, layers or texture atlas had changed. A
+//! modified file which has chunks out of bounds is not applied, the entities
+//! keep the previous version until the file is fixed.
+//!
+//! This module requires the **"loader"** feature.
+//!
+//! # Examples
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_tilemap::prelude::*;
+//!
+//! fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     // Optional, to reload the tilemap when the file is modified.
+//!     asset_server.watch_for_changes().unwrap();
+//!
+//!     let tilemap: Handle<Tilemap> = asset_server.load("maps/level.tilemap");
+//!     commands.spawn((tilemap, Transform::default(), GlobalTransform::default()));
+//! }
+//!
+//! App::build()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(TilemapDefaultPlugins)
+//!     .add_startup_system(setup.system())
+//!     .run()
+//! ```
+//!
+//! [RON]: https://github.com/ron-rs/ron
+//! [`TilemapFile`]: TilemapFile
+//! [`TextureAtlasSource`]: TextureAtlasSource
+//! [`AssetServer`]: bevy::asset::AssetServer
+
+use crate::{
+    lib::{
+        bevy::{
+            app::EventReader,
+            asset::{AssetEvent, AssetLoader, AssetPath, LoadContext, LoadedAsset},
+            ecs::Local,
+            utils::BoxedFuture,
+        },
+        *,
+    },
+    tilemap::Tilemap,
+};
+
+/// The label of the texture atlas which is constructed from a grid.
+const TEXTURE_ATLAS_LABEL: &str = "texture_atlas";
+
+/// The source of the texture atlas of a tilemap file.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TextureAtlasSource {
+    /// The asset path of a texture atlas.
+    ///
+    /// The texture atlas is not loaded with the tilemap, it must be loaded or
+    /// set in `Assets<TextureAtlas>` with the handle of this path.
+    Path(String),
+    /// A texture which is split into a grid of tiles of the same size.
+    ///
+    /// The texture is loaded with the tilemap and the texture atlas is added
+    /// as a labeled asset of the tilemap file.
+    Grid {
+        /// The asset path of the texture.
+        texture: String,
+        /// The size of a tile in pixels.
+        tile_size: (f32, f32),
+        /// The amount of columns of tiles.
+        columns: usize,
+        /// The amount of rows of tiles.
+        rows: usize,
+    },
+}
+
+/// The contents of a native tilemap file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TilemapFile {
+    /// The source of the texture atlas of the tilemap.
+    pub texture_atlas: TextureAtlasSource,
+    /// The tilemap with its chunks and tiles.
+    pub tilemap: Tilemap,
+}
+
+impl TilemapFile {
+    /// Serializes the tilemap file into the RON format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tilemap could not be serialized.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::loader::{TextureAtlasSource, TilemapFile};
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // This must be set in Asset<TextureAtlas>.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let file = TilemapFile {
+    ///     texture_atlas: TextureAtlasSource::Path("textures/tiles.atlas".to_string()),
+    ///     tilemap: Tilemap::new(texture_atlas_handle),
+    /// };
+    ///
+    /// let ron = file.to_ron().unwrap();
+    /// ```
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+/// An asset loader of native tilemap files.
+#[derive(Default)]
+pub struct TilemapLoader;
+
+impl AssetLoader for TilemapLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let TilemapFile {
+                texture_atlas,
+                mut tilemap,
+            } = ron::de::from_bytes(bytes)?;
+            tilemap.check_chunks()?;
+
+            let mut dependencies = Vec::new();
+            let handle = match texture_atlas {
+                TextureAtlasSource::Path(path) => {
+                    load_context.get_handle(AssetPath::new(PathBuf::from(path), None))
+                }
+                TextureAtlasSource::Grid {
+                    texture,
+                    tile_size,
+                    columns,
+                    rows,
+                } => {
+                    let texture_path = AssetPath::new(PathBuf::from(texture), None);
+                    let atlas = TextureAtlas::from_grid(
+                        load_context.get_handle(texture_path.clone()),
+                        Vec2::new(tile_size.0, tile_size.1),
+                        columns,
+                        rows,
+                    );
+                    dependencies.push(texture_path);
+                    load_context.set_labeled_asset(TEXTURE_ATLAS_LABEL, LoadedAsset::new(atlas));
+                    load_context.get_handle(AssetPath::new_ref(
+                        load_context.path(),
+                        Some(TEXTURE_ATLAS_LABEL),
+                    ))
+                }
+            };
+            tilemap.set_texture_atlas(handle);
+
+            let mut asset = LoadedAsset::new(tilemap);
+            for dependency in dependencies.into_iter() {
+                asset = asset.with_dependency(dependency);
+            }
+            load_context.set_default_asset(asset);
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tilemap"]
+    }
+}
+
+/// The versions of the tilemap assets which were last applied to each
+/// entity, which hot reloading compares new versions against.
+#[derive(Default)]
+pub(crate) struct TilemapAssetVersions(HashMap<Entity, Tilemap>);
+
+/// Inserts a copy of every loaded tilemap asset into the entities with its
+/// handle, spawning all of its chunks.
+///
+/// A tilemap which has chunks that can not be spawned is not inserted.
+pub(crate) fn tilemap_asset_system(
+    mut commands: Commands,
+    tilemaps: Res<Assets<Tilemap>>,
+    mut versions: ResMut<TilemapAssetVersions>,
+    query: Query<Without<Tilemap, (Entity, &Handle<Tilemap>)>>,
+) {
+    for (entity, handle) in query.iter() {
+        let asset = match tilemaps.get(handle) {
+            Some(asset) => asset,
+            None => continue,
+        };
+
+        // Loaded tilemaps are checked by the loader, but a tilemap asset that
+        // was added directly may still have chunks out of bounds.
+        let mut tilemap = asset.duplicate();
+        if tilemap.spawn_all_chunks().is_err() {
+            // TODO: Bevy log error when implemented
+            continue;
+        }
+        versions.0.insert(entity, asset.duplicate());
+        commands.insert_one(entity, tilemap);
+    }
+}
+
+/// Reloads every tilemap whose asset had been modified, applying only the
+/// changes between the previous and the new version of the asset.
+///
+/// An entity whose tilemap fails to reload is left untouched and keeps the
+/// previous version, which the next modification is compared against.
+pub(crate) fn tilemap_hot_reload_system(
+    mut reader: Local<EventReader<AssetEvent<Tilemap>>>,
+    events: Res<Events<AssetEvent<Tilemap>>>,
+    tilemaps: Res<Assets<Tilemap>>,
+    mut versions: ResMut<TilemapAssetVersions>,
+    mut query: Query<(Entity, &Handle<Tilemap>, &mut Tilemap)>,
+) {
+    for event in reader.iter(&events) {
+        let handle = match event {
+            AssetEvent::Modified { handle } => handle,
+            _ => continue,
+        };
+        let new = match tilemaps.get(handle) {
+            Some(new) => new,
+            None => continue,
+        };
+
+        for (entity, tilemap_handle, mut tilemap) in query.iter_mut() {
+            if tilemap_handle != handle {
+                continue;
+            }
+            // Tilemaps that were never inserted from the asset have nothing
+            // to reload.
+            let old = match versions.0.get(&entity) {
+                Some(old) => old,
+                None => continue,
+            };
+            if tilemap.reload(old, new).is_err() {
+                // TODO: Bevy log error when implemented
+                continue;
+            }
+            versions.0.insert(entity, new.duplicate());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+
+    #[test]
+    fn round_trip() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        tilemap
+            .insert_tiles(vec![Tile::new((1, 1), 3), Tile::new((-40, 7), 5)])
+            .unwrap();
+        let file = TilemapFile {
+            texture_atlas: TextureAtlasSource::Grid {
+                texture: "textures/tiles.png".to_string(),
+                tile_size: (16.0, 16.0),
+                columns: 8,
+                rows: 8,
+            },
+            tilemap,
+        };
+
+        let ron = file.to_ron().unwrap();
+        let mut loaded: TilemapFile = ron::de::from_str(&ron).unwrap();
+
+        assert_eq!(loaded.texture_atlas, file.texture_atlas);
+        assert_eq!(loaded.tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+        assert_eq!(loaded.tilemap.get_tile((-40, 7), 0).unwrap().index, 5);
+        assert!(loaded.tilemap.get_tile((2, 2), 0).is_none());
+    }
+}
@@ -12,6 +12,8 @@ impl ChunkMesh {
     pub(crate) const ATTRIBUTE_TILE_INDEX: &'static str = "Vertex_Tile_Index";
     /// Vertex attribute of the tile's color.
     pub(crate) const ATTRIBUTE_TILE_COLOR: &'static str = "Vertex_Tile_Color";
+    /// Vertex attribute of the tile's flip flags.
+    pub(crate) const ATTRIBUTE_TILE_FLIP: &'static str = "Vertex_Tile_Flip";
 
     /// Constructs a new chunk mesh.
     pub(crate) fn new(dimensions: Dimension2) -> ChunkMesh {
@@ -50,12 +52,14 @@ impl From<&ChunkMesh> for Mesh {
 
         let tile_indexes = vec![0.; vertices.len()];
         let tile_colors: Vec<[f32; 4]> = vec![Color::WHITE.into(); vertices.len()];
+        let tile_flips = vec![0.; vertices.len()];
 
         let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
         mesh.set_indices(Some(indices));
         mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, vertices.into());
         mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, tile_indexes.into());
         mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, tile_colors.into());
+        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_FLIP, tile_flips.into());
 
         mesh
     }
@@ -0,0 +1,619 @@
+//! Pathfinding over the tiles of a tilemap.
+//!
+//! A [`Pathfinder`] searches the tiles of a [`Tilemap`] directly, moving
+//! between the neighbours of the topology of the tilemap as returned by
+//! [`Tilemap::neighbours`]. The cost of moving onto a point is decided by a
+//! cost function that is called with every tile at the point and its z order.
+//! The function returns the cost of the tile, or `None` if the tile can not be
+//! passed. The costs of all the tiles at a point are added together, and a
+//! point without any tiles can not be passed.
+//!
+//! Two searches are provided:
+//!
+//! * [`Pathfinder::astar`] finds the cheapest path from a start to a goal with
+//! A*, using the [`distance`] between points in the topology of the tilemap
+//! as the heuristic.
+//! * [`Pathfinder::dijkstra_map`] finds the cost of reaching every point from
+//! the closest of many sources, which is useful for moving many entities
+//! towards or away from the same targets.
+//!
+//! Paths are free to cross the boundaries of chunks. Points outside of the
+//! dimensions of the tilemap can never be passed. Points in chunks that are
+//! not loaded are treated as set by [`Pathfinder::unloaded_chunks`].
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::pathfinding::Pathfinder;
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! // A floor with a wall in the way.
+//! let floor = 0;
+//! let wall = 1;
+//! let mut tiles = Vec::new();
+//! for y in -5..5 {
+//!     for x in -5..5 {
+//!         let index = if x == 0 && y < 4 { wall } else { floor };
+//!         tiles.push(Tile::new((x, y), index));
+//!     }
+//! }
+//! tilemap.insert_tiles(tiles).unwrap();
+//!
+//! let path = Pathfinder::new(&tilemap, |tile, _| if tile.index == wall { None } else { Some(1) })
+//!     .astar((-2, 0), (2, 0))
+//!     .unwrap()
+//!     .unwrap();
+//!
+//! assert_eq!(path.points.first(), Some(&Point2::new(-2, 0)));
+//! assert_eq!(path.points.last(), Some(&Point2::new(2, 0)));
+//! assert!(path.points.contains(&Point2::new(0, 4)));
+//! assert_eq!(path.cost, 12);
+//! ```
+//!
+//! [`Pathfinder`]: Pathfinder
+//! [`Tilemap`]: crate::tilemap::Tilemap
+//! [`Tilemap::neighbours`]: crate::tilemap::Tilemap::neighbours
+//! [`Pathfinder::astar`]: Pathfinder::astar
+//! [`distance`]: crate::topology::distance
+//! [`Pathfinder::dijkstra_map`]: Pathfinder::dijkstra_map
+//! [`Pathfinder::unloaded_chunks`]: Pathfinder::unloaded_chunks
+
+use crate::{
+    lib::{
+        std::{cmp::Reverse, collections::BinaryHeap},
+        *,
+    },
+    render::GridTopology,
+    tile::RawTile,
+    tilemap::Tilemap,
+    topology,
+};
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+/// The kinds of errors that can occur.
+enum ErrorKind {
+    /// The search reached a chunk that is not loaded.
+    UnloadedChunk(Point2),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use ErrorKind::*;
+        match self {
+            UnloadedChunk(point) => write!(
+                f,
+                "the search reached the chunk {} which is not loaded",
+                point
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+/// The error type for pathfinding.
+pub struct PathError(Box<ErrorKind>);
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for PathError {}
+
+impl From<ErrorKind> for PathError {
+    fn from(kind: ErrorKind) -> PathError {
+        PathError(Box::new(kind))
+    }
+}
+
+/// A result type for pathfinding.
+pub type PathResult<T> = Result<T, PathError>;
+
+/// How a search treats points in chunks that are not loaded.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum UnloadedChunks {
+    /// Points in unloaded chunks can not be passed, as if they were walls.
+    Impassable,
+    /// The search fails with an error once it reaches an unloaded chunk.
+    Error,
+}
+
+impl Default for UnloadedChunks {
+    fn default() -> Self {
+        UnloadedChunks::Impassable
+    }
+}
+
+/// A path that was found between two points.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Path {
+    /// The points of the path, from the start to the goal, both included.
+    pub points: Vec<Point2>,
+    /// The total cost of moving along the path.
+    pub cost: u32,
+}
+
+/// The costs of reaching points from the closest of many sources.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DijkstraMap {
+    /// The type of grid the map was made for.
+    topology: GridTopology,
+    /// If diagonal tiles are neighbours on a square grid.
+    diagonals: bool,
+    /// The cost of every point that can be reached.
+    costs: HashMap<Point2, u32>,
+}
+
+impl DijkstraMap {
+    /// Returns the cost of reaching a point from the closest source, or `None`
+    /// if it can not be reached.
+    pub fn cost<P: Into<Point2>>(&self, point: P) -> Option<u32> {
+        self.costs.get(&point.into()).copied()
+    }
+
+    /// Returns the neighbour of a point that is the cheapest to reach, if it
+    /// is closer to a source than the point.
+    ///
+    /// Following the steps from any point that can be reached leads to the
+    /// closest source.
+    pub fn step<P: Into<Point2>>(&self, point: P) -> Option<Point2> {
+        let point = point.into();
+        let cost = self.cost(point)?;
+        topology::neighbours(self.topology, point, self.diagonals)
+            .into_iter()
+            .filter_map(|neighbour| self.cost(neighbour).map(|cost| (cost, neighbour)))
+            .filter(|(neighbour_cost, _)| *neighbour_cost < cost)
+            .min()
+            .map(|(_, neighbour)| neighbour)
+    }
+
+    /// Returns the costs of all the points that can be reached.
+    pub fn costs(&self) -> &HashMap<Point2, u32> {
+        &self.costs
+    }
+}
+
+/// Searches for paths over the tiles of a tilemap.
+///
+/// The cost function is called with every tile at a point and the z order of
+/// the tile. Costs of 0 are treated as 1, so that the heuristic of A* never
+/// overestimates the cost of a path.
+pub struct Pathfinder<'a, F> {
+    /// The tilemap that is searched.
+    tilemap: &'a Tilemap,
+    /// The cost of passing a tile.
+    cost: F,
+    /// How points in unloaded chunks are treated.
+    unloaded: UnloadedChunks,
+    /// The highest cost that is searched up to, if any.
+    max_cost: Option<u32>,
+    /// The costs of moving onto the points that were looked at.
+    point_costs: HashMap<Point2, Option<u32>>,
+}
+
+impl<'a, F> Pathfinder<'a, F>
+where
+    F: FnMut(&RawTile, usize) -> Option<u32>,
+{
+    /// Constructs a new pathfinder over a tilemap with a cost function.
+    ///
+    /// By default points in unloaded chunks can not be passed and there is no
+    /// limit on the cost of the search.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::pathfinding::Pathfinder;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// // Water on the second layer can not be passed, anything else costs 1.
+    /// let water = 5;
+    /// let pathfinder = Pathfinder::new(&tilemap, |tile, z_order| {
+    ///     if z_order == 1 && tile.index == water {
+    ///         None
+    ///     } else {
+    ///         Some(1)
+    ///     }
+    /// });
+    /// ```
+    pub fn new(tilemap: &'a Tilemap, cost: F) -> Pathfinder<'a, F> {
+        Pathfinder {
+            tilemap,
+            cost,
+            unloaded: UnloadedChunks::default(),
+            max_cost: None,
+            point_costs: HashMap::default(),
+        }
+    }
+
+    /// Sets how points in chunks that are not loaded are treated.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::pathfinding::{Pathfinder, UnloadedChunks};
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let tiles = (0..16).map(|x| Tile::new((x, 0), 0)).collect::<Vec<_>>();
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// // The chunk (1, 0) starts at the point (16, 0).
+    /// let result = Pathfinder::new(&tilemap, |_, _| Some(1))
+    ///     .unloaded_chunks(UnloadedChunks::Error)
+    ///     .astar((0, 0), (20, 0));
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn unloaded_chunks(mut self, unloaded: UnloadedChunks) -> Self {
+        self.unloaded = unloaded;
+        self
+    }
+
+    /// Sets the highest cost that is searched up to.
+    ///
+    /// Points that cost more to reach are not searched, which keeps searches
+    /// on large or endless tilemaps bounded.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::pathfinding::Pathfinder;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let tiles = (-5..5).map(|x| Tile::new((x, 0), 0)).collect::<Vec<_>>();
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// let mut pathfinder = Pathfinder::new(&tilemap, |_, _| Some(1)).max_cost(3);
+    ///
+    /// assert!(pathfinder.astar((0, 0), (3, 0)).unwrap().is_some());
+    /// assert!(pathfinder.astar((0, 0), (4, 0)).unwrap().is_none());
+    /// ```
+    pub fn max_cost(mut self, max_cost: u32) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    /// Finds the cheapest path from a start to a goal with A*, returning
+    /// `None` if there is no path.
+    ///
+    /// The start does not need to be passable, the goal does.
+    ///
+    /// # Errors
+    ///
+    /// If the search reaches a chunk that is not loaded while unloaded chunks
+    /// are set to [`UnloadedChunks::Error`], an error is returned.
+    ///
+    /// [`UnloadedChunks::Error`]: UnloadedChunks::Error
+    pub fn astar<P: Into<Point2>>(&mut self, start: P, goal: P) -> PathResult<Option<Path>> {
+        let (start, goal) = (start.into(), goal.into());
+        let topology = self.tilemap.topology();
+        let diagonals = self.tilemap.diagonal_neighbours();
+
+        let mut open = BinaryHeap::new();
+        let mut best: HashMap<Point2, u32> = HashMap::default();
+        let mut came_from: HashMap<Point2, Point2> = HashMap::default();
+        open.push(Reverse((
+            topology::distance(topology, diagonals, start, goal),
+            0,
+            start,
+        )));
+        best.insert(start, 0);
+
+        while let Some(Reverse((_, cost, point))) = open.pop() {
+            if point == goal {
+                let mut points = vec![goal];
+                let mut point = goal;
+                while let Some(previous) = came_from.get(&point) {
+                    points.push(*previous);
+                    point = *previous;
+                }
+                points.reverse();
+                return Ok(Some(Path { points, cost }));
+            }
+            if best.get(&point).map_or(false, |best| cost > *best) {
+                continue;
+            }
+            for neighbour in topology::neighbours(topology, point, diagonals) {
+                let cost = match self.reach(neighbour, cost)? {
+                    Some(cost) => cost,
+                    None => continue,
+                };
+                if best.get(&neighbour).map_or(true, |best| cost < *best) {
+                    best.insert(neighbour, cost);
+                    came_from.insert(neighbour, point);
+                    let estimate = cost
+                        .saturating_add(topology::distance(topology, diagonals, neighbour, goal));
+                    open.push(Reverse((estimate, cost, neighbour)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds the cost of reaching every point from the closest of many
+    /// sources with Dijkstra's algorithm.
+    ///
+    /// The sources have a cost of 0 and do not need to be passable.
+    ///
+    /// # Errors
+    ///
+    /// If the search reaches a chunk that is not loaded while unloaded chunks
+    /// are set to [`UnloadedChunks::Error`], an error is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::pathfinding::Pathfinder;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let tiles = (-5..5).map(|x| Tile::new((x, 0), 0)).collect::<Vec<_>>();
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// let map = Pathfinder::new(&tilemap, |_, _| Some(1))
+    ///     .dijkstra_map(vec![(-5, 0), (4, 0)])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(map.cost((-3, 0)), Some(2));
+    /// assert_eq!(map.cost((1, 0)), Some(3));
+    /// assert_eq!(map.step((1, 0)), Some(Point2::new(2, 0)));
+    /// assert_eq!(map.cost((0, 1)), None);
+    /// ```
+    ///
+    /// [`UnloadedChunks::Error`]: UnloadedChunks::Error
+    pub fn dijkstra_map<P, I>(&mut self, sources: I) -> PathResult<DijkstraMap>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        let topology = self.tilemap.topology();
+        let diagonals = self.tilemap.diagonal_neighbours();
+
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<Point2, u32> = HashMap::default();
+        for source in sources.into_iter() {
+            let source = source.into();
+            costs.insert(source, 0);
+            open.push(Reverse((0, source)));
+        }
+
+        while let Some(Reverse((cost, point))) = open.pop() {
+            if costs.get(&point).map_or(false, |best| cost > *best) {
+                continue;
+            }
+            for neighbour in topology::neighbours(topology, point, diagonals) {
+                let cost = match self.reach(neighbour, cost)? {
+                    Some(cost) => cost,
+                    None => continue,
+                };
+                if costs.get(&neighbour).map_or(true, |best| cost < *best) {
+                    costs.insert(neighbour, cost);
+                    open.push(Reverse((cost, neighbour)));
+                }
+            }
+        }
+
+        Ok(DijkstraMap {
+            topology,
+            diagonals,
+            costs,
+        })
+    }
+
+    /// Returns the total cost of reaching a point from a neighbour that was
+    /// reached for a cost, or `None` if the point can not be passed or costs
+    /// more than the maximum.
+    fn reach(&mut self, point: Point2, cost: u32) -> PathResult<Option<u32>> {
+        let cost = self
+            .point_cost(point)?
+            .map(|point_cost| cost.saturating_add(point_cost));
+        Ok(cost.filter(|cost| self.max_cost.map_or(true, |max_cost| *cost <= max_cost)))
+    }
+
+    /// Returns the cost of moving onto a point, or `None` if it can not be
+    /// passed.
+    fn point_cost(&mut self, point: Point2) -> PathResult<Option<u32>> {
+        if let Some(cost) = self.point_costs.get(&point) {
+            return Ok(*cost);
+        }
+        let cost = match self.tilemap.chunk_and_index(point) {
+            Some((chunk, index)) => {
+                let mut total: Option<u32> = None;
+                for z_order in 0..self.tilemap.layer_count() {
+                    let tile = match chunk.get_tile(z_order, index) {
+                        Some(tile) => tile,
+                        None => continue,
+                    };
+                    match (self.cost)(tile, z_order) {
+                        Some(cost) => total = Some(total.unwrap_or(0).saturating_add(cost)),
+                        None => {
+                            total = None;
+                            break;
+                        }
+                    }
+                }
+                total.map(|total| total.max(1))
+            }
+            None => {
+                let chunk_point: Point2 = self.tilemap.point_to_chunk_point(point).into();
+                if self.tilemap.chunk_in_bounds(chunk_point)
+                    && self.unloaded == UnloadedChunks::Error
+                {
+                    return Err(ErrorKind::UnloadedChunk(chunk_point).into());
+                }
+                None
+            }
+        };
+        self.point_costs.insert(point, cost);
+        Ok(cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+
+    fn new_tilemap(topology: GridTopology) -> Tilemap {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .topology(topology)
+            .chunk_dimensions(4, 4)
+            .auto_chunk()
+            .finish()
+            .unwrap()
+    }
+
+    fn fill(tilemap: &mut Tilemap, radius: i32) {
+        let mut tiles = Vec::new();
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                tiles.push(Tile::new((x, y), 0));
+            }
+        }
+        tilemap.insert_tiles(tiles).unwrap();
+    }
+
+    #[test]
+    fn distance_matches_open_floor() {
+        use GridTopology::*;
+        for &topology in [
+            Square,
+            HexY,
+            HexX,
+            HexEvenRows,
+            HexOddRows,
+            HexEvenCols,
+            HexOddCols,
+        ]
+        .iter()
+        {
+            for &diagonals in [false, true].iter() {
+                let mut tilemap = new_tilemap(topology);
+                tilemap.set_diagonal_neighbours(diagonals);
+                fill(&mut tilemap, 12);
+                for &origin in [Point2::new(0, 0), Point2::new(1, -1)].iter() {
+                    let map = Pathfinder::new(&tilemap, |_, _| Some(1))
+                        .dijkstra_map(vec![origin])
+                        .unwrap();
+                    for y in -5..=5 {
+                        for x in -5..=5 {
+                            let point = Point2::new(x, y);
+                            assert_eq!(
+                                map.cost(point),
+                                Some(topology::distance(topology, diagonals, origin, point)),
+                                "{:?} {} {:?} {:?}",
+                                topology,
+                                diagonals,
+                                origin,
+                                point
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn astar_crosses_chunks() {
+        let mut tilemap = new_tilemap(GridTopology::Square);
+        fill(&mut tilemap, 10);
+        // A wall through several chunks with a single gap.
+        let wall: Vec<Tile> = (-10..=10)
+            .filter(|y| *y != -9)
+            .map(|y| Tile::with_z_order((3, y), 1, 1))
+            .collect();
+        tilemap.insert_tiles(wall).unwrap();
+
+        let path = Pathfinder::new(&tilemap, |tile, z_order| match (z_order, tile.index) {
+            (1, 1) => None,
+            _ => Some(1),
+        })
+        .astar((0, 8), (6, 8))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(path.cost, 6 + 17 * 2);
+        assert_eq!(path.points.len(), path.cost as usize + 1);
+        assert!(path.points.contains(&Point2::new(3, -9)));
+        for pair in path.points.windows(2) {
+            assert!(tilemap.neighbours(pair[0]).contains(&pair[1]));
+        }
+    }
+
+    #[test]
+    fn unloaded_chunks() {
+        let mut tilemap = new_tilemap(GridTopology::Square);
+        fill(&mut tilemap, 5);
+        tilemap.remove_chunk((1, 0)).unwrap();
+
+        // The chunk (1, 0) holds the points from (2, -2) to (5, 1).
+        let unloaded = |point: &Point2| point.x >= 2 && point.y >= -2 && point.y <= 1;
+        let path = Pathfinder::new(&tilemap, |_, _| Some(1))
+            .astar((0, 0), (5, 4))
+            .unwrap()
+            .unwrap();
+        assert!(!path.points.iter().any(unloaded));
+
+        let err = Pathfinder::new(&tilemap, |_, _| Some(1))
+            .unloaded_chunks(UnloadedChunks::Error)
+            .astar((0, 0), (5, 4))
+            .unwrap_err();
+        assert!(err.to_string().contains("(1, 0)"));
+    }
+
+    #[test]
+    fn costs_add_up_across_layers() {
+        let mut tilemap = new_tilemap(GridTopology::Square);
+        fill(&mut tilemap, 3);
+        tilemap
+            .insert_tiles(vec![Tile::with_z_order((1, 0), 2, 1)])
+            .unwrap();
+
+        let mut pathfinder =
+            Pathfinder::new(
+                &tilemap,
+                |tile, _| if tile.index == 2 { Some(5) } else { Some(0) },
+            );
+
+        // Costs of 0 are treated as 1, the tile with two layers costs 5.
+        let path = pathfinder.astar((0, 0), (2, 0)).unwrap().unwrap();
+        assert_eq!(path.cost, 4);
+        assert!(!path.points.contains(&Point2::new(1, 0)));
+        assert_eq!(pathfinder.astar((0, 0), (1, 0)).unwrap().unwrap().cost, 5);
+    }
+}
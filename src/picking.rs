@@ -0,0 +1,283 @@
+//! Picking of tiles with the mouse cursor through the active 2D camera.
+//!
+//! With the [`TilemapPickingPlugin`] added, a [`TilePicked`] event is sent
+//! whenever the cursor hovers over another tile of a tilemap, and whenever a
+//! mouse button is pressed over one. Every tilemap entity needs a `Transform`
+//! to be picked.
+//!
+//! The projection of the cursor into the world is done by
+//! [`cursor_to_world`], which can be used on its own as well.
+//!
+//! # Examples
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_tilemap::picking::{PickKind, TilePicked, TilemapPickingPlugin};
+//! use bevy_tilemap::prelude::*;
+//!
+//! fn paint(
+//!     mut reader: Local<EventReader<TilePicked>>,
+//!     events: Res<Events<TilePicked>>,
+//!     mut query: Query<&mut Tilemap>,
+//! ) {
+//!     for picked in reader.iter(&events) {
+//!         if picked.kind != PickKind::Clicked(MouseButton::Left) {
+//!             continue;
+//!         }
+//!         if let Ok(mut tilemap) = query.get_mut(picked.tilemap) {
+//!             tilemap.insert_tile(Tile::new(picked.point, 1)).ok();
+//!         }
+//!     }
+//! }
+//!
+//! App::build()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(TilemapDefaultPlugins)
+//!     .add_plugin(TilemapPickingPlugin)
+//!     .add_system(paint.system())
+//!     .run()
+//! ```
+//!
+//! [`TilemapPickingPlugin`]: TilemapPickingPlugin
+//! [`TilePicked`]: TilePicked
+//! [`cursor_to_world`]: cursor_to_world
+
+use crate::{
+    lib::{
+        bevy::{
+            app::EventReader,
+            ecs::Local,
+            input::{mouse::MouseButton, Input},
+            math::Vec4,
+            render::{
+                camera::{ActiveCameras, Camera},
+                render_graph::base::camera::CAMERA2D,
+            },
+            window::{CursorMoved, WindowId, Windows},
+        },
+        *,
+    },
+    tilemap::Tilemap,
+};
+
+/// The mouse buttons which tiles can be clicked with.
+const MOUSE_BUTTONS: [MouseButton; 3] =
+    [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+/// How a tile was picked.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PickKind {
+    /// The cursor moved over the tile.
+    Hovered,
+    /// A mouse button was pressed over the tile.
+    Clicked(MouseButton),
+}
+
+/// An event of a tile that was picked with the cursor.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TilePicked {
+    /// The entity of the tilemap.
+    pub tilemap: Entity,
+    /// The point of the tile.
+    pub point: Point2,
+    /// The highest z order that has a tile at the point, if any.
+    pub z_order: Option<usize>,
+    /// How the tile was picked.
+    pub kind: PickKind,
+}
+
+/// The plugin which sends [`TilePicked`] events.
+///
+/// [`TilePicked`]: TilePicked
+#[derive(Default)]
+pub struct TilemapPickingPlugin;
+
+impl Plugin for TilemapPickingPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<TilePicked>()
+            .add_system_to_stage(bevy::app::stage::PRE_UPDATE, tile_picking_system.system());
+    }
+}
+
+/// Projects a cursor position in a window into a world position, as seen by
+/// a camera.
+///
+/// The cursor position is in pixels from the bottom left of the window and
+/// the window size is in pixels.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use bevy::render::camera::Camera;
+/// use bevy_tilemap::picking::cursor_to_world;
+///
+/// let camera = Camera {
+///     projection_matrix: Mat4::orthographic_rh(-400.0, 400.0, -300.0, 300.0, 0.0, 1000.0),
+///     ..Default::default()
+/// };
+/// let camera_transform = GlobalTransform::from_translation(Vec3::new(100.0, 50.0, 0.0));
+/// let window_size = Vec2::new(800.0, 600.0);
+///
+/// let world = cursor_to_world(Vec2::new(400.0, 300.0), window_size, &camera, &camera_transform);
+///
+/// assert!((world - Vec2::new(100.0, 50.0)).length() < 0.001);
+/// ```
+pub fn cursor_to_world(
+    cursor: Vec2,
+    window_size: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Vec2 {
+    let ndc = cursor / window_size * 2.0 - Vec2::new(1.0, 1.0);
+    let matrix = camera_transform.compute_matrix() * camera.projection_matrix.inverse();
+    let position = matrix * Vec4::new(ndc.x(), ndc.y(), 0.0, 1.0);
+    Vec2::new(position.x() / position.w(), position.y() / position.w())
+}
+
+/// The state of picking between frames.
+#[derive(Default)]
+struct PickingState {
+    /// The reader of cursor events.
+    cursor_reader: EventReader<CursorMoved>,
+    /// The last position of the cursor and the window it is in.
+    cursor: Option<(WindowId, Vec2)>,
+    /// The tile that is hovered over in every tilemap.
+    hovered: HashMap<Entity, Point2>,
+}
+
+/// Sends events for the tiles that are hovered over or clicked with the
+/// cursor through the active 2D camera.
+#[allow(clippy::too_many_arguments)]
+fn tile_picking_system(
+    mut state: Local<PickingState>,
+    cursor_events: Res<Events<CursorMoved>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    active_cameras: Res<ActiveCameras>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    tilemaps: Query<(Entity, &Tilemap, &Transform)>,
+    mut picked: ResMut<Events<TilePicked>>,
+) {
+    if let Some(event) = state.cursor_reader.latest(&cursor_events) {
+        state.cursor = Some((event.id, event.position));
+    }
+    let (window_id, cursor) = match state.cursor {
+        Some(cursor) => cursor,
+        None => return,
+    };
+    let (camera, camera_transform) = match active_cameras
+        .get(CAMERA2D)
+        .and_then(|entity| cameras.get(entity).ok())
+    {
+        Some(camera) => camera,
+        None => return,
+    };
+    if camera.window != window_id {
+        return;
+    }
+    let window = match windows.get(window_id) {
+        Some(window) => window,
+        None => return,
+    };
+    let window_size = Vec2::new(window.width() as f32, window.height() as f32);
+    let world = cursor_to_world(cursor, window_size, camera, camera_transform);
+
+    for (entity, tilemap, transform) in tilemaps.iter() {
+        let point = tilemap.world_to_point(transform, world);
+        let z_order = tilemap.top_z_order(point);
+        if state.hovered.insert(entity, point) != Some(point) {
+            picked.send(TilePicked {
+                tilemap: entity,
+                point,
+                z_order,
+                kind: PickKind::Hovered,
+            });
+        }
+        for button in MOUSE_BUTTONS.iter() {
+            if mouse_buttons.just_pressed(*button) {
+                picked.send(TilePicked {
+                    tilemap: entity,
+                    point,
+                    z_order,
+                    kind: PickKind::Clicked(*button),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::bevy::math::Mat4;
+    use crate::tile::Tile;
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 0.001, "{:?} is not {:?}", a, b);
+    }
+
+    fn camera() -> Camera {
+        Camera {
+            projection_matrix: Mat4::orthographic_rh(-400.0, 400.0, -300.0, 300.0, 0.0, 1000.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn cursor_projects_through_the_camera() {
+        let window_size = Vec2::new(800.0, 600.0);
+        let mut camera_transform = GlobalTransform::from_translation(Vec3::new(100.0, 50.0, 0.0));
+
+        let corner = cursor_to_world(
+            Vec2::new(0.0, 0.0),
+            window_size,
+            &camera(),
+            &camera_transform,
+        );
+        assert_near(corner, Vec2::new(-300.0, -250.0));
+
+        camera_transform.scale = Vec3::new(2.0, 2.0, 1.0);
+        let corner = cursor_to_world(
+            Vec2::new(0.0, 0.0),
+            window_size,
+            &camera(),
+            &camera_transform,
+        );
+        assert_near(corner, Vec2::new(-700.0, -550.0));
+        let top_right = cursor_to_world(window_size, window_size, &camera(), &camera_transform);
+        assert_near(top_right, Vec2::new(900.0, 650.0));
+    }
+
+    #[test]
+    fn picks_the_top_tile() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .tile_dimensions(16, 16)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        tilemap
+            .insert_tiles(vec![
+                Tile::with_z_order((2, 3), 1, 0),
+                Tile::with_z_order((2, 3), 1, 2),
+                Tile::with_z_order((3, 3), 1, 0),
+            ])
+            .unwrap();
+        let camera_transform = GlobalTransform::default();
+        let tilemap_transform = Transform::default();
+
+        // The center of the window is the origin of the world.
+        let world = cursor_to_world(
+            Vec2::new(400.0 + 40.0, 300.0 + 56.0),
+            Vec2::new(800.0, 600.0),
+            &camera(),
+            &camera_transform,
+        );
+        let point = tilemap.world_to_point(&tilemap_transform, world);
+
+        assert_eq!(point, Point2::new(2, 3));
+        assert_eq!(tilemap.top_z_order(point), Some(2));
+        assert_eq!(tilemap.top_z_order(Point2::new(3, 3)), Some(0));
+        assert_eq!(tilemap.top_z_order(Point2::new(4, 3)), None);
+    }
+}
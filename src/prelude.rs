@@ -5,16 +5,25 @@
 //! The current version of this prelude (version 0) is located in
 //! [`bevy_tilemap::prelude::v0`], and re-exports the following.
 //!
+//! * [`bevy_tilemap::animation`]::[`TileAnimation`], the definition of an
+//! animation which animated tiles cycle through.
+//! * [`bevy_tilemap::auto_tile`]::{[`AutoTileKind`], [`AutoTileset`]}, the
+//! kinds of auto tilesets and the tileset used to pick sprites for terrains.
 //! * [`bevy_tilemap::chunk`]::[`LayerKind`], the only public part
 //! of `chunk` module is the kind of layer you need to specify to create.
 //! * [`bevy_tilemap::default_plugin`]::[`TilemapDefaultPlugins`], the
 //! default plugins for the library.
 //! * [`bevy_tilemap::entity`]::[`TilemapComponents`], the components
 //! for spawning with a Tilemap.
+//! * [`bevy_tilemap::fog`]::{[`FogState`], [`FogTints`]}, the fog of war
+//! state of a tile and the tints the states are rendered with.
 //! * [`bevy_tilemap::sprite_sheet`]::{[`SpriteSheet`], [`SpriteSheetBuilder`]},
 //! a sprite sheet and a builder both used to construct sprite sheets.
-//! * [`bevy_tilemap::tile`]::[`Tile`], a sprite tile which
-//! holds minimal amount of data for small data sizes.
+//! * [`bevy_tilemap::streamer`]::[`ChunkStreamer`], a component which spawns
+//! and despawns chunks around an entity.
+//! * [`bevy_tilemap::tile`]::{[`Tile`], [`TileFlip`]}, a sprite tile which
+//! holds minimal amount of data for small data sizes and the flip flags of its
+//! sprite.
 //! * [`bevy_tilemap::tilemap`]::{[`Tilemap`], [`TilemapBuilder`]},
 //! the core object that is used for virtually everything in this library.
 //! * [`bevy_tilemap`]::[`Tilemap2DPlugin`], the main plugin with
@@ -28,10 +37,14 @@
 //! and helpers for dealing with points of the 2nd and 3rd dimension.
 //!
 //! [`bevy_tilemap::prelude::v0`]: crate::prelude::v0
+//! [`bevy_tilemap::animation`]: crate::animation
+//! [`bevy_tilemap::auto_tile`]: crate::auto_tile
 //! [`bevy_tilemap::default_plugin`]: crate::default_plugin
 //! [`bevy_tilemap::chunk`]: crate::chunk
 //! [`bevy_tilemap::entity`]: crate::entity
+//! [`bevy_tilemap::fog`]: crate::fog
 //! [`bevy_tilemap::sprite_sheet`]: crate::sprite_sheet
+//! [`bevy_tilemap::streamer`]: crate::streamer
 //! [`bevy_tilemap::tile`]: crate::tile
 //! [`bevy_tilemap::tilemap`]: crate::tilemap
 //! [`bevy_tilemap::dimension`]: crate::dimension
@@ -43,12 +56,16 @@ pub mod v0 {
     #[cfg(feature = "types")]
     pub use crate::bevy_tilemap_types::prelude::v0::*;
     pub use crate::{
+        animation::TileAnimation,
+        auto_tile::{AutoTileKind, AutoTileset},
         chunk::LayerKind,
         default_plugin::TilemapDefaultPlugins,
         entity::TilemapComponents,
+        fog::{FogState, FogTints},
         render::GridTopology,
         sprite_sheet::prelude::v0::*,
-        tile::Tile,
+        streamer::ChunkStreamer,
+        tile::{Tile, TileFlip},
         tilemap::{Tilemap, TilemapBuilder},
         Tilemap2DPlugin,
     };
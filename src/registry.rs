@@ -0,0 +1,665 @@
+//! Named tile definitions which are independent of the order of the sprites.
+//!
+//! Tiles are normally inserted with the index of a sprite in the texture
+//! atlas, which changes whenever the texture atlas is rebuilt with a different
+//! order of textures. A [`TileRegistry`] instead maps names such as `"grass"`
+//! or `"wall_stone"` to a [`TileDefinition`], which refers to its sprite by the
+//! asset path of its texture and also holds a default tint, an animation and
+//! any properties of the tile.
+//!
+//! Before tiles can be created from a registry its sprites must be resolved to
+//! indexes with [`TileRegistry::resolve_texture_atlas`] or
+//! [`TileRegistry::resolve_sprite_sheet`], once the texture atlas or sprite
+//! sheet is built. Definitions which only use [`TileSprite::Index`] never
+//! need to be resolved.
+//!
+//! The [`Tilemap2DPlugin`] adds an empty `TileRegistry` as a resource. With
+//! the **"loader"** feature a registry can also be read from a [RON] file,
+//! which is a map of names to definitions.
+//!
+//! ```ron
+//! {
+//!     "grass": (
+//!         sprite: Texture("textures/grass.png"),
+//!         properties: { "walkable": "true" },
+//!     ),
+//!     "water": (
+//!         sprite: Texture("textures/water_0.png"),
+//!         tint: (red: 0.8, green: 0.9, blue: 1.0, alpha: 1.0),
+//!         animation: [
+//!             (Texture("textures/water_0.png"), 0.5),
+//!             (Texture("textures/water_1.png"), 0.5),
+//!         ],
+//!     ),
+//! }
+//! ```
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy_tilemap::registry::{TileDefinition, TileRegistry, TileSprite};
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut registry = TileRegistry::default();
+//! registry.register("grass", TileDefinition::new(TileSprite::Index(3)));
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! registry.insert_tile(&mut tilemap, "grass", (1, 1), 0).unwrap();
+//!
+//! assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+//! ```
+//!
+//! [`TileRegistry`]: TileRegistry
+//! [`TileDefinition`]: TileDefinition
+//! [`TileRegistry::resolve_texture_atlas`]: TileRegistry::resolve_texture_atlas
+//! [`TileRegistry::resolve_sprite_sheet`]: TileRegistry::resolve_sprite_sheet
+//! [`TileSprite::Index`]: TileSprite::Index
+//! [`Tilemap2DPlugin`]: crate::Tilemap2DPlugin
+//! [RON]: https://github.com/ron-rs/ron
+
+use crate::{
+    animation::TileAnimation,
+    lib::*,
+    sprite_sheet::SpriteSheet,
+    tile::Tile,
+    tilemap::{Tilemap, TilemapError},
+};
+
+#[derive(Debug)]
+/// The kinds of errors that can occur with a tile registry.
+enum ErrorKind {
+    /// No tile is registered with the name.
+    UnknownTile(String),
+    /// A sprite of the tile could not be resolved to an index.
+    UnresolvedSprite(String),
+    /// A file could not be read.
+    #[cfg(feature = "loader")]
+    Io(io::Error),
+    /// The RON is malformed or is not a tile registry.
+    #[cfg(feature = "loader")]
+    Ron(String),
+    /// The tile could not be inserted into the tilemap.
+    Tilemap(TilemapError),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use ErrorKind::*;
+        match self {
+            UnknownTile(name) => write!(f, "no tile is registered as `{}`", name),
+            UnresolvedSprite(name) => write!(
+                f,
+                "a sprite of tile `{}` is not resolved, try `resolve_texture_atlas` first",
+                name
+            ),
+            #[cfg(feature = "loader")]
+            Io(err) => write!(f, "could not read the registry: {}", err),
+            #[cfg(feature = "loader")]
+            Ron(err) => write!(f, "the registry is malformed: {}", err),
+            Tilemap(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The error type for operations when interacting with a tile registry.
+pub struct RegistryError(Box<ErrorKind>);
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for RegistryError {}
+
+impl From<ErrorKind> for RegistryError {
+    fn from(kind: ErrorKind) -> RegistryError {
+        RegistryError(Box::new(kind))
+    }
+}
+
+#[cfg(feature = "loader")]
+impl From<io::Error> for RegistryError {
+    fn from(err: io::Error) -> RegistryError {
+        RegistryError(Box::new(ErrorKind::Io(err)))
+    }
+}
+
+impl From<TilemapError> for RegistryError {
+    fn from(err: TilemapError) -> RegistryError {
+        RegistryError(Box::new(ErrorKind::Tilemap(err)))
+    }
+}
+
+/// A tile registry result.
+pub type RegistryResult<T> = Result<T, RegistryError>;
+
+/// A reference to a sprite which does not depend on the order of the sprites.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TileSprite {
+    /// The index of the sprite, which is used as it is.
+    Index(usize),
+    /// The asset path of a texture which was added to the texture atlas or
+    /// sprite sheet as a single sprite.
+    Texture(String),
+    /// A sprite of a texture in a sprite sheet.
+    Cell {
+        /// The asset path of the texture.
+        texture: String,
+        /// The point of the sprite in the texture, in sprites.
+        point: (i32, i32),
+    },
+}
+
+/// The definition of a named tile.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileDefinition {
+    /// The sprite of the tile.
+    pub sprite: TileSprite,
+    /// The tint that the tile is created with.
+    pub tint: Color,
+    /// The frames of the animation of the tile with how long they are shown
+    /// for in seconds. The tile is not animated if there are none.
+    pub animation: Vec<(TileSprite, f32)>,
+    /// Any properties of the tile, such as if it can be walked on.
+    pub properties: HashMap<String, String>,
+}
+
+impl Default for TileDefinition {
+    fn default() -> TileDefinition {
+        TileDefinition::new(TileSprite::Index(0))
+    }
+}
+
+impl TileDefinition {
+    /// Constructs a new definition of a tile with a sprite, no tint, no
+    /// animation and no properties.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::registry::{TileDefinition, TileSprite};
+    /// use bevy::prelude::*;
+    ///
+    /// let mut wall = TileDefinition::new(TileSprite::Texture("textures/wall.png".to_string()));
+    /// wall.tint = Color::rgb(0.5, 0.5, 0.5);
+    /// wall.properties.insert("solid".to_string(), "true".to_string());
+    ///
+    /// assert_eq!(wall.properties["solid"], "true");
+    /// ```
+    pub fn new(sprite: TileSprite) -> TileDefinition {
+        TileDefinition {
+            sprite,
+            tint: Color::WHITE,
+            animation: Vec::new(),
+            properties: HashMap::default(),
+        }
+    }
+
+    /// Returns the sprites of the definition, the sprite first followed by the
+    /// frames of the animation.
+    fn sprites(&self) -> impl Iterator<Item = &TileSprite> {
+        ::std::iter::once(&self.sprite).chain(self.animation.iter().map(|(sprite, _)| sprite))
+    }
+}
+
+/// A tile definition with the indexes its sprites are resolved to.
+#[derive(Clone, PartialEq, Debug)]
+struct Entry {
+    /// The definition of the tile.
+    definition: TileDefinition,
+    /// The index of the sprite and of every frame of the animation, in order,
+    /// if they are resolved.
+    indexes: Option<Vec<usize>>,
+}
+
+impl Entry {
+    /// Resolves the sprites of the definition with a lookup of the sprites that
+    /// are not indexes and returns `false` if any could not be resolved.
+    fn resolve<F>(&mut self, mut lookup: F) -> bool
+    where
+        F: FnMut(&TileSprite) -> Option<usize>,
+    {
+        let indexes: Option<Vec<usize>> = self
+            .definition
+            .sprites()
+            .map(|sprite| match sprite {
+                TileSprite::Index(index) => Some(*index),
+                sprite => lookup(sprite),
+            })
+            .collect();
+        let resolved = indexes.is_some();
+        self.indexes = indexes;
+        resolved
+    }
+}
+
+/// A registry of tile definitions by name.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct TileRegistry {
+    /// The tile definitions with their resolved sprites by name.
+    entries: HashMap<String, Entry>,
+}
+
+impl TileRegistry {
+    /// Registers a tile definition with a name and returns the definition that
+    /// was registered with the name before, if any.
+    ///
+    /// Sprites which are only indexes are resolved right away, any other
+    /// sprites are resolved the next time the registry is resolved.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::registry::{TileDefinition, TileRegistry, TileSprite};
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.register("grass", TileDefinition::new(TileSprite::Index(0)));
+    ///
+    /// assert!(registry.get("grass").is_some());
+    /// ```
+    pub fn register<S: ToString>(
+        &mut self,
+        name: S,
+        definition: TileDefinition,
+    ) -> Option<TileDefinition> {
+        let mut entry = Entry {
+            definition,
+            indexes: None,
+        };
+        entry.resolve(|_| None);
+        self.entries
+            .insert(name.to_string(), entry)
+            .map(|entry| entry.definition)
+    }
+
+    /// Removes a tile definition by name and returns it, if it exists.
+    pub fn remove(&mut self, name: &str) -> Option<TileDefinition> {
+        self.entries.remove(name).map(|entry| entry.definition)
+    }
+
+    /// Returns a reference to a tile definition by name, if it exists.
+    pub fn get(&self, name: &str) -> Option<&TileDefinition> {
+        self.entries.get(name).map(|entry| &entry.definition)
+    }
+
+    /// Returns a property of a tile definition by name, if both exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::registry::{TileDefinition, TileRegistry, TileSprite};
+    ///
+    /// let mut wall = TileDefinition::new(TileSprite::Index(1));
+    /// wall.properties.insert("solid".to_string(), "true".to_string());
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.register("wall_stone", wall);
+    ///
+    /// assert_eq!(registry.property("wall_stone", "solid"), Some("true"));
+    /// assert_eq!(registry.property("wall_stone", "flammable"), None);
+    /// ```
+    pub fn property(&self, name: &str, property: &str) -> Option<&str> {
+        self.get(name)?.properties.get(property).map(String::as_str)
+    }
+
+    /// Returns an iterator over the names and definitions of all the tiles.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TileDefinition)> {
+        self.entries
+            .iter()
+            .map(|(name, entry)| (name.as_str(), &entry.definition))
+    }
+
+    /// Returns the amount of tiles that are registered.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no tiles are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Resolves the sprites of every tile with a lookup, which is given every
+    /// sprite that is not an index.
+    fn resolve_with<F>(&mut self, mut lookup: F) -> RegistryResult<()>
+    where
+        F: FnMut(&TileSprite) -> Option<usize>,
+    {
+        let mut unresolved = None;
+        for (name, entry) in self.entries.iter_mut() {
+            if !entry.resolve(&mut lookup) && unresolved.is_none() {
+                unresolved = Some(name.clone());
+            }
+        }
+        match unresolved {
+            Some(name) => Err(ErrorKind::UnresolvedSprite(name).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolves the sprites of every tile to their indexes in a texture atlas.
+    ///
+    /// Textures are looked up by their asset path, which must be the path they
+    /// were loaded with and added to the texture atlas with. A
+    /// [`TileSprite::Cell`] can not be resolved with a texture atlas.
+    ///
+    /// This must be done again whenever the texture atlas is rebuilt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the name of a tile if any of its sprites is not
+    /// in the texture atlas. Every tile that could be resolved still is.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::registry::{TileDefinition, TileRegistry, TileSprite};
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // Usually the texture atlas is built with a `TextureAtlasBuilder`.
+    /// let mut texture_atlas = TextureAtlas::new_empty(
+    ///     Handle::weak(HandleId::random::<Texture>()),
+    ///     Vec2::new(16.0, 16.0),
+    /// );
+    /// let mut texture_handles = bevy::utils::HashMap::default();
+    /// texture_handles.insert(Handle::weak(HandleId::from("textures/grass.png")), 0);
+    /// texture_atlas.texture_handles = Some(texture_handles);
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.register(
+    ///     "grass",
+    ///     TileDefinition::new(TileSprite::Texture("textures/grass.png".to_string())),
+    /// );
+    ///
+    /// registry.resolve_texture_atlas(&texture_atlas).unwrap();
+    /// ```
+    ///
+    /// [`TileSprite::Cell`]: TileSprite::Cell
+    pub fn resolve_texture_atlas(&mut self, texture_atlas: &TextureAtlas) -> RegistryResult<()> {
+        self.resolve_with(|sprite| match sprite {
+            TileSprite::Texture(path) => {
+                texture_atlas.get_texture_index(&Handle::weak(HandleId::from(path.as_str())))
+            }
+            _ => None,
+        })
+    }
+
+    /// Resolves the sprites of every tile to their indexes in a sprite sheet.
+    ///
+    /// Textures are looked up by their asset path, which must be the path they
+    /// were loaded with and added to the sprite sheet with. A
+    /// [`TileSprite::Texture`] is the first sprite of its texture.
+    ///
+    /// This must be done again whenever the sprite sheet is rebuilt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the name of a tile if any of its sprites is not
+    /// in the sprite sheet. Every tile that could be resolved still is.
+    ///
+    /// [`TileSprite::Texture`]: TileSprite::Texture
+    pub fn resolve_sprite_sheet(&mut self, sprite_sheet: &SpriteSheet) -> RegistryResult<()> {
+        self.resolve_with(|sprite| {
+            let (path, point) = match sprite {
+                TileSprite::Index(index) => return Some(*index),
+                TileSprite::Texture(path) => (path, (0, 0)),
+                TileSprite::Cell { texture, point } => (texture, *point),
+            };
+            let texture = Handle::weak(HandleId::from(path.as_str()));
+            sprite_sheet
+                .get_sprite_index(&texture, point)
+                .ok()
+                .flatten()
+        })
+    }
+
+    /// Creates a tile by name at a point and Z order.
+    ///
+    /// The tile has the sprite and tint of its definition. If the definition
+    /// has an animation it is added to the tilemap, unless the tilemap already
+    /// has the same animation, and the tile is animated with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no tile is registered with the name, or if its
+    /// sprites are not resolved.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::registry::{TileDefinition, TileRegistry, TileSprite};
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut water = TileDefinition::new(TileSprite::Index(4));
+    /// water.animation = vec![(TileSprite::Index(4), 0.5), (TileSprite::Index(5), 0.5)];
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.register("water", water);
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// let tiles = vec![
+    ///     registry.tile(&mut tilemap, "water", (0, 0), 0).unwrap(),
+    ///     registry.tile(&mut tilemap, "water", (1, 0), 0).unwrap(),
+    /// ];
+    ///
+    /// // Both tiles share the same animation.
+    /// assert_eq!(tiles[0].animation, Some(0));
+    /// assert_eq!(tiles[1].animation, Some(0));
+    /// ```
+    pub fn tile<P: Into<Point2>>(
+        &self,
+        tilemap: &mut Tilemap,
+        name: &str,
+        point: P,
+        z_order: usize,
+    ) -> RegistryResult<Tile> {
+        let entry = self
+            .entries
+            .get(name)
+            .ok_or_else(|| ErrorKind::UnknownTile(name.to_string()))?;
+        let indexes = entry
+            .indexes
+            .as_ref()
+            .ok_or_else(|| ErrorKind::UnresolvedSprite(name.to_string()))?;
+        let definition = &entry.definition;
+
+        let animation = if definition.animation.is_empty() {
+            None
+        } else {
+            let frames = indexes[1..]
+                .iter()
+                .zip(definition.animation.iter())
+                .map(|(index, (_, duration))| (*index, *duration));
+            Some(tilemap.find_or_add_animation(TileAnimation::new(frames)))
+        };
+
+        Ok(Tile {
+            point: point.into(),
+            z_order,
+            sprite_index: indexes[0],
+            tint: definition.tint,
+            animation,
+            ..Tile::default()
+        })
+    }
+
+    /// Inserts a tile by name into a tilemap at a point and Z order.
+    ///
+    /// This is the same as inserting the tile of [`TileRegistry::tile`], to
+    /// insert many tiles at once collect them and use
+    /// [`Tilemap::insert_tiles`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no tile is registered with the name, if its sprites
+    /// are not resolved or if the tile could not be inserted.
+    ///
+    /// [`TileRegistry::tile`]: TileRegistry::tile
+    /// [`Tilemap::insert_tiles`]: crate::tilemap::Tilemap::insert_tiles
+    pub fn insert_tile<P: Into<Point2>>(
+        &self,
+        tilemap: &mut Tilemap,
+        name: &str,
+        point: P,
+        z_order: usize,
+    ) -> RegistryResult<()> {
+        let tile = self.tile(tilemap, name, point, z_order)?;
+        tilemap.insert_tile(tile)?;
+        Ok(())
+    }
+
+    /// Reads a tile registry from a RON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or is not a valid
+    /// registry.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use bevy_tilemap::registry::TileRegistry;
+    ///
+    /// let registry = TileRegistry::from_path("assets/tiles.ron").unwrap();
+    /// ```
+    #[cfg(feature = "loader")]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> RegistryResult<TileRegistry> {
+        TileRegistry::from_ron(&fs::read_to_string(path)?)
+    }
+
+    /// Reads a tile registry from a RON string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid registry.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::registry::TileRegistry;
+    ///
+    /// let registry = TileRegistry::from_ron(r#"{
+    ///     "grass": (sprite: Texture("textures/grass.png")),
+    ///     "wall_stone": (sprite: Index(7), properties: { "solid": "true" }),
+    /// }"#)
+    /// .unwrap();
+    ///
+    /// assert_eq!(registry.len(), 2);
+    /// assert_eq!(registry.property("wall_stone", "solid"), Some("true"));
+    /// ```
+    #[cfg(feature = "loader")]
+    pub fn from_ron(ron: &str) -> RegistryResult<TileRegistry> {
+        let definitions: HashMap<String, TileDefinition> =
+            ron::de::from_str(ron).map_err(|err| ErrorKind::Ron(err.to_string()))?;
+        let mut registry = TileRegistry::default();
+        for (name, definition) in definitions {
+            registry.register(name, definition);
+        }
+        Ok(registry)
+    }
+
+    /// Serializes the tile definitions into the RON format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definitions could not be serialized.
+    #[cfg(feature = "loader")]
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        let definitions: HashMap<&str, &TileDefinition> = self.iter().collect();
+        ron::ser::to_string_pretty(&definitions, ron::ser::PrettyConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprites_are_resolved_by_texture() {
+        let mut texture_atlas = TextureAtlas::new_empty(
+            Handle::weak(HandleId::random::<Texture>()),
+            Vec2::new(32.0, 16.0),
+        );
+        let mut texture_handles = HashMap::default();
+        for (index, path) in ["textures/wall.png", "textures/grass.png"]
+            .iter()
+            .enumerate()
+        {
+            texture_handles.insert(Handle::weak(HandleId::from(*path)), index + 1);
+        }
+        texture_atlas.texture_handles = Some(texture_handles);
+        let index_of = |path: &str| {
+            texture_atlas
+                .get_texture_index(&Handle::weak(HandleId::from(path)))
+                .unwrap()
+        };
+
+        let mut registry = TileRegistry::default();
+        let wall = TileSprite::Texture("textures/wall.png".to_string());
+        registry.register("wall", TileDefinition::new(wall.clone()));
+        let mut grass = TileDefinition::new(TileSprite::Index(9));
+        grass.animation = vec![
+            (TileSprite::Texture("textures/grass.png".to_string()), 1.0),
+            (wall, 1.0),
+        ];
+        registry.register("grass", grass);
+
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle);
+        assert!(registry.tile(&mut tilemap, "wall", (0, 0), 0).is_err());
+
+        registry.resolve_texture_atlas(&texture_atlas).unwrap();
+        let wall = registry.tile(&mut tilemap, "wall", (0, 0), 0).unwrap();
+        assert_eq!(wall.sprite_index, index_of("textures/wall.png"));
+        assert_eq!(wall.animation, None);
+
+        let grass = registry.tile(&mut tilemap, "grass", (0, 0), 0).unwrap();
+        assert_eq!(grass.sprite_index, 9);
+        assert_eq!(grass.animation, Some(0));
+        assert_eq!(
+            tilemap.find_or_add_animation(TileAnimation::new(vec![
+                (index_of("textures/grass.png"), 1.0),
+                (index_of("textures/wall.png"), 1.0),
+            ])),
+            0
+        );
+
+        registry.register(
+            "missing",
+            TileDefinition::new(TileSprite::Texture("textures/missing.png".to_string())),
+        );
+        assert!(registry.resolve_texture_atlas(&texture_atlas).is_err());
+        assert!(registry.tile(&mut tilemap, "wall", (0, 0), 0).is_ok());
+        assert!(registry.tile(&mut tilemap, "missing", (0, 0), 0).is_err());
+    }
+
+    #[cfg(feature = "loader")]
+    #[test]
+    fn ron_round_trip() {
+        let mut water = TileDefinition::new(TileSprite::Cell {
+            texture: "textures/water.png".to_string(),
+            point: (1, 2),
+        });
+        water.tint = Color::rgba(0.8, 0.9, 1.0, 1.0);
+        water.animation = vec![(TileSprite::Index(4), 0.5), (TileSprite::Index(5), 0.5)];
+        water
+            .properties
+            .insert("swimmable".to_string(), "true".to_string());
+
+        let mut registry = TileRegistry::default();
+        registry.register("water", water);
+        registry.register("grass", TileDefinition::new(TileSprite::Index(0)));
+
+        let loaded = TileRegistry::from_ron(&registry.to_ron().unwrap()).unwrap();
+        assert_eq!(loaded, registry);
+    }
+}
@@ -3,9 +3,11 @@
 layout(location = 0) in vec3 Vertex_Position;
 layout(location = 1) in float Vertex_Tile_Index;
 layout(location = 2) in vec4 Vertex_Tile_Color;
+layout(location = 3) in float Vertex_Tile_Flip;
 
 layout(location = 0) out vec2 v_Uv;
 layout(location = 1) out vec4 v_Color;
+layout(location = 2) out float v_Solid;
 
 layout(set = 0, binding = 0) uniform Camera {
     mat4 ViewProj;
@@ -31,8 +33,26 @@ layout(set = 2, binding = 0) uniform Transform {
     mat4 ChunkTransform;
 };
 
+// Returns the corner of the sprite that is drawn at a corner of the tile. The
+// flip flags are 1 for horizontal, 2 for vertical and 4 for diagonal, where
+// the diagonal flip is applied first.
+int flip_corner(int corner, int flip) {
+    if ((flip & 2) != 0) {
+        corner = corner ^ 1;
+    }
+    if ((flip & 1) != 0) {
+        corner = 3 - corner;
+    }
+    if ((flip & 4) != 0 && corner % 2 == 0) {
+        corner = 2 - corner;
+    }
+    return corner;
+}
+
 void main() {
-    Rect sprite_rect = Textures[int(Vertex_Tile_Index)];
+    // A negative index is a tile filled with its color, sized as the first
+    // sprite.
+    Rect sprite_rect = Textures[int(max(Vertex_Tile_Index, 0.0))];
     vec2 sprite_dimensions = sprite_rect.end - sprite_rect.begin;
 
     int local_index = gl_VertexIndex % 4;
@@ -65,7 +85,8 @@ void main() {
         vec2(sprite_rect.end.x, sprite_rect.begin.y),
         sprite_rect.end
     );
-    v_Uv = (atlas_positions[local_index]) / AtlasSize;
+    v_Uv = (atlas_positions[flip_corner(local_index, int(Vertex_Tile_Flip))]) / AtlasSize;
     v_Color = Vertex_Tile_Color;
+    v_Solid = Vertex_Tile_Index < 0.0 ? 1.0 : 0.0;
     gl_Position = ViewProj * ChunkTransform * vec4(ceil(vertex_position), 1.0);
 }
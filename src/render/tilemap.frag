@@ -2,6 +2,7 @@
 
 layout(location = 0) in vec2 v_Uv;
 layout(location = 1) in vec4 v_Color;
+layout(location = 2) in float v_Solid;
 
 layout(location = 0) out vec4 o_Target;
 
@@ -12,6 +13,10 @@ void main() {
     if (v_Color.a == 0.0) {
         discard;
     }
+    if (v_Solid > 0.5) {
+        o_Target = v_Color;
+        return;
+    }
     o_Target = v_Color * texture(
         sampler2D(TextureAtlas_texture, TextureAtlas_texture_sampler),
         v_Uv
@@ -0,0 +1,377 @@
+//! Versioned saves of tilemaps which can be loaded by later versions.
+//!
+//! Serializing a tilemap with serde directly ties the output to the private
+//! fields of the tilemap, which breaks whenever a field is added. A save made
+//! with [`Tilemap::to_save_bytes`] instead wraps the serialized tilemap in an
+//! envelope with the version of the format it was written in. When a save of
+//! an older version is loaded with [`Tilemap::from_save_bytes`], it is
+//! upgraded step by step through a migration for every version after it.
+//!
+//! The versions of the format are:
+//!
+//! 1. The format of `bevy_tilemap` 0.2, before auto tiling and animations.
+//! 2. Auto tilesets and animations on the tilemap, and the terrains and
+//! animations of the tiles in the layers of the chunks.
+//! 3. The flip flags of every tile.
+//!
+//! This module requires the **"serialize"** feature.
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::builder()
+//!     .texture_atlas(texture_atlas_handle.clone())
+//!     .auto_chunk()
+//!     .finish()
+//!     .unwrap();
+//! tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+//!
+//! let bytes = tilemap.to_save_bytes().unwrap();
+//!
+//! let mut loaded = Tilemap::from_save_bytes(&bytes).unwrap();
+//! loaded.set_texture_atlas(texture_atlas_handle);
+//!
+//! assert_eq!(loaded.get_tile((1, 1), 0).unwrap().index, 3);
+//! ```
+//!
+//! [`Tilemap::to_save_bytes`]: crate::tilemap::Tilemap::to_save_bytes
+//! [`Tilemap::from_save_bytes`]: crate::tilemap::Tilemap::from_save_bytes
+
+use crate::{
+    animation::AnimationState,
+    auto_tile::AutoTileset,
+    chunk::LayerKind,
+    lib::*,
+    prelude::GridTopology,
+    tile::{RawTile, TileFlip},
+};
+
+/// The magic number that every save starts with.
+pub(crate) const MAGIC: [u8; 4] = *b"BTMS";
+
+/// A migration of a serialized tilemap from a version to the next.
+type Migration = fn(&[u8]) -> bincode::Result<Vec<u8>>;
+
+/// The migrations of every version to the next, starting with version 1.
+const MIGRATIONS: &[Migration] = &[migrate_v1, migrate_v2];
+
+/// The version of the format that tilemaps are saved in.
+pub const SAVE_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// The envelope around a serialized tilemap.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SaveEnvelope {
+    /// The magic number of a save.
+    pub(crate) magic: [u8; 4],
+    /// The version of the format the tilemap was serialized in.
+    pub(crate) version: u32,
+    /// The serialized tilemap.
+    pub(crate) data: Vec<u8>,
+}
+
+/// Upgrades a serialized tilemap from a supported version to the latest.
+pub(crate) fn migrate(version: u32, mut data: Vec<u8>) -> bincode::Result<Vec<u8>> {
+    for migration in MIGRATIONS.iter().skip(version as usize - 1) {
+        data = migration(&data)?;
+    }
+    Ok(data)
+}
+
+/// The tile of versions 1 and 2, without flip flags.
+#[derive(Serialize, Deserialize)]
+struct RawTileV1 {
+    /// The index of the tile in the sprite sheet.
+    index: usize,
+    /// The color, or tint, of the tile.
+    color: Color,
+}
+
+/// The dense layer of version 1, with tiles of the version.
+#[derive(Serialize, Deserialize)]
+struct DenseLayerV1<T> {
+    /// A vector of all the tiles in the chunk.
+    tiles: Vec<T>,
+}
+
+/// The sparse layer of version 1, with tiles of the version.
+#[derive(Serialize, Deserialize)]
+struct SparseLayerV1<T> {
+    /// A map of all the tiles in the chunk.
+    tiles: HashMap<usize, T>,
+}
+
+/// The layer storage of version 1, which is unchanged in versions 2 and 3
+/// apart from the tiles.
+#[derive(Serialize, Deserialize)]
+enum LayerKindInnerV1<T> {
+    /// Inner dense layer storage.
+    Dense(DenseLayerV1<T>),
+    /// Inner sparse layer storage.
+    Sparse(SparseLayerV1<T>),
+}
+
+/// The sprite layer of version 1.
+#[derive(Serialize, Deserialize)]
+struct SpriteLayerV1 {
+    /// Enum storage of the kind of layer.
+    inner: LayerKindInnerV1<RawTileV1>,
+}
+
+/// The chunk of version 1.
+#[derive(Serialize, Deserialize)]
+struct ChunkV1 {
+    /// The point of the chunk.
+    point: Point2,
+    /// The sprite layers of the chunk.
+    sprite_layers: Vec<Option<SpriteLayerV1>>,
+}
+
+/// The tilemap of version 1.
+#[derive(Serialize, Deserialize)]
+struct TilemapV1 {
+    /// The type of grid to use.
+    topology: GridTopology,
+    /// The dimensions of the tilemap in chunks, if any.
+    dimensions: Option<Dimension2>,
+    /// A chunks dimensions in tiles.
+    chunk_dimensions: Dimension2,
+    /// A tiles dimensions in pixels.
+    tile_dimensions: Dimension2,
+    /// The layers of the tilemap.
+    layers: Vec<Option<LayerKind>>,
+    /// The bits of the auto flags.
+    auto_flags: u16,
+    /// A map of all the chunks at points.
+    chunks: HashMap<Point2, ChunkV1>,
+}
+
+/// The sprite layer of version 2, which is unchanged in version 3 apart from
+/// the tiles.
+#[derive(Serialize, Deserialize)]
+struct SpriteLayerV2<T> {
+    /// Enum storage of the kind of layer.
+    inner: LayerKindInnerV1<T>,
+    /// A map of tile indexes to the terrain of the tile.
+    terrains: HashMap<usize, u32>,
+    /// A map of tile indexes to the ID of the animation of the tile.
+    animations: HashMap<usize, usize>,
+}
+
+/// The chunk of version 2, which is unchanged in version 3 apart from the
+/// tiles.
+#[derive(Serialize, Deserialize)]
+struct ChunkV2<T> {
+    /// The point of the chunk.
+    point: Point2,
+    /// The sprite layers of the chunk.
+    sprite_layers: Vec<Option<SpriteLayerV2<T>>>,
+}
+
+/// The tilemap of version 2, which is unchanged in version 3 apart from the
+/// tiles.
+#[derive(Serialize, Deserialize)]
+struct TilemapV2<T> {
+    /// The type of grid to use.
+    topology: GridTopology,
+    /// The dimensions of the tilemap in chunks, if any.
+    dimensions: Option<Dimension2>,
+    /// A chunks dimensions in tiles.
+    chunk_dimensions: Dimension2,
+    /// A tiles dimensions in pixels.
+    tile_dimensions: Dimension2,
+    /// The layers of the tilemap.
+    layers: Vec<Option<LayerKind>>,
+    /// The bits of the auto flags.
+    auto_flags: u16,
+    /// The auto tilesets of each terrain.
+    auto_tilesets: HashMap<u32, AutoTileset>,
+    /// The animations of the tilemap.
+    animations: Vec<AnimationState>,
+    /// A map of all the chunks at points.
+    chunks: HashMap<Point2, ChunkV2<T>>,
+}
+
+/// Migrates version 1 to version 2, in which no tiles have terrains or
+/// animations.
+fn migrate_v1(data: &[u8]) -> bincode::Result<Vec<u8>> {
+    let tilemap: TilemapV1 = bincode::deserialize(data)?;
+    let chunks = tilemap
+        .chunks
+        .into_iter()
+        .map(|(point, chunk)| {
+            let sprite_layers = chunk
+                .sprite_layers
+                .into_iter()
+                .map(|layer| {
+                    layer.map(|layer| SpriteLayerV2 {
+                        inner: layer.inner,
+                        terrains: HashMap::default(),
+                        animations: HashMap::default(),
+                    })
+                })
+                .collect();
+            let chunk = ChunkV2 {
+                point: chunk.point,
+                sprite_layers,
+            };
+            (point, chunk)
+        })
+        .collect();
+    bincode::serialize(&TilemapV2::<RawTileV1> {
+        topology: tilemap.topology,
+        dimensions: tilemap.dimensions,
+        chunk_dimensions: tilemap.chunk_dimensions,
+        tile_dimensions: tilemap.tile_dimensions,
+        layers: tilemap.layers,
+        auto_flags: tilemap.auto_flags,
+        auto_tilesets: HashMap::default(),
+        animations: Vec::new(),
+        chunks,
+    })
+}
+
+/// Migrates version 2 to version 3, in which no tiles are flipped.
+fn migrate_v2(data: &[u8]) -> bincode::Result<Vec<u8>> {
+    let tilemap: TilemapV2<RawTileV1> = bincode::deserialize(data)?;
+    let add_flip = |tile: RawTileV1| RawTile {
+        index: tile.index,
+        color: tile.color,
+        flip: TileFlip::empty(),
+    };
+    let chunks = tilemap
+        .chunks
+        .into_iter()
+        .map(|(point, chunk)| {
+            let sprite_layers = chunk
+                .sprite_layers
+                .into_iter()
+                .map(|layer| {
+                    layer.map(|layer| SpriteLayerV2 {
+                        inner: match layer.inner {
+                            LayerKindInnerV1::Dense(dense) => {
+                                LayerKindInnerV1::Dense(DenseLayerV1 {
+                                    tiles: dense.tiles.into_iter().map(add_flip).collect(),
+                                })
+                            }
+                            LayerKindInnerV1::Sparse(sparse) => {
+                                LayerKindInnerV1::Sparse(SparseLayerV1 {
+                                    tiles: sparse
+                                        .tiles
+                                        .into_iter()
+                                        .map(|(index, tile)| (index, add_flip(tile)))
+                                        .collect(),
+                                })
+                            }
+                        },
+                        terrains: layer.terrains,
+                        animations: layer.animations,
+                    })
+                })
+                .collect();
+            let chunk = ChunkV2 {
+                point: chunk.point,
+                sprite_layers,
+            };
+            (point, chunk)
+        })
+        .collect();
+    bincode::serialize(&TilemapV2::<RawTile> {
+        topology: tilemap.topology,
+        dimensions: tilemap.dimensions,
+        chunk_dimensions: tilemap.chunk_dimensions,
+        tile_dimensions: tilemap.tile_dimensions,
+        layers: tilemap.layers,
+        auto_flags: tilemap.auto_flags,
+        auto_tilesets: tilemap.auto_tilesets,
+        animations: tilemap.animations,
+        chunks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tilemap::Tilemap;
+
+    #[test]
+    fn migrate_from_v1() {
+        let mut dense: Vec<RawTileV1> = (0..32 * 32)
+            .map(|_| RawTileV1 {
+                index: 0,
+                color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            })
+            .collect();
+        dense[0] = RawTileV1 {
+            index: 4,
+            color: Color::WHITE,
+        };
+        let mut sparse = HashMap::default();
+        sparse.insert(
+            1,
+            RawTileV1 {
+                index: 9,
+                color: Color::WHITE,
+            },
+        );
+        let mut chunks = HashMap::default();
+        chunks.insert(
+            Point2::new(0, 0),
+            ChunkV1 {
+                point: Point2::new(0, 0),
+                sprite_layers: vec![
+                    Some(SpriteLayerV1 {
+                        inner: LayerKindInnerV1::Dense(DenseLayerV1 { tiles: dense }),
+                    }),
+                    Some(SpriteLayerV1 {
+                        inner: LayerKindInnerV1::Sparse(SparseLayerV1 { tiles: sparse }),
+                    }),
+                ],
+            },
+        );
+        let tilemap = TilemapV1 {
+            topology: GridTopology::Square,
+            dimensions: None,
+            chunk_dimensions: Dimension2::new(32, 32),
+            tile_dimensions: Dimension2::new(32, 32),
+            layers: vec![Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            auto_flags: 0,
+            chunks,
+        };
+        let bytes = bincode::serialize(&SaveEnvelope {
+            magic: MAGIC,
+            version: 1,
+            data: bincode::serialize(&tilemap).unwrap(),
+        })
+        .unwrap();
+
+        let mut tilemap = Tilemap::from_save_bytes(&bytes).unwrap();
+
+        assert_eq!(tilemap.get_tile((-16, -16), 0).unwrap().index, 4);
+        assert_eq!(tilemap.get_tile((-15, -16), 1).unwrap().index, 9);
+        assert_eq!(
+            tilemap.get_tile((-15, -16), 1).unwrap().flip,
+            TileFlip::empty()
+        );
+        assert_eq!(tilemap.get_terrain((-16, -16), 0), None);
+    }
+
+    #[test]
+    fn unsupported_versions() {
+        for version in [0, SAVE_VERSION + 1].iter() {
+            let bytes = bincode::serialize(&SaveEnvelope {
+                magic: MAGIC,
+                version: *version,
+                data: Vec::new(),
+            })
+            .unwrap();
+            let err = Tilemap::from_save_bytes(&bytes).unwrap_err();
+            assert!(err.to_string().contains("not supported"));
+        }
+    }
+}
@@ -0,0 +1,489 @@
+//! Stamps of tiles which are copied from and pasted into tilemaps.
+//!
+//! A [`TileStamp`] is captured from a rectangle of a tilemap with
+//! [`TileStamp::capture`] and holds the tiles of every sprite layer in it,
+//! across chunks, with their tints, flips, terrains and animations. It can be
+//! pasted with [`TileStamp::paste`] at any point of the same or another
+//! tilemap, such as to build a level out of room templates.
+//!
+//! Stamps of square grids can be rotated and mirrored, which also rotates and
+//! mirrors the sprites of the tiles with their [`TileFlip`]. With the
+//! **"serialize"** feature stamps can be serialized to keep a library of them.
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy_tilemap::stamp::TileStamp;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! // A corridor going east.
+//! tilemap.fill_rect((0, 0), (3, 0), Tile::new((0, 0), 1)).unwrap();
+//! let corridor = TileStamp::capture(&tilemap, (0, 0), (3, 0));
+//!
+//! // The same corridor going south.
+//! let corridor = corridor.rotate_90().unwrap();
+//! assert_eq!((corridor.width(), corridor.height()), (1, 4));
+//!
+//! corridor.paste(&mut tilemap, (5, 5), true).unwrap();
+//! assert_eq!(tilemap.get_tile((5, 8), 0).unwrap().flip, TileFlip::ROTATE_90);
+//! ```
+//!
+//! [`TileStamp`]: TileStamp
+//! [`TileStamp::capture`]: TileStamp::capture
+//! [`TileStamp::paste`]: TileStamp::paste
+//! [`TileFlip`]: crate::tile::TileFlip
+
+use crate::{
+    animation::TileAnimation,
+    lib::*,
+    render::GridTopology,
+    tile::{RawTile, Tile, TileFlip},
+    tilemap::{ErrorKind, Tilemap, TilemapResult},
+};
+
+/// A tile of a stamp.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+struct StampTile {
+    /// The point of the tile from the bottom left corner of the stamp.
+    point: Point2,
+    /// The Z order of the tile.
+    z_order: usize,
+    /// The sprite, tint and flip of the tile.
+    tile: RawTile,
+    /// The terrain of the tile, if any.
+    terrain: Option<u32>,
+    /// The index of the animation of the tile in the stamp, if any.
+    animation: Option<usize>,
+}
+
+/// A rectangle of tiles across all of the sprite layers of a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileStamp {
+    /// The topology of the tilemap the stamp was captured from.
+    topology: GridTopology,
+    /// The width of the stamp in tiles.
+    width: u32,
+    /// The height of the stamp in tiles.
+    height: u32,
+    /// The Z orders of the sprite layers that were captured.
+    z_orders: Vec<usize>,
+    /// The tiles of the stamp.
+    tiles: Vec<StampTile>,
+    /// The animations of the tiles of the stamp.
+    animations: Vec<TileAnimation>,
+}
+
+impl TileStamp {
+    /// Captures the tiles of every sprite layer in the rectangle between two
+    /// corners of a tilemap, both included.
+    ///
+    /// Points in chunks that do not exist are captured as empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::stamp::TileStamp;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+    ///
+    /// let stamp = TileStamp::capture(&tilemap, (3, 3), (0, 0));
+    ///
+    /// assert_eq!((stamp.width(), stamp.height()), (4, 4));
+    /// assert_eq!(stamp.len(), 1);
+    /// ```
+    pub fn capture<P: Into<Point2>>(tilemap: &Tilemap, corner: P, opposite_corner: P) -> TileStamp {
+        let (a, b): (Point2, Point2) = (corner.into(), opposite_corner.into());
+        let origin = Point2::new(a.x.min(b.x), a.y.min(b.y));
+        let z_orders: Vec<usize> = (0..tilemap.layer_count())
+            .filter(|z_order| tilemap.is_sprite_layer(*z_order))
+            .collect();
+
+        let width = (a.x - b.x).abs() as u32 + 1;
+        let height = (a.y - b.y).abs() as u32 + 1;
+        let mut tiles = Vec::new();
+        let mut animations: Vec<TileAnimation> = Vec::new();
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let point = Point2::new(origin.x + x, origin.y + y);
+                let (chunk, index) = match tilemap.chunk_and_index(point) {
+                    Some(chunk_and_index) => chunk_and_index,
+                    None => continue,
+                };
+                for &z_order in z_orders.iter() {
+                    let tile = match chunk.get_tile(z_order, index) {
+                        Some(tile) => *tile,
+                        None => continue,
+                    };
+                    let animation = chunk
+                        .get_animation(z_order, index)
+                        .and_then(|animation| tilemap.get_animation(animation))
+                        .map(
+                            |animation| match animations.iter().position(|a| a == animation) {
+                                Some(index) => index,
+                                None => {
+                                    animations.push(animation.clone());
+                                    animations.len() - 1
+                                }
+                            },
+                        );
+                    tiles.push(StampTile {
+                        point: Point2::new(x, y),
+                        z_order,
+                        tile,
+                        terrain: chunk.get_terrain(z_order, index),
+                        animation,
+                    });
+                }
+            }
+        }
+
+        TileStamp {
+            topology: tilemap.topology(),
+            width,
+            height,
+            z_orders,
+            tiles,
+            animations,
+        }
+    }
+
+    /// The width of the stamp in tiles.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the stamp in tiles.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The amount of tiles in the stamp, across all layers.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns `true` if the stamp has no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns a copy of the stamp with its tiles transformed by flip flags,
+    /// if it is of a square grid.
+    fn transform(&self, flip: TileFlip) -> Option<TileStamp> {
+        if self.topology != GridTopology::Square {
+            return None;
+        }
+        // The flip flags are for sprites with the y axis pointing down, the
+        // points of the tilemap have the y axis pointing up.
+        let [[a, b], [c, d]] = flip.to_matrix();
+        let (b, c) = (-b, -c);
+        let transform =
+            |point: Point2| Point2::new(a * point.x + b * point.y, c * point.x + d * point.y);
+
+        let (width, height) = if a == 0 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        let far_corner = transform(Point2::new(self.width as i32 - 1, self.height as i32 - 1));
+        let offset = Point2::new(far_corner.x.min(0), far_corner.y.min(0));
+
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|tile| {
+                let point = transform(tile.point);
+                StampTile {
+                    point: Point2::new(point.x - offset.x, point.y - offset.y),
+                    tile: RawTile {
+                        flip: tile.tile.flip.then(flip),
+                        ..tile.tile
+                    },
+                    ..tile.clone()
+                }
+            })
+            .collect();
+
+        Some(TileStamp {
+            width,
+            height,
+            tiles,
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy of the stamp rotated clockwise by 90 degrees, if it is
+    /// of a square grid.
+    ///
+    /// The sprites of the tiles are rotated as well.
+    pub fn rotate_90(&self) -> Option<TileStamp> {
+        self.transform(TileFlip::ROTATE_90)
+    }
+
+    /// Returns a copy of the stamp rotated by 180 degrees, if it is of a
+    /// square grid.
+    ///
+    /// The sprites of the tiles are rotated as well.
+    pub fn rotate_180(&self) -> Option<TileStamp> {
+        self.transform(TileFlip::ROTATE_180)
+    }
+
+    /// Returns a copy of the stamp rotated clockwise by 270 degrees, if it is
+    /// of a square grid.
+    ///
+    /// The sprites of the tiles are rotated as well.
+    pub fn rotate_270(&self) -> Option<TileStamp> {
+        self.transform(TileFlip::ROTATE_270)
+    }
+
+    /// Returns a copy of the stamp mirrored from left to right, if it is of a
+    /// square grid.
+    ///
+    /// The sprites of the tiles are mirrored as well.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::stamp::TileStamp;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile::new((0, 0), 1)).unwrap();
+    ///
+    /// let stamp = TileStamp::capture(&tilemap, (0, 0), (2, 0)).mirror_horizontal().unwrap();
+    /// stamp.paste(&mut tilemap, (0, 1), false).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((0, 1), 0), None);
+    /// assert_eq!(tilemap.get_tile((2, 1), 0).unwrap().flip, TileFlip::HORIZONTAL);
+    /// ```
+    pub fn mirror_horizontal(&self) -> Option<TileStamp> {
+        self.transform(TileFlip::HORIZONTAL)
+    }
+
+    /// Returns a copy of the stamp mirrored from top to bottom, if it is of a
+    /// square grid.
+    ///
+    /// The sprites of the tiles are mirrored as well.
+    pub fn mirror_vertical(&self) -> Option<TileStamp> {
+        self.transform(TileFlip::VERTICAL)
+    }
+
+    /// Pastes the stamp into a tilemap with its bottom left corner at a point.
+    ///
+    /// If `skip_empty` is set the points of the stamp without a tile are left
+    /// as they are, otherwise they are cleared in every layer that the stamp
+    /// was captured from. The animations of the tiles are added to the tilemap
+    /// if it does not have them yet. All the tiles are inserted at once, so
+    /// every chunk is only re-rendered once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tile of the stamp has an animation which the
+    /// stamp does not have, which is only possible for a deserialized stamp,
+    /// or if the tiles could not be inserted, see [`Tilemap::insert_tiles`].
+    ///
+    /// [`Tilemap::insert_tiles`]: crate::tilemap::Tilemap::insert_tiles
+    pub fn paste<P: Into<Point2>>(
+        &self,
+        tilemap: &mut Tilemap,
+        point: P,
+        skip_empty: bool,
+    ) -> TilemapResult<()> {
+        let origin: Point2 = point.into();
+        // The animations are checked before any is added to the tilemap.
+        for stamp_tile in self.tiles.iter() {
+            if let Some(animation) = stamp_tile.animation {
+                if animation >= self.animations.len() {
+                    return Err(ErrorKind::AnimationDoesNotExist(animation).into());
+                }
+            }
+        }
+        let animations: Vec<usize> = self
+            .animations
+            .iter()
+            .map(|animation| tilemap.find_or_add_animation(animation.clone()))
+            .collect();
+
+        let mut tiles = Vec::with_capacity(self.tiles.len());
+        let mut filled = HashSet::default();
+        for stamp_tile in self.tiles.iter() {
+            let point = Point2::new(origin.x + stamp_tile.point.x, origin.y + stamp_tile.point.y);
+            filled.insert((stamp_tile.point, stamp_tile.z_order));
+            tiles.push(Tile {
+                point,
+                z_order: stamp_tile.z_order,
+                sprite_index: stamp_tile.tile.index,
+                tint: stamp_tile.tile.color,
+                flip: stamp_tile.tile.flip,
+                terrain: stamp_tile.terrain,
+                animation: stamp_tile.animation.map(|animation| animations[animation]),
+            });
+        }
+
+        if !skip_empty {
+            for y in 0..self.height as i32 {
+                for x in 0..self.width as i32 {
+                    for &z_order in self.z_orders.iter() {
+                        if filled.contains(&(Point2::new(x, y), z_order)) {
+                            continue;
+                        }
+                        tiles.push(Tile::with_z_order_and_tint(
+                            (origin.x + x, origin.y + y),
+                            0,
+                            z_order,
+                            Color::rgba(0.0, 0.0, 0.0, 0.0),
+                        ));
+                    }
+                }
+            }
+        }
+
+        tilemap.insert_tiles(tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chunk::LayerKind, tilemap::TilemapError};
+
+    fn new_tilemap() -> Tilemap {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .add_layer(LayerKind::Sparse, 1)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap
+    }
+
+    #[test]
+    fn flips_compose_like_rotations() {
+        let rotations = [
+            TileFlip::empty(),
+            TileFlip::ROTATE_90,
+            TileFlip::ROTATE_180,
+            TileFlip::ROTATE_270,
+        ];
+        for (i, a) in rotations.iter().enumerate() {
+            for (j, b) in rotations.iter().enumerate() {
+                assert_eq!(a.then(*b), rotations[(i + j) % 4]);
+            }
+        }
+        for bits in 0..8 {
+            let flip = TileFlip::from_bits(bits).unwrap();
+            assert_eq!(flip.then(TileFlip::empty()), flip);
+            assert_eq!(TileFlip::from_matrix(flip.to_matrix()), flip);
+        }
+    }
+
+    #[test]
+    fn capture_across_chunks_and_paste_rotated() {
+        let mut tilemap = new_tilemap();
+        let water = tilemap.add_animation(TileAnimation::new(vec![(4, 0.5), (5, 0.5)]));
+        // An L shape of 3 by 2 tiles across two chunks, with a tile above it.
+        let tiles = vec![
+            Tile::new((1, 0), 1),
+            Tile::new((2, 0), 2),
+            Tile::new((3, 0), 3),
+            Tile::with_animation((1, 1), water, 0),
+            Tile::with_z_order((2, 0), 7, 1),
+        ];
+        tilemap.insert_tiles(tiles).unwrap();
+
+        let stamp = TileStamp::capture(&tilemap, (1, 0), (3, 1));
+        assert_eq!(stamp.len(), 5);
+        assert_eq!(stamp.z_orders, vec![0, 1]);
+
+        // Rotating four times gives the same stamp back.
+        let mut rotated = stamp.clone();
+        for _ in 0..4 {
+            rotated = rotated.rotate_90().unwrap();
+        }
+        assert_eq!(rotated, stamp);
+        assert_eq!(
+            stamp.mirror_horizontal().unwrap().mirror_horizontal(),
+            Some(stamp.clone())
+        );
+
+        // Clockwise the top left corner goes to the top right.
+        let rotated = stamp.rotate_90().unwrap();
+        assert_eq!((rotated.width(), rotated.height()), (2, 3));
+        let mut other = new_tilemap();
+        rotated.paste(&mut other, (-2, -2), true).unwrap();
+        assert_eq!(other.get_tile((-1, 0), 0).unwrap().index, 4);
+        assert_eq!(other.get_tile((-2, 0), 0).unwrap().index, 1);
+        assert_eq!(other.get_tile((-2, -2), 0).unwrap().index, 3);
+        assert_eq!(other.get_tile((-2, -1), 1).unwrap().index, 7);
+        assert_eq!(
+            other.get_tile((-2, -1), 1).unwrap().flip,
+            TileFlip::ROTATE_90
+        );
+        assert_eq!(other.get_animation(0), tilemap.get_animation(water));
+
+        // Pasting without skipping clears what was under the stamp, in the
+        // dense and the sparse layer.
+        other.insert_tile(Tile::new((-1, -2), 9)).unwrap();
+        other
+            .insert_tile(Tile::with_z_order((-1, 0), 9, 1))
+            .unwrap();
+        rotated.paste(&mut other, (-2, -2), false).unwrap();
+        assert_eq!(other.get_tile((-1, -2), 0), None);
+        assert_eq!(other.get_tile((-1, 0), 1), None);
+        assert_eq!(other.get_tile((-2, -1), 1).unwrap().index, 7);
+    }
+
+    #[test]
+    fn missing_animations_are_rejected() {
+        let mut tilemap = new_tilemap();
+        let water = tilemap.add_animation(TileAnimation::new(vec![(4, 0.5), (5, 0.5)]));
+        tilemap
+            .insert_tile(Tile::with_animation((0, 0), water, 0))
+            .unwrap();
+        let mut stamp = TileStamp::capture(&tilemap, (0, 0), (0, 0));
+        stamp.animations.clear();
+
+        let mut other = new_tilemap();
+        let expected: TilemapError = ErrorKind::AnimationDoesNotExist(0).into();
+        assert_eq!(stamp.paste(&mut other, (0, 0), true).unwrap_err(), expected);
+        assert_eq!(other.get_tile((0, 0), 0), None);
+        assert_eq!(other.get_animation(0), None);
+    }
+
+    #[test]
+    fn hex_stamps_do_not_rotate() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .topology(GridTopology::HexY)
+            .finish()
+            .unwrap();
+        let stamp = TileStamp::capture(&tilemap, (0, 0), (1, 1));
+        assert!(stamp.rotate_90().is_none());
+        assert!(stamp.mirror_vertical().is_none());
+    }
+}
@@ -0,0 +1,234 @@
+//! Persistence of chunks so that they can be removed and loaded again later.
+//!
+//! A [`ChunkStore`] can be set on a tilemap with [`TilemapBuilder::store`] or
+//! [`Tilemap::set_store`]. When a chunk is removed with
+//! [`Tilemap::remove_chunk`] it is written to the store, but only if it had
+//! been modified since it was created or loaded. When a chunk is inserted
+//! again it is read from the store instead of being created empty or
+//! generated.
+//!
+//! The [`FileChunkStore`] writes every chunk into its own file in a directory.
+//! Other stores, such as a database or an archive, only have to implement
+//! reading and writing the bytes of a chunk.
+//!
+//! This module requires the **"serialize"** feature.
+//!
+//! # Examples
+//! ```no_run
+//! use bevy_tilemap::prelude::*;
+//! use bevy_tilemap::store::FileChunkStore;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .store(FileChunkStore::new("saves/world"))
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+//!
+//! // The chunk is modified so it is written to `saves/world`.
+//! tilemap.remove_chunk((0, 0)).unwrap();
+//!
+//! // Later on, the chunk is read back with the edit kept.
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+//! ```
+//!
+//! [`ChunkStore`]: ChunkStore
+//! [`FileChunkStore`]: FileChunkStore
+//! [`TilemapBuilder::store`]: crate::tilemap::TilemapBuilder::store
+//! [`Tilemap::set_store`]: crate::tilemap::Tilemap::set_store
+//! [`Tilemap::remove_chunk`]: crate::tilemap::Tilemap::remove_chunk
+
+use crate::lib::*;
+
+/// A store which reads and writes the serialized bytes of chunks.
+pub trait ChunkStore: Send + Sync + 'static {
+    /// Reads the bytes of the chunk at a point, returning `None` if the chunk
+    /// was never written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read.
+    fn read(&self, point: Point2) -> io::Result<Option<Vec<u8>>>;
+
+    /// Writes the bytes of the chunk at a point, replacing any that were
+    /// written before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be written to.
+    fn write(&self, point: Point2, bytes: &[u8]) -> io::Result<()>;
+}
+
+/// A chunk store which writes every chunk into a file in a directory.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FileChunkStore {
+    /// The directory that the chunk files are in.
+    directory: PathBuf,
+}
+
+impl FileChunkStore {
+    /// Constructs a new file chunk store in a directory.
+    ///
+    /// The directory is created when the first chunk is written.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::store::FileChunkStore;
+    ///
+    /// let store = FileChunkStore::new("saves/world");
+    ///
+    /// assert_eq!(store.directory(), std::path::Path::new("saves/world"));
+    /// ```
+    pub fn new<P: Into<PathBuf>>(directory: P) -> FileChunkStore {
+        FileChunkStore {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the directory that the chunk files are in.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::store::FileChunkStore;
+    ///
+    /// let store = FileChunkStore::new("saves/world");
+    ///
+    /// assert_eq!(store.directory(), std::path::Path::new("saves/world"));
+    /// ```
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the path of the file of a chunk.
+    fn chunk_path(&self, point: Point2) -> PathBuf {
+        self.directory
+            .join(format!("{}_{}.chunk", point.x, point.y))
+    }
+}
+
+impl ChunkStore for FileChunkStore {
+    fn read(&self, point: Point2) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.chunk_path(point)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn write(&self, point: Point2, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.chunk_path(point), bytes)
+    }
+}
+
+/// A shared chunk store that is stored in a tilemap.
+#[derive(Clone)]
+pub(crate) struct Store(pub(crate) Arc<dyn ChunkStore>);
+
+impl Debug for Store {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("Store")
+    }
+}
+
+impl PartialEq for Store {
+    fn eq(&self, other: &Store) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tile::Tile, tilemap::Tilemap};
+
+    #[derive(Clone, Default)]
+    struct MemoryChunkStore(Arc<Mutex<HashMap<Point2, Vec<u8>>>>);
+
+    impl ChunkStore for MemoryChunkStore {
+        fn read(&self, point: Point2) -> io::Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(&point).cloned())
+        }
+
+        fn write(&self, point: Point2, bytes: &[u8]) -> io::Result<()> {
+            self.0.lock().unwrap().insert(point, bytes.to_vec());
+            Ok(())
+        }
+    }
+
+    fn new_tilemap(store: MemoryChunkStore) -> Tilemap {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .store(store)
+            .finish()
+            .unwrap()
+    }
+
+    #[test]
+    fn save_only_modified_chunks() {
+        let store = MemoryChunkStore::default();
+        let mut tilemap = new_tilemap(store.clone());
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((1, 0)).unwrap();
+        tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+
+        tilemap.save_chunks().unwrap();
+        let points: Vec<Point2> = store.0.lock().unwrap().keys().copied().collect();
+        assert_eq!(points, vec![Point2::new(0, 0)]);
+
+        // Saved chunks are no longer modified and are not written again.
+        store.0.lock().unwrap().clear();
+        tilemap.save_chunks().unwrap();
+        assert!(store.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn load_bincode_chunks() {
+        let store = MemoryChunkStore::default();
+        let mut tilemap = new_tilemap(store.clone());
+        let point = Point2::new(0, 0);
+        tilemap.insert_chunk(point).unwrap();
+        tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+        let chunk = tilemap.get_chunk(&point).unwrap();
+
+        // As written by a chunk store before the compact format.
+        let bytes = bincode::serialize(chunk).unwrap();
+        store.0.lock().unwrap().insert(point, bytes);
+        let mut tilemap = new_tilemap(store);
+        tilemap.insert_chunk(point).unwrap();
+
+        assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+    }
+
+    #[test]
+    fn removed_chunk_is_loaded_unchanged() {
+        let store = MemoryChunkStore::default();
+        let mut tilemap = new_tilemap(store.clone());
+        let point = Point2::new(0, 0);
+        tilemap.insert_chunk(point).unwrap();
+        tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+        tilemap
+            .insert_tile(Tile::with_z_order((-2, 0), 5, 1))
+            .unwrap();
+        tilemap.save_chunks().unwrap();
+        let chunk = tilemap.get_chunk(&point).unwrap().clone();
+
+        tilemap.remove_chunk(point).unwrap();
+        assert!(tilemap.get_chunk(&point).is_none());
+        tilemap.insert_chunk(point).unwrap();
+
+        assert_eq!(tilemap.get_chunk(&point), Some(&chunk));
+        assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+        assert_eq!(tilemap.get_tile((-2, 0), 1).unwrap().index, 5);
+    }
+}
@@ -0,0 +1,307 @@
+//! Streaming of chunks around a focus entity, such as a camera or a player.
+//!
+//! Adding a [`ChunkStreamer`] component to an entity with a transform will
+//! automatically spawn every chunk that comes within its load radius and
+//! despawn every chunk that leaves its unload radius. Both radiuses are in
+//! chunks. If a chunk does not yet exist it will be inserted before it is
+//! spawned, which is what allows a tilemap to be endless. A tilemap with
+//! dimensions will never stream chunks outside of its bounds.
+//!
+//! Only the chunks that were spawned by a streamer are despawned by it,
+//! chunks that were spawned by hand are left alone. While there are no
+//! streamers at all, the chunks of a tilemap are not touched.
+//!
+//! The unload radius should be larger than the load radius so that chunks on
+//! the edge are not spawned and despawned over and over again as the focus
+//! moves back and forth.
+//!
+//! # Examples
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_tilemap::prelude::*;
+//!
+//! fn setup(mut commands: Commands) {
+//!     commands
+//!         .spawn(Camera2dComponents::default())
+//!         .with(ChunkStreamer::new(1, 2));
+//! }
+//!
+//! App::build()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(TilemapDefaultPlugins)
+//!     .add_startup_system(setup.system())
+//!     .run()
+//! ```
+//!
+//! [`ChunkStreamer`]: ChunkStreamer
+
+use crate::{
+    lib::{bevy::ecs::Local, *},
+    tilemap::Tilemap,
+};
+
+/// A component which streams the chunks of every tilemap around the entity.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ChunkStreamer {
+    /// The radius in chunks of which chunks are spawned.
+    load_radius: u32,
+    /// The radius in chunks of which chunks outside of are despawned.
+    unload_radius: u32,
+}
+
+impl Default for ChunkStreamer {
+    fn default() -> ChunkStreamer {
+        ChunkStreamer::new(1, 2)
+    }
+}
+
+impl ChunkStreamer {
+    /// Constructs a new chunk streamer with a load radius and unload radius in
+    /// chunks.
+    ///
+    /// The unload radius is never smaller than the load radius.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let streamer = ChunkStreamer::new(2, 1);
+    ///
+    /// assert_eq!(streamer.load_radius(), 2);
+    /// assert_eq!(streamer.unload_radius(), 2);
+    /// ```
+    pub fn new(load_radius: u32, unload_radius: u32) -> ChunkStreamer {
+        ChunkStreamer {
+            load_radius,
+            unload_radius: unload_radius.max(load_radius),
+        }
+    }
+
+    /// The radius in chunks of which chunks are spawned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let streamer = ChunkStreamer::new(1, 3);
+    ///
+    /// assert_eq!(streamer.load_radius(), 1);
+    /// ```
+    pub fn load_radius(&self) -> u32 {
+        self.load_radius
+    }
+
+    /// The radius in chunks of which chunks outside of are despawned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let streamer = ChunkStreamer::new(1, 3);
+    ///
+    /// assert_eq!(streamer.unload_radius(), 3);
+    /// ```
+    pub fn unload_radius(&self) -> u32 {
+        self.unload_radius
+    }
+
+    /// Returns the points of the chunks within the load radius of a center
+    /// chunk.
+    fn chunks_to_load(&self, center: Point2) -> impl Iterator<Item = Point2> {
+        let radius = self.load_radius as i32;
+        (-radius..=radius)
+            .flat_map(move |y| (-radius..=radius).map(move |x| Point2::new(x, y)))
+            .map(move |offset| Point2::new(center.x + offset.x, center.y + offset.y))
+    }
+
+    /// Returns `true` if a chunk is outside of the unload radius of a center
+    /// chunk.
+    fn should_unload(&self, center: Point2, point: Point2) -> bool {
+        let radius = self.unload_radius as i32;
+        (point.x - center.x).abs() > radius || (point.y - center.y).abs() > radius
+    }
+}
+
+/// Returns the point of the chunk that a streamer is in, as the tiles are
+/// rendered for the topology of the tilemap.
+fn streamer_chunk(
+    tilemap: &Tilemap,
+    map_transform: &GlobalTransform,
+    transform: &GlobalTransform,
+) -> Point2 {
+    let map_transform = Transform {
+        translation: map_transform.translation,
+        rotation: map_transform.rotation,
+        scale: map_transform.scale,
+    };
+    let position = Vec2::new(transform.translation.x(), transform.translation.y());
+    let tile_point = tilemap.world_to_point(&map_transform, position);
+    tilemap.point_to_chunk_point(tile_point).into()
+}
+
+/// The chunks that were spawned by chunk streamers, for every tilemap entity.
+#[derive(Default)]
+pub(crate) struct StreamedChunks(HashMap<Entity, HashSet<Point2>>);
+
+/// Spawns the chunks that come within range of any chunk streamer and
+/// despawns the streamed chunks which are out of range of all of them.
+///
+/// Chunks which were spawned by other means are never despawned, and nothing
+/// happens to a tilemap while there are no chunk streamers.
+pub(crate) fn chunk_streamer_system(
+    mut streamed: Local<StreamedChunks>,
+    streamer_query: Query<(&ChunkStreamer, &GlobalTransform)>,
+    mut map_query: Query<(Entity, &mut Tilemap, &GlobalTransform)>,
+) {
+    if streamer_query.iter().next().is_none() {
+        return;
+    }
+
+    for (map_entity, mut tilemap, map_transform) in map_query.iter_mut() {
+        let mut to_load: HashSet<Point2> = HashSet::default();
+        let mut centers = Vec::new();
+        for (streamer, transform) in streamer_query.iter() {
+            let center = streamer_chunk(&tilemap, map_transform, transform);
+            to_load.extend(streamer.chunks_to_load(center));
+            centers.push((streamer, center));
+        }
+
+        let chunks = streamed
+            .0
+            .entry(map_entity)
+            .or_insert_with(HashSet::default);
+        // Chunks that were despawned by other means are no longer streamed.
+        chunks.retain(|point| tilemap.spawned_chunks().contains(point));
+
+        let to_unload: Vec<Point2> = chunks
+            .iter()
+            .filter(|point| {
+                centers
+                    .iter()
+                    .all(|(streamer, center)| streamer.should_unload(*center, **point))
+            })
+            .copied()
+            .collect();
+        for point in to_unload.into_iter() {
+            chunks.remove(&point);
+            // Spawned chunks are always in bounds.
+            tilemap.despawn_chunk(point).ok();
+        }
+
+        for point in to_load.into_iter() {
+            if tilemap.spawned_chunks().contains(&point) {
+                continue;
+            }
+            // A chunk that does not exist is generated in the background if
+            // there is a generator, else an empty chunk is inserted.
+            if tilemap.contains_chunk(point)
+                || tilemap.has_generator()
+                || tilemap.insert_chunk(point).is_ok()
+            {
+                // Spawning fails if the chunk is out of the tilemap bounds,
+                // which is expected at the edges of a tilemap with dimensions.
+                if tilemap.spawn_chunk(point).is_ok() {
+                    chunks.insert(point);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entity::TilemapComponents, lib::bevy::app::App, render::GridTopology};
+
+    #[test]
+    fn load_and_unload_radius() {
+        let streamer = ChunkStreamer::new(1, 2);
+        let center = Point2::new(3, -1);
+
+        let to_load: Vec<Point2> = streamer.chunks_to_load(center).collect();
+        assert_eq!(to_load.len(), 9);
+        assert!(to_load.contains(&Point2::new(2, -2)));
+        assert!(to_load.contains(&Point2::new(4, 0)));
+        assert!(to_load
+            .iter()
+            .all(|point| !streamer.should_unload(center, *point)));
+
+        // Chunks between the two radiuses are kept but not loaded.
+        assert!(!streamer.should_unload(center, Point2::new(5, 1)));
+        assert!(!to_load.contains(&Point2::new(5, 1)));
+        assert!(streamer.should_unload(center, Point2::new(6, -1)));
+        assert!(streamer.should_unload(center, Point2::new(3, -4)));
+    }
+
+    #[test]
+    fn only_streamed_chunks_are_unloaded() {
+        let mut app = App::build();
+        app.add_system(chunk_streamer_system.system());
+
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .tile_dimensions(16, 16)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        let map_entity = app.app.world.spawn(TilemapComponents {
+            tilemap,
+            transform: Default::default(),
+            global_transform: Default::default(),
+        });
+        let spawned = |app: &App, point: (i32, i32)| {
+            let tilemap = app.world.get::<Tilemap>(map_entity).unwrap();
+            tilemap.spawned_chunks().contains(&Point2::from(point))
+        };
+
+        // Without a streamer the chunk that was spawned by hand stays.
+        app.app.update();
+        assert!(spawned(&app.app, (0, 0)));
+
+        // A streamer far away loads its own chunks but leaves it alone.
+        let transform = GlobalTransform::from_translation(Vec3::new(640.0, 0.0, 0.0));
+        let streamer = app.app.world.spawn((ChunkStreamer::new(0, 0), transform));
+        app.app.update();
+        assert!(spawned(&app.app, (0, 0)));
+        assert!(spawned(&app.app, (10, 0)));
+
+        // Moving the streamer away unloads the chunk it had loaded.
+        *app.app.world.get_mut::<GlobalTransform>(streamer).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(0.0, 640.0, 0.0));
+        app.app.update();
+        assert!(spawned(&app.app, (0, 0)));
+        assert!(!spawned(&app.app, (10, 0)));
+        assert!(spawned(&app.app, (0, 10)));
+    }
+
+    #[test]
+    fn streamer_chunk_follows_the_topology() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .topology(GridTopology::HexY)
+            .chunk_dimensions(4, 4)
+            .tile_dimensions(16, 16)
+            .finish()
+            .unwrap();
+        let map_transform = GlobalTransform::default();
+
+        // The center of the hex at (1, 1), which is the last column of the
+        // chunk at (0, 0) even though it is more than 2 tiles to the right.
+        let transform = GlobalTransform::from_translation(Vec3::new(36.0, 18.0, 0.0));
+        assert_eq!(
+            streamer_chunk(&tilemap, &map_transform, &transform),
+            Point2::new(0, 0)
+        );
+
+        let transform = GlobalTransform::from_translation(Vec3::new(-68.0, 6.0, 0.0));
+        assert_eq!(
+            streamer_chunk(&tilemap, &map_transform, &transform),
+            Point2::new(-1, 0)
+        );
+    }
+}
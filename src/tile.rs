@@ -1,13 +1,107 @@
 use crate::lib::*;
 
+bitflags! {
+    /// The flip flags of the sprite of a tile.
+    ///
+    /// The diagonal flip swaps the x and y axes of the sprite and is applied
+    /// before the horizontal and vertical flips, the same as in Tiled. Together
+    /// they rotate a sprite in steps of 90 degrees.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Default)]
+    pub struct TileFlip: u8 {
+        /// The sprite is flipped horizontally.
+        const HORIZONTAL = 0b001;
+        /// The sprite is flipped vertically.
+        const VERTICAL = 0b010;
+        /// The sprite is flipped diagonally, swapping its axes.
+        const DIAGONAL = 0b100;
+    }
+}
+
+impl TileFlip {
+    /// Flip flags which rotate a sprite clockwise by 90 degrees.
+    pub const ROTATE_90: TileFlip = TileFlip {
+        bits: TileFlip::DIAGONAL.bits | TileFlip::HORIZONTAL.bits,
+    };
+    /// Flip flags which rotate a sprite by 180 degrees.
+    pub const ROTATE_180: TileFlip = TileFlip {
+        bits: TileFlip::HORIZONTAL.bits | TileFlip::VERTICAL.bits,
+    };
+    /// Flip flags which rotate a sprite clockwise by 270 degrees.
+    pub const ROTATE_270: TileFlip = TileFlip {
+        bits: TileFlip::DIAGONAL.bits | TileFlip::VERTICAL.bits,
+    };
+
+    /// Returns the flip flags of flipping a sprite by these flags followed by
+    /// the other flags.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// assert_eq!(TileFlip::ROTATE_90.then(TileFlip::ROTATE_90), TileFlip::ROTATE_180);
+    /// assert_eq!(TileFlip::ROTATE_90.then(TileFlip::ROTATE_270), TileFlip::empty());
+    /// assert_eq!(
+    ///     TileFlip::HORIZONTAL.then(TileFlip::VERTICAL),
+    ///     TileFlip::ROTATE_180
+    /// );
+    /// ```
+    pub fn then(self, other: TileFlip) -> TileFlip {
+        let (a, b) = (other.to_matrix(), self.to_matrix());
+        let mut product = [[0; 2]; 2];
+        for (row, product_row) in product.iter_mut().enumerate() {
+            for (column, value) in product_row.iter_mut().enumerate() {
+                *value = a[row][0] * b[0][column] + a[row][1] * b[1][column];
+            }
+        }
+        TileFlip::from_matrix(product)
+    }
+
+    /// Returns the matrix that the flags transform the coordinates of a sprite
+    /// with, with the y axis pointing down.
+    pub(crate) fn to_matrix(self) -> [[i32; 2]; 2] {
+        let mut matrix = if self.contains(TileFlip::DIAGONAL) {
+            [[0, 1], [1, 0]]
+        } else {
+            [[1, 0], [0, 1]]
+        };
+        if self.contains(TileFlip::HORIZONTAL) {
+            matrix[0] = [-matrix[0][0], -matrix[0][1]];
+        }
+        if self.contains(TileFlip::VERTICAL) {
+            matrix[1] = [-matrix[1][0], -matrix[1][1]];
+        }
+        matrix
+    }
+
+    /// Returns the flags of a matrix returned by [`to_matrix`].
+    ///
+    /// [`to_matrix`]: TileFlip::to_matrix
+    pub(crate) fn from_matrix(matrix: [[i32; 2]; 2]) -> TileFlip {
+        let mut flip = TileFlip::empty();
+        if matrix[0][0] == 0 {
+            flip |= TileFlip::DIAGONAL;
+        }
+        if matrix[0][0] + matrix[0][1] < 0 {
+            flip |= TileFlip::HORIZONTAL;
+        }
+        if matrix[1][0] + matrix[1][1] < 0 {
+            flip |= TileFlip::VERTICAL;
+        }
+        flip
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, PartialEq, Debug)]
-/// A raw tile composed of simply an index and a color.
+/// A raw tile composed of simply an index, a color and flip flags.
 pub struct RawTile {
     /// The index of the tile in the sprite sheet.
     pub index: usize,
     /// The color, or tint, of the tile.
     pub color: Color,
+    /// The flip flags of the sprite of the tile.
+    pub flip: TileFlip,
 }
 
 // pub struct TileBuilder {
@@ -54,6 +148,12 @@ pub struct Tile {
     pub sprite_index: usize,
     /// The desired tint and alpha of the tile. White means no change.
     pub tint: Color,
+    /// The terrain of the tile, if any, used for auto tiling.
+    pub terrain: Option<u32>,
+    /// The ID of the animation of the tile, if any.
+    pub animation: Option<usize>,
+    /// The flip flags of the sprite of the tile.
+    pub flip: TileFlip,
 }
 
 impl Default for Tile {
@@ -63,6 +163,9 @@ impl Default for Tile {
             z_order: 0,
             sprite_index: 0,
             tint: Color::WHITE,
+            terrain: None,
+            animation: None,
+            flip: TileFlip::empty(),
         }
     }
 }
@@ -89,6 +192,9 @@ impl Tile {
             z_order: 0,
             sprite_index,
             tint: Color::WHITE,
+            terrain: None,
+            animation: None,
+            flip: TileFlip::empty(),
         }
     }
 
@@ -99,6 +205,9 @@ impl Tile {
             z_order,
             sprite_index,
             tint: Color::WHITE,
+            terrain: None,
+            animation: None,
+            flip: TileFlip::empty(),
         }
     }
 
@@ -129,6 +238,9 @@ impl Tile {
             z_order: 0,
             sprite_index,
             tint: tint.into(),
+            terrain: None,
+            animation: None,
+            flip: TileFlip::empty(),
         }
     }
 
@@ -159,33 +271,132 @@ impl Tile {
             z_order,
             sprite_index,
             tint: tint.into(),
+            terrain: None,
+            animation: None,
+            flip: TileFlip::empty(),
+        }
+    }
+
+    /// Creates a new tile with a terrain at a point and a given Z order.
+    ///
+    /// The sprite of the tile is picked by the [`AutoTileset`] registered for
+    /// the terrain in the tilemap. If none is registered, the sprite index of
+    /// 0 is used.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let point = (15, 15);
+    /// let terrain = 1;
+    /// let z_order = 0;
+    ///
+    /// let tile = Tile::with_terrain(point, terrain, z_order);
+    /// ```
+    ///
+    /// [`AutoTileset`]: crate::auto_tile::AutoTileset
+    pub fn with_terrain<P: Into<Point2>>(point: P, terrain: u32, z_order: usize) -> Tile {
+        Tile {
+            point: point.into(),
+            z_order,
+            sprite_index: 0,
+            tint: Color::WHITE,
+            terrain: Some(terrain),
+            animation: None,
+            flip: TileFlip::empty(),
+        }
+    }
+
+    /// Creates a new animated tile at a point and a given Z order.
+    ///
+    /// The animation ID is the one returned by [`Tilemap::add_animation`]. The
+    /// sprite of the tile is set by the current frame of the animation.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// let animation = tilemap.add_animation(TileAnimation::new(vec![(0, 0.5), (1, 0.5)]));
+    /// let z_order = 0;
+    ///
+    /// let tile = Tile::with_animation((15, 15), animation, z_order);
+    /// ```
+    ///
+    /// [`Tilemap::add_animation`]: crate::tilemap::Tilemap::add_animation
+    pub fn with_animation<P: Into<Point2>>(point: P, animation: usize, z_order: usize) -> Tile {
+        Tile {
+            point: point.into(),
+            z_order,
+            sprite_index: 0,
+            tint: Color::WHITE,
+            terrain: None,
+            animation: Some(animation),
+            flip: TileFlip::empty(),
+        }
+    }
+
+    /// Creates a new tile with flip flags and a given sprite index at a point.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let point = (15, 15);
+    /// let sprite_index = 2;
+    ///
+    /// let mirrored = Tile::with_flip(point, sprite_index, TileFlip::HORIZONTAL);
+    /// let rotated = Tile::with_flip(point, sprite_index, TileFlip::ROTATE_90);
+    /// ```
+    pub fn with_flip<P: Into<Point2>>(point: P, sprite_index: usize, flip: TileFlip) -> Tile {
+        Tile {
+            point: point.into(),
+            z_order: 0,
+            sprite_index,
+            tint: Color::WHITE,
+            terrain: None,
+            animation: None,
+            flip,
         }
     }
 }
 
+/// The vertex attributes of the tiles of a layer, which are the sprite
+/// indexes, the colors and the flip flags of every vertex.
+pub(crate) type TileAttributes = (Vec<f32>, Vec<[f32; 4]>, Vec<f32>);
+
 // TODO: Fix both these renderer parts below to only include the current depth.
-/// A utility function that takes an array of `Tile`s and splits the indexes and
-/// colors and returns them as separate vectors for use in the renderer.
-pub(crate) fn dense_tiles_to_attributes(tiles: &[RawTile]) -> (Vec<f32>, Vec<[f32; 4]>) {
+/// A utility function that takes an array of `Tile`s and splits the indexes,
+/// colors and flip flags and returns them as separate vectors for use in the
+/// renderer.
+pub(crate) fn dense_tiles_to_attributes(tiles: &[RawTile]) -> TileAttributes {
     let capacity = tiles.len() * 4;
     let mut tile_indexes: Vec<f32> = Vec::with_capacity(capacity);
     let mut tile_colors: Vec<[f32; 4]> = Vec::with_capacity(capacity);
+    let mut tile_flips: Vec<f32> = Vec::with_capacity(capacity);
     for tile in tiles.iter() {
         tile_indexes.extend([tile.index as f32; 4].iter());
         tile_colors.extend([tile.color.into(); 4].iter());
+        tile_flips.extend([f32::from(tile.flip.bits()); 4].iter());
     }
-    (tile_indexes, tile_colors)
+    (tile_indexes, tile_colors, tile_flips)
 }
 
-/// A utility function that takes a sparse map of `Tile`s and splits the indexes
-/// and colors and returns them as separate vectors for use in the renderer.
+/// A utility function that takes a sparse map of `Tile`s and splits the
+/// indexes, colors and flip flags and returns them as separate vectors for use
+/// in the renderer.
 pub(crate) fn sparse_tiles_to_attributes(
     area: usize,
     tiles: &HashMap<usize, RawTile>,
-) -> (Vec<f32>, Vec<[f32; 4]>) {
+) -> TileAttributes {
     let mut tile_indexes = vec![0.; area * 4];
     // If tiles are set with an alpha of 0, they are discarded.
     let mut tile_colors = vec![[0.0, 0.0, 0.0, 0.0]; area * 4];
+    let mut tile_flips = vec![0.; area * 4];
     for (index, tile) in tiles.iter() {
         for i in 0..4 {
             if let Some(index) = tile_indexes.get_mut(index * 4 + i) {
@@ -194,7 +405,10 @@ pub(crate) fn sparse_tiles_to_attributes(
             if let Some(index) = tile_colors.get_mut(index * 4 + i) {
                 *index = tile.color.into();
             }
+            if let Some(index) = tile_flips.get_mut(index * 4 + i) {
+                *index = f32::from(tile.flip.bits());
+            }
         }
     }
-    (tile_indexes, tile_colors)
+    (tile_indexes, tile_colors, tile_flips)
 }
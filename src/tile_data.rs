@@ -0,0 +1,404 @@
+//! Custom data of tiles, stored in chunks alongside a tilemap.
+//!
+//! A [`TileData`] keeps a value of any type for tiles of a tilemap, such as
+//! if a tile can be walked on, its movement cost or who owns it. The values
+//! are stored per chunk and per layer the same way as the tiles of the
+//! tilemap, so a point and a z order reach the same tile in both. The data is
+//! never rendered and is kept out of the meshes of the chunks entirely.
+//!
+//! The data is usually added as a component to the entity of its tilemap. It
+//! is not changed when chunks of the tilemap are removed, use
+//! [`TileData::remove_chunk`] to drop the data of a chunk along with it.
+//!
+//! With the **"serialize"** feature the data can be serialized with serde, or
+//! written and read with [`TileData::to_bytes`] and [`TileData::from_bytes`].
+//!
+//! # Examples
+//! ```
+//! use bevy_tilemap::prelude::*;
+//! use bevy_tilemap::tile_data::TileData;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! #[derive(Clone, PartialEq, Debug)]
+//! struct Ground {
+//!     walkable: bool,
+//!     cost: u32,
+//! }
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let tilemap = Tilemap::new(texture_atlas_handle);
+//! let mut ground = TileData::new(&tilemap);
+//!
+//! ground.insert((3, 4), 0, Ground { walkable: true, cost: 2 });
+//!
+//! assert_eq!(ground.get((3, 4), 0).map(|ground| ground.cost), Some(2));
+//! assert_eq!(ground.get((3, 4), 1), None);
+//! ```
+//!
+//! [`TileData`]: TileData
+//! [`TileData::remove_chunk`]: TileData::remove_chunk
+//! [`TileData::to_bytes`]: TileData::to_bytes
+//! [`TileData::from_bytes`]: TileData::from_bytes
+
+use crate::{lib::*, tilemap::Tilemap};
+
+#[cfg(feature = "serialize")]
+use crate::{
+    lib::serde::de::DeserializeOwned,
+    tilemap::{ErrorKind, TilemapResult},
+};
+
+/// The data of the tiles in a chunk.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+struct ChunkData<T> {
+    /// A map of tile indexes to the data of the tile, for every z order.
+    layers: Vec<HashMap<usize, T>>,
+}
+
+impl<T> Default for ChunkData<T> {
+    fn default() -> Self {
+        ChunkData { layers: Vec::new() }
+    }
+}
+
+/// Custom data of tiles, stored per chunk and per layer.
+///
+/// The chunks of the data must have the same dimensions as the chunks of the
+/// tilemap it belongs to, which [`TileData::new`] takes care of.
+///
+/// [`TileData::new`]: TileData::new
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileData<T> {
+    /// A chunks dimensions in tiles.
+    chunk_dimensions: Dimension2,
+    /// A map of all the chunks with data at points.
+    chunks: HashMap<Point2, ChunkData<T>>,
+}
+
+impl<T> TileData<T> {
+    /// Constructs empty tile data with the chunk dimensions of a tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::tile_data::TileData;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle);
+    /// let owners: TileData<u32> = TileData::new(&tilemap);
+    /// ```
+    pub fn new(tilemap: &Tilemap) -> TileData<T> {
+        let chunk_dimensions = tilemap.chunk_dimensions();
+        TileData::with_chunk_dimensions(chunk_dimensions.width, chunk_dimensions.height)
+    }
+
+    /// Constructs empty tile data with chunk dimensions in tiles.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let owners: TileData<u32> = TileData::with_chunk_dimensions(32, 32);
+    /// ```
+    pub fn with_chunk_dimensions(width: u32, height: u32) -> TileData<T> {
+        TileData {
+            chunk_dimensions: Dimension2::new(width, height),
+            chunks: HashMap::default(),
+        }
+    }
+
+    /// Sets the data of a tile at a point and z order, returning the previous
+    /// data if there was any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    ///
+    /// assert_eq!(owners.insert((1, 1), 0, 7), None);
+    /// assert_eq!(owners.insert((1, 1), 0, 8), Some(7));
+    /// ```
+    pub fn insert<P>(&mut self, point: P, z_order: usize, data: T) -> Option<T>
+    where
+        P: Into<Point2>,
+    {
+        let (chunk_point, index) = self.chunk_point_and_index(point.into());
+        let layers = &mut self.chunks.entry(chunk_point).or_default().layers;
+        if layers.len() <= z_order {
+            layers.resize_with(z_order + 1, HashMap::default);
+        }
+        layers[z_order].insert(index, data)
+    }
+
+    /// Gets the data of a tile at a point and z order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    /// owners.insert((-40, 3), 2, 7);
+    ///
+    /// assert_eq!(owners.get((-40, 3), 2), Some(&7));
+    /// assert_eq!(owners.get((-40, 3), 0), None);
+    /// ```
+    pub fn get<P>(&self, point: P, z_order: usize) -> Option<&T>
+    where
+        P: Into<Point2>,
+    {
+        let (chunk_point, index) = self.chunk_point_and_index(point.into());
+        self.chunks
+            .get(&chunk_point)?
+            .layers
+            .get(z_order)?
+            .get(&index)
+    }
+
+    /// Gets the mutable data of a tile at a point and z order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    /// owners.insert((1, 1), 0, 7);
+    ///
+    /// if let Some(owner) = owners.get_mut((1, 1), 0) {
+    ///     *owner = 8;
+    /// }
+    ///
+    /// assert_eq!(owners.get((1, 1), 0), Some(&8));
+    /// ```
+    pub fn get_mut<P>(&mut self, point: P, z_order: usize) -> Option<&mut T>
+    where
+        P: Into<Point2>,
+    {
+        let (chunk_point, index) = self.chunk_point_and_index(point.into());
+        self.chunks
+            .get_mut(&chunk_point)?
+            .layers
+            .get_mut(z_order)?
+            .get_mut(&index)
+    }
+
+    /// Removes the data of a tile at a point and z order, returning it if
+    /// there was any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    /// owners.insert((1, 1), 0, 7);
+    ///
+    /// assert_eq!(owners.remove((1, 1), 0), Some(7));
+    /// assert_eq!(owners.get((1, 1), 0), None);
+    /// ```
+    pub fn remove<P>(&mut self, point: P, z_order: usize) -> Option<T>
+    where
+        P: Into<Point2>,
+    {
+        let (chunk_point, index) = self.chunk_point_and_index(point.into());
+        let chunk = self.chunks.get_mut(&chunk_point)?;
+        let data = chunk.layers.get_mut(z_order)?.remove(&index);
+        if chunk.layers.iter().all(|layer| layer.is_empty()) {
+            self.chunks.remove(&chunk_point);
+        }
+        data
+    }
+
+    /// Removes the data of every tile in a chunk.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    /// owners.insert((1, 1), 0, 7);
+    /// owners.insert((40, 1), 0, 8);
+    ///
+    /// owners.remove_chunk((0, 0));
+    ///
+    /// assert_eq!(owners.get((1, 1), 0), None);
+    /// assert_eq!(owners.get((40, 1), 0), Some(&8));
+    /// ```
+    pub fn remove_chunk<P>(&mut self, chunk_point: P)
+    where
+        P: Into<Point2>,
+    {
+        self.chunks.remove(&chunk_point.into());
+    }
+
+    /// Returns an iterator over the point, z order and data of every tile
+    /// with data, in no particular order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    /// owners.insert((1, 1), 0, 7);
+    /// owners.insert((40, -2), 1, 8);
+    ///
+    /// let mut tiles: Vec<(Point2, usize, &u32)> = owners.iter().collect();
+    /// tiles.sort();
+    ///
+    /// assert_eq!(tiles, vec![(Point2::new(1, 1), 0, &7), (Point2::new(40, -2), 1, &8)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, usize, &T)> + '_ {
+        let dimensions = self.chunk_dimensions;
+        self.chunks.iter().flat_map(move |(chunk_point, chunk)| {
+            chunk
+                .layers
+                .iter()
+                .enumerate()
+                .flat_map(move |(z_order, layer)| {
+                    layer.iter().map(move |(index, data)| {
+                        let tile_point = dimensions.decode_point_unchecked(*index);
+                        let width = dimensions.width as i32;
+                        let height = dimensions.height as i32;
+                        let point = Point2::new(
+                            tile_point.x + (width * chunk_point.x) - (width / 2),
+                            tile_point.y + (height * chunk_point.y) - (height / 2),
+                        );
+                        (point, z_order, data)
+                    })
+                })
+        })
+    }
+
+    /// Returns `true` if no tile has data.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Returns the point of the chunk that a point is in and the index of
+    /// the point in the chunk, the same as in a tilemap.
+    fn chunk_point_and_index(&self, point: Point2) -> (Point2, usize) {
+        let width = self.chunk_dimensions.width as f32;
+        let height = self.chunk_dimensions.height as f32;
+        let chunk_point = Point2::new(
+            ((point.x as f32 + width / 2.0) / width).floor() as i32,
+            ((point.y as f32 + height / 2.0) / height).floor() as i32,
+        );
+        let width = self.chunk_dimensions.width as i32;
+        let height = self.chunk_dimensions.height as i32;
+        let tile_point = Point2::new(
+            point.x - (width * chunk_point.x) + (width / 2),
+            point.y - (height * chunk_point.y) + (height / 2),
+        );
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        (chunk_point, index)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<T> TileData<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Serializes the data of every tile into bytes. Requires the
+    /// **"serialize"** feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data could not be serialized.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tile_data::TileData;
+    ///
+    /// let mut owners = TileData::with_chunk_dimensions(32, 32);
+    /// owners.insert((1, 1), 0, 7u32);
+    ///
+    /// let bytes = owners.to_bytes().unwrap();
+    /// let loaded: TileData<u32> = TileData::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(loaded, owners);
+    /// ```
+    pub fn to_bytes(&self) -> TilemapResult<Vec<u8>> {
+        Ok(bincode::serialize(self).map_err(|err| ErrorKind::TileData(err.to_string()))?)
+    }
+
+    /// Deserializes tile data from bytes written with [`to_bytes`]. Requires
+    /// the **"serialize"** feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not valid tile data of the type.
+    ///
+    /// [`to_bytes`]: TileData::to_bytes
+    pub fn from_bytes(bytes: &[u8]) -> TilemapResult<TileData<T>> {
+        Ok(bincode::deserialize(bytes).map_err(|err| ErrorKind::TileData(err.to_string()))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_follows_the_chunks_of_the_tilemap() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .finish()
+            .unwrap();
+        let mut data = TileData::new(&tilemap);
+        for &(x, y) in [(-3, 0), (-2, 0), (1, 1), (2, 1), (5, -7)].iter() {
+            data.insert((x, y), 1, x * 10 + y);
+        }
+
+        let mut points: Vec<(Point2, usize, i32)> = data
+            .iter()
+            .map(|(point, z, data)| (point, z, *data))
+            .collect();
+        points.sort();
+        assert_eq!(
+            points,
+            vec![
+                (Point2::new(-3, 0), 1, -30),
+                (Point2::new(-2, 0), 1, -20),
+                (Point2::new(1, 1), 1, 11),
+                (Point2::new(2, 1), 1, 21),
+                (Point2::new(5, -7), 1, 43),
+            ]
+        );
+
+        // The data is in the same chunks as the tiles would be.
+        let chunk_point: Point2 = tilemap.point_to_chunk_point((2, 1)).into();
+        data.remove_chunk(chunk_point);
+        assert_eq!(data.get((2, 1), 1), None);
+        assert_eq!(data.get((1, 1), 1), Some(&11));
+
+        assert_eq!(data.remove((-3, 0), 1), Some(-30));
+        assert_eq!(data.remove((-3, 0), 1), None);
+        assert_eq!(data.get((-2, 0), 1), Some(&-20));
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn bytes_round_trip() {
+        let mut data = TileData::with_chunk_dimensions(8, 8);
+        data.insert((1, 1), 0, String::from("grass"));
+        data.insert((-20, 9), 3, String::from("water"));
+
+        let bytes = data.to_bytes().unwrap();
+        let loaded: TileData<String> = TileData::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded, data);
+        let err = TileData::<String>::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(err.to_string().starts_with("the tile data is invalid"));
+    }
+}
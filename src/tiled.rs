@@ -0,0 +1,956 @@
+//! Importing of maps made with the [Tiled] map editor.
+//!
+//! A [`TiledMap`] is read from a `.tmx` file along with all of its tilesets,
+//! including external `.tsx` tilesets. Every tile layer becomes a z layer in
+//! the order that they are in the file, with layers in groups flattened. A
+//! layer that is at least half filled becomes a [`LayerKind::Dense`] layer,
+//! otherwise it becomes a [`LayerKind::Sparse`] layer. The opacity and tint
+//! color of a layer and its groups are combined into the tint of its tiles.
+//!
+//! Orthogonal and hexagonal maps are supported, hexagonal maps are mapped to
+//! the [`GridTopology`] with the same stagger axis that looks the same once
+//! the rows are flipped. Tile data in the XML, CSV and base64 encodings are
+//! supported, with base64 data optionally compressed with zlib or gzip.
+//! Infinite maps are supported as well.
+//!
+//! The horizontal, vertical and diagonal flip flags of the tiles are kept as
+//! the [`TileFlip`] of the tiles. The 120 degree rotation of hexagonal tiles
+//! is not supported and ignored.
+//!
+//! Tiled counts rows from the top while the tilemap counts them from the
+//! bottom, the tile in column `x` and row `y` is placed at the point
+//! `(x, height - 1 - y)`. The sprite index of a tile is its index in the
+//! tileset added to the tile count of all the tilesets before it, so the
+//! texture atlas is expected to contain the tiles of every tileset in order.
+//!
+//! This module requires the **"tiled"** feature.
+//!
+//! # Examples
+//! ```no_run
+//! use bevy_tilemap::tiled::TiledMap;
+//! use bevy::asset::HandleId;
+//! use bevy::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let map = TiledMap::from_path("assets/maps/dungeon.tmx").unwrap();
+//! let tilemap = map.to_tilemap(texture_atlas_handle).unwrap();
+//! ```
+//!
+//! [Tiled]: https://www.mapeditor.org/
+//! [`TiledMap`]: TiledMap
+//! [`LayerKind::Dense`]: crate::chunk::LayerKind::Dense
+//! [`LayerKind::Sparse`]: crate::chunk::LayerKind::Sparse
+//! [`GridTopology`]: crate::render::GridTopology
+//! [`TileFlip`]: crate::tile::TileFlip
+
+use crate::{
+    chunk::LayerKind,
+    lib::{
+        base64, flate2,
+        io::Read,
+        xml::reader::{EventReader, XmlEvent},
+        *,
+    },
+    render::GridTopology,
+    tile::{Tile, TileFlip},
+    tilemap::{Tilemap, TilemapBuilder, TilemapError},
+};
+
+/// The bits of a GID which are used for the flip flags.
+const FLIP_MASK: u32 = 0xF000_0000;
+
+#[derive(Debug)]
+/// The kinds of errors that can occur when importing.
+enum ErrorKind {
+    /// A file could not be read.
+    Io(io::Error),
+    /// The XML is malformed.
+    Xml(String),
+    /// An element that is required is missing.
+    MissingElement(&'static str),
+    /// An attribute that is required is missing from an element.
+    MissingAttribute(String, &'static str),
+    /// An attribute has a value that could not be parsed.
+    InvalidAttribute(String, &'static str, String),
+    /// The orientation of the map is not supported.
+    UnsupportedOrientation(String),
+    /// The encoding of the tile data is not supported.
+    UnsupportedEncoding(String),
+    /// The compression of the tile data is not supported.
+    UnsupportedCompression(String),
+    /// The tile data could not be decoded.
+    InvalidData(String),
+    /// A tile has a GID which is not in any tileset.
+    UnknownGid(u32),
+    /// The tile count of a tileset does not fit in its integer type.
+    TileCountOverflow(String),
+    /// The tilemap could not be constructed.
+    Tilemap(TilemapError),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use ErrorKind::*;
+        match self {
+            Io(err) => write!(f, "could not read the map: {}", err),
+            Xml(err) => write!(f, "the map is malformed: {}", err),
+            MissingElement(name) => write!(f, "the `{}` element is missing", name),
+            MissingAttribute(element, name) => write!(
+                f,
+                "the `{}` attribute is missing from the `{}` element",
+                name, element
+            ),
+            InvalidAttribute(element, name, value) => write!(
+                f,
+                "the `{}` attribute of the `{}` element has an invalid value `{}`",
+                name, element, value
+            ),
+            UnsupportedOrientation(orientation) => {
+                write!(f, "the `{}` orientation is not supported", orientation)
+            }
+            UnsupportedEncoding(encoding) => {
+                write!(f, "the `{}` encoding is not supported", encoding)
+            }
+            UnsupportedCompression(compression) => {
+                write!(f, "the `{}` compression is not supported", compression)
+            }
+            InvalidData(err) => write!(f, "the tile data is invalid: {}", err),
+            UnknownGid(gid) => write!(f, "the GID {} is not in any tileset", gid),
+            TileCountOverflow(tileset) => {
+                write!(f, "the tile count of tileset `{}` is too large", tileset)
+            }
+            Tilemap(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The error type for importing a Tiled map.
+pub struct TiledError(Box<ErrorKind>);
+
+impl Display for TiledError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for TiledError {}
+
+impl From<ErrorKind> for TiledError {
+    fn from(kind: ErrorKind) -> TiledError {
+        TiledError(Box::new(kind))
+    }
+}
+
+impl From<io::Error> for TiledError {
+    fn from(err: io::Error) -> TiledError {
+        TiledError(Box::new(ErrorKind::Io(err)))
+    }
+}
+
+impl From<TilemapError> for TiledError {
+    fn from(err: TilemapError) -> TiledError {
+        TiledError(Box::new(ErrorKind::Tilemap(err)))
+    }
+}
+
+/// A Tiled import result.
+pub type TiledResult<T> = Result<T, TiledError>;
+
+bitflags! {
+    /// The flip flags of a tile, which Tiled stores in the high bits of a GID.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct TiledFlip: u32 {
+        /// The tile is flipped horizontally.
+        const HORIZONTAL = 0x8000_0000;
+        /// The tile is flipped vertically.
+        const VERTICAL = 0x4000_0000;
+        /// The tile is flipped diagonally, which is used for rotations.
+        const DIAGONAL = 0x2000_0000;
+        /// The hexagonal tile is rotated by 120 degrees.
+        const ROTATED_HEX_120 = 0x1000_0000;
+    }
+}
+
+impl From<TiledFlip> for TileFlip {
+    fn from(flip: TiledFlip) -> TileFlip {
+        let mut tile_flip = TileFlip::empty();
+        tile_flip.set(TileFlip::HORIZONTAL, flip.contains(TiledFlip::HORIZONTAL));
+        tile_flip.set(TileFlip::VERTICAL, flip.contains(TiledFlip::VERTICAL));
+        tile_flip.set(TileFlip::DIAGONAL, flip.contains(TiledFlip::DIAGONAL));
+        tile_flip
+    }
+}
+
+/// A tile of a Tiled layer.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub struct TiledTile {
+    /// The point of the tile in the tilemap.
+    pub point: Point2,
+    /// The sprites index in the texture atlas.
+    pub sprite_index: usize,
+    /// The flip flags of the tile.
+    pub flip: TiledFlip,
+}
+
+/// A tile layer of a Tiled map.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct TiledLayer {
+    /// The name of the layer.
+    pub name: String,
+    /// The kind of layer that suits the amount of tiles in it.
+    pub kind: LayerKind,
+    /// If the layer and all of its groups are visible.
+    pub visible: bool,
+    /// The tint of the tiles, combining the tint colors and opacities of the
+    /// layer and all of its groups.
+    pub tint: Color,
+    /// The tiles of the layer.
+    pub tiles: Vec<TiledTile>,
+}
+
+/// A tileset of a Tiled map.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct TiledTileset {
+    /// The name of the tileset.
+    pub name: String,
+    /// The GID of the first tile in the tileset.
+    pub first_gid: u32,
+    /// The amount of tiles in the tileset.
+    pub tile_count: u32,
+    /// The width of a tile in pixels.
+    pub tile_width: u32,
+    /// The height of a tile in pixels.
+    pub tile_height: u32,
+    /// The path to the image of the tileset, if it uses a single image.
+    pub image: Option<PathBuf>,
+}
+
+/// A map that was made with Tiled.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct TiledMap {
+    /// The grid topology of the map.
+    pub topology: GridTopology,
+    /// The width of the map in tiles.
+    pub width: u32,
+    /// The height of the map in tiles.
+    pub height: u32,
+    /// The width of a tile in pixels.
+    pub tile_width: u32,
+    /// The height of a tile in pixels.
+    pub tile_height: u32,
+    /// The tilesets of the map, ordered by their first GID.
+    pub tilesets: Vec<TiledTileset>,
+    /// The tile layers of the map, from the bottom to the top.
+    pub layers: Vec<TiledLayer>,
+}
+
+impl TiledMap {
+    /// Reads a Tiled map from a `.tmx` file.
+    ///
+    /// External tilesets are read relative to the directory of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any file could not be read or is not a valid
+    /// Tiled map or tileset, or if the map uses a feature that is not
+    /// supported.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use bevy_tilemap::tiled::TiledMap;
+    ///
+    /// let map = TiledMap::from_path("assets/maps/dungeon.tmx").unwrap();
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> TiledResult<TiledMap> {
+        let path = path.as_ref();
+        let file = fs::File::open(path)?;
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        TiledMap::from_reader(file, directory)
+    }
+
+    /// Reads a Tiled map from a reader.
+    ///
+    /// External tilesets are read relative to the directory given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the map or any external tileset could not be read
+    /// or is not valid, or if the map uses a feature that is not supported.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tiled::TiledMap;
+    /// use std::path::Path;
+    ///
+    /// let tmx = r#"
+    ///     <map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16">
+    ///         <tileset firstgid="1" name="tiles" tilewidth="16" tileheight="16" tilecount="4"/>
+    ///         <layer name="ground" width="2" height="2">
+    ///             <data encoding="csv">1,2,3,4</data>
+    ///         </layer>
+    ///     </map>
+    /// "#;
+    ///
+    /// let map = TiledMap::from_reader(tmx.as_bytes(), Path::new("")).unwrap();
+    ///
+    /// assert_eq!(map.layers[0].tiles.len(), 4);
+    /// ```
+    pub fn from_reader<R: io::Read>(reader: R, directory: &Path) -> TiledResult<TiledMap> {
+        let root = Element::parse(reader)?;
+        if root.name != "map" {
+            return Err(ErrorKind::MissingElement("map").into());
+        }
+
+        let width: u32 = root.parse_attribute("width")?;
+        let height: u32 = root.parse_attribute("height")?;
+        let topology = match root.attribute("orientation").unwrap_or("orthogonal") {
+            "orthogonal" => GridTopology::Square,
+            "hexagonal" => {
+                let odd = root.attribute("staggerindex").unwrap_or("odd") == "odd";
+                if root.attribute("staggeraxis") == Some("x") {
+                    // Tiled shifts the staggered columns down, which are the
+                    // columns this crate shifts up for the other parity.
+                    if odd {
+                        GridTopology::HexEvenCols
+                    } else {
+                        GridTopology::HexOddCols
+                    }
+                } else if odd != (height % 2 == 0) {
+                    // Rows are counted from the bottom, which swaps odd and
+                    // even rows if the map has an even height.
+                    GridTopology::HexOddRows
+                } else {
+                    GridTopology::HexEvenRows
+                }
+            }
+            orientation => {
+                return Err(ErrorKind::UnsupportedOrientation(orientation.to_string()).into())
+            }
+        };
+
+        let mut tilesets = Vec::new();
+        for element in root.children("tileset") {
+            tilesets.push(TiledTileset::parse(element, directory)?);
+        }
+        tilesets.sort_by_key(|tileset| tileset.first_gid);
+
+        let mut map = TiledMap {
+            topology,
+            width,
+            height,
+            tile_width: root.parse_attribute("tilewidth")?,
+            tile_height: root.parse_attribute("tileheight")?,
+            tilesets,
+            layers: Vec::new(),
+        };
+        let group = Group {
+            visible: true,
+            tint: [1.0; 4],
+        };
+        map.parse_layers(&root, group)?;
+
+        Ok(map)
+    }
+
+    /// Constructs a tilemap with the topology, tile dimensions and layers of
+    /// the map and inserts the tiles of every visible layer.
+    ///
+    /// The tilemap automatically creates chunks, which allows it to hold maps
+    /// of any size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tiles could not be inserted into the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tiled::TiledMap;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    /// use std::path::Path;
+    ///
+    /// // This must be set in Asset<TextureAtlas>.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tmx = r#"
+    ///     <map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16">
+    ///         <tileset firstgid="1" name="tiles" tilewidth="16" tileheight="16" tilecount="4"/>
+    ///         <layer name="ground" width="2" height="2">
+    ///             <data encoding="csv">1,2,3,4</data>
+    ///         </layer>
+    ///     </map>
+    /// "#;
+    ///
+    /// let map = TiledMap::from_reader(tmx.as_bytes(), Path::new("")).unwrap();
+    /// let mut tilemap = map.to_tilemap(texture_atlas_handle).unwrap();
+    ///
+    /// // The top left tile of the map.
+    /// assert_eq!(tilemap.get_tile((0, 1), 0).unwrap().index, 0);
+    /// ```
+    pub fn to_tilemap(&self, texture_atlas: Handle<TextureAtlas>) -> TiledResult<Tilemap> {
+        let mut builder = TilemapBuilder::new()
+            .texture_atlas(texture_atlas)
+            .topology(self.topology)
+            .tile_dimensions(self.tile_width, self.tile_height)
+            .z_layers(self.layers.len())
+            .auto_chunk();
+        for (z_order, layer) in self.layers.iter().enumerate() {
+            builder = builder.add_layer(layer.kind, z_order);
+        }
+        let mut tilemap = builder.finish()?;
+
+        let tiles = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.visible)
+            .flat_map(|(z_order, layer)| {
+                layer.tiles.iter().map(move |tile| Tile {
+                    point: tile.point,
+                    z_order,
+                    sprite_index: tile.sprite_index,
+                    tint: layer.tint,
+                    flip: tile.flip.into(),
+                    ..Tile::default()
+                })
+            });
+        tilemap.insert_tiles(tiles)?;
+
+        Ok(tilemap)
+    }
+
+    /// Parses the tile layers in an element, recursing into groups.
+    fn parse_layers(&mut self, element: &Element, group: Group) -> TiledResult<()> {
+        for child in element.children.iter() {
+            match child.name.as_str() {
+                "layer" => {
+                    let layer = self.parse_layer(child, group.with(child)?)?;
+                    self.layers.push(layer);
+                }
+                "group" => self.parse_layers(child, group.with(child)?)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a tile layer.
+    fn parse_layer(&self, element: &Element, group: Group) -> TiledResult<TiledLayer> {
+        let data = element
+            .child("data")
+            .ok_or(ErrorKind::MissingElement("data"))?;
+
+        // Infinite maps store their tiles in chunks.
+        let mut regions = Vec::new();
+        if data.child("chunk").is_some() {
+            for chunk in data.children("chunk") {
+                regions.push((
+                    chunk.parse_attribute::<i32>("x")?,
+                    chunk.parse_attribute::<i32>("y")?,
+                    chunk.parse_attribute::<u32>("width")?,
+                    decode_gids(data, chunk)?,
+                ));
+            }
+        } else {
+            regions.push((0, 0, self.width, decode_gids(data, data)?));
+        }
+
+        let mut area = 0;
+        let mut tiles = Vec::new();
+        for (x, y, width, gids) in regions.into_iter() {
+            area += gids.len();
+            for (i, gid) in gids.into_iter().enumerate() {
+                if gid & !FLIP_MASK == 0 {
+                    continue;
+                }
+                let column = x + (i as u32 % width.max(1)) as i32;
+                let row = y + (i as u32 / width.max(1)) as i32;
+                tiles.push(TiledTile {
+                    point: Point2::new(column, self.height as i32 - 1 - row),
+                    sprite_index: self.sprite_index(gid & !FLIP_MASK)?,
+                    flip: TiledFlip::from_bits_truncate(gid & FLIP_MASK),
+                });
+            }
+        }
+
+        let kind = LayerKind::for_tile_count(tiles.len(), area);
+        let [r, g, b, a] = group.tint;
+
+        Ok(TiledLayer {
+            name: element.attribute("name").unwrap_or("").to_string(),
+            kind,
+            visible: group.visible,
+            tint: Color::rgba(r, g, b, a),
+            tiles,
+        })
+    }
+
+    /// Returns the sprite index of a GID without flip flags.
+    fn sprite_index(&self, gid: u32) -> TiledResult<usize> {
+        let mut offset = 0;
+        for tileset in self.tilesets.iter() {
+            if gid >= tileset.first_gid && gid - tileset.first_gid < tileset.tile_count {
+                return Ok((offset + gid - tileset.first_gid) as usize);
+            }
+            offset += tileset.tile_count;
+        }
+        Err(ErrorKind::UnknownGid(gid).into())
+    }
+}
+
+impl TiledTileset {
+    /// Parses a tileset element of a map, reading the external tileset if it
+    /// has a source.
+    fn parse(element: &Element, directory: &Path) -> TiledResult<TiledTileset> {
+        let first_gid = element.parse_attribute("firstgid")?;
+        match element.attribute("source") {
+            Some(source) => {
+                let path = directory.join(source);
+                let tileset = Element::parse(fs::File::open(&path)?)?;
+                if tileset.name != "tileset" {
+                    return Err(ErrorKind::MissingElement("tileset").into());
+                }
+                let directory = path.parent().unwrap_or_else(|| Path::new(""));
+                TiledTileset::parse_inner(&tileset, first_gid, directory)
+            }
+            None => TiledTileset::parse_inner(element, first_gid, directory),
+        }
+    }
+
+    /// Parses the contents of a tileset.
+    fn parse_inner(
+        element: &Element,
+        first_gid: u32,
+        directory: &Path,
+    ) -> TiledResult<TiledTileset> {
+        let tile_width = element.parse_size_attribute("tilewidth")?;
+        let tile_height = element.parse_size_attribute("tileheight")?;
+        let name = element.attribute("name").unwrap_or("");
+        let image = element.child("image");
+
+        let tile_count = match (element.attribute("tilecount"), image) {
+            (Some(_), _) => element.parse_attribute("tilecount")?,
+            // Older versions of Tiled do not save the tile count.
+            (None, Some(image)) => {
+                let margin: u32 = element.parse_attribute_or("margin", 0)?;
+                let spacing: u32 = element.parse_attribute_or("spacing", 0)?;
+                let width: u32 = image.parse_attribute("width")?;
+                let height: u32 = image.parse_attribute("height")?;
+                let tiles_along = |pixels: u32, size: u32| {
+                    let step = size.checked_add(spacing)?;
+                    let border = margin.checked_mul(2)?;
+                    Some(pixels.checked_add(spacing)?.saturating_sub(border) / step)
+                };
+                tiles_along(width, tile_width)
+                    .and_then(|columns| columns.checked_mul(tiles_along(height, tile_height)?))
+                    .ok_or_else(|| ErrorKind::TileCountOverflow(name.to_string()))?
+            }
+            (None, None) => {
+                return Err(ErrorKind::MissingAttribute("tileset".to_string(), "tilecount").into())
+            }
+        };
+
+        Ok(TiledTileset {
+            name: name.to_string(),
+            first_gid,
+            tile_count,
+            tile_width,
+            tile_height,
+            image: image
+                .and_then(|image| image.attribute("source"))
+                .map(|source| directory.join(source)),
+        })
+    }
+}
+
+/// The properties of the groups that a layer is in.
+#[derive(Copy, Clone)]
+struct Group {
+    /// If every group is visible.
+    visible: bool,
+    /// The combined tint of every group, with the opacity in the alpha.
+    tint: [f32; 4],
+}
+
+impl Group {
+    /// Combines the group with the visibility, opacity and tint of a layer or
+    /// group element.
+    fn with(self, element: &Element) -> TiledResult<Group> {
+        let visible: u8 = element.parse_attribute_or("visible", 1)?;
+        let opacity: f32 = element.parse_attribute_or("opacity", 1.0)?;
+        let tint = match element.attribute("tintcolor") {
+            Some(value) => parse_color(value).ok_or_else(|| {
+                ErrorKind::InvalidAttribute(element.name.clone(), "tintcolor", value.to_string())
+            })?,
+            None => [1.0; 4],
+        };
+        Ok(Group {
+            visible: self.visible && visible != 0,
+            tint: [
+                self.tint[0] * tint[0],
+                self.tint[1] * tint[1],
+                self.tint[2] * tint[2],
+                self.tint[3] * tint[3] * opacity,
+            ],
+        })
+    }
+}
+
+/// Parses a Tiled color in the `#AARRGGBB` or `#RRGGBB` format.
+fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let value = value.trim_start_matches('#');
+    let argb = match value.len() {
+        6 => 0xFF00_0000 | u32::from_str_radix(value, 16).ok()?,
+        8 => u32::from_str_radix(value, 16).ok()?,
+        _ => return None,
+    };
+    let channel = |shift: u32| ((argb >> shift) & 0xFF) as f32 / 255.0;
+    Some([channel(16), channel(8), channel(0), channel(24)])
+}
+
+/// Decodes the GIDs of a data element, or a chunk in it, in the encoding and
+/// compression of the data element.
+fn decode_gids(data: &Element, element: &Element) -> TiledResult<Vec<u32>> {
+    match data.attribute("encoding") {
+        None => element
+            .children("tile")
+            .map(|tile| tile.parse_attribute_or("gid", 0))
+            .collect(),
+        Some("csv") => element
+            .text
+            .split(',')
+            .map(|gid| gid.trim())
+            .filter(|gid| !gid.is_empty())
+            .map(|gid| {
+                gid.parse::<u32>()
+                    .map_err(|err| ErrorKind::InvalidData(err.to_string()).into())
+            })
+            .collect(),
+        Some("base64") => {
+            let bytes = base64::decode(element.text.trim())
+                .map_err(|err| ErrorKind::InvalidData(err.to_string()))?;
+            let bytes = match data.attribute("compression") {
+                None => bytes,
+                Some("zlib") => {
+                    let mut decompressed = Vec::new();
+                    flate2::read::ZlibDecoder::new(bytes.as_slice())
+                        .read_to_end(&mut decompressed)
+                        .map_err(|err| ErrorKind::InvalidData(err.to_string()))?;
+                    decompressed
+                }
+                Some("gzip") => {
+                    let mut decompressed = Vec::new();
+                    flate2::read::GzDecoder::new(bytes.as_slice())
+                        .read_to_end(&mut decompressed)
+                        .map_err(|err| ErrorKind::InvalidData(err.to_string()))?;
+                    decompressed
+                }
+                Some(compression) => {
+                    return Err(ErrorKind::UnsupportedCompression(compression.to_string()).into())
+                }
+            };
+            if bytes.len() % 4 != 0 {
+                return Err(ErrorKind::InvalidData(format!(
+                    "{} bytes is not a multiple of 4",
+                    bytes.len()
+                ))
+                .into());
+            }
+            Ok(bytes
+                .chunks(4)
+                .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+                .collect())
+        }
+        Some(encoding) => Err(ErrorKind::UnsupportedEncoding(encoding.to_string()).into()),
+    }
+}
+
+/// A minimal XML element tree, which is all that is needed of a Tiled file.
+struct Element {
+    /// The local name of the element.
+    name: String,
+    /// The attributes of the element.
+    attributes: HashMap<String, String>,
+    /// The child elements in the order they are in.
+    children: Vec<Element>,
+    /// All the text directly in the element.
+    text: String,
+}
+
+impl Element {
+    /// Parses the root element of an XML document.
+    fn parse<R: io::Read>(reader: R) -> TiledResult<Element> {
+        let mut stack: Vec<Element> = Vec::new();
+        for event in EventReader::new(reader) {
+            match event.map_err(|err| ErrorKind::Xml(err.to_string()))? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => stack.push(Element {
+                    name: name.local_name,
+                    attributes: attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.name.local_name, attribute.value))
+                        .collect(),
+                    children: Vec::new(),
+                    text: String::new(),
+                }),
+                XmlEvent::EndElement { .. } => {
+                    let element = stack.pop().ok_or_else(|| {
+                        ErrorKind::Xml("an element was closed that was never opened".to_string())
+                    })?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(ErrorKind::Xml("the document has no root element".to_string()).into())
+    }
+
+    /// Returns the value of an attribute, if it exists.
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|value| value.as_str())
+    }
+
+    /// Parses the value of an attribute which is required.
+    fn parse_attribute<T: ::std::str::FromStr>(&self, name: &'static str) -> TiledResult<T> {
+        match self.attribute(name) {
+            Some(value) => value.trim().parse().map_err(|_| {
+                ErrorKind::InvalidAttribute(self.name.clone(), name, value.to_string()).into()
+            }),
+            None => Err(ErrorKind::MissingAttribute(self.name.clone(), name).into()),
+        }
+    }
+
+    /// Parses the value of an attribute, or returns a default if it does not
+    /// exist.
+    fn parse_attribute_or<T: ::std::str::FromStr>(
+        &self,
+        name: &'static str,
+        default: T,
+    ) -> TiledResult<T> {
+        match self.attribute(name) {
+            Some(_) => self.parse_attribute(name),
+            None => Ok(default),
+        }
+    }
+
+    /// Parses the value of an attribute which is a size in pixels, which can
+    /// not be zero.
+    fn parse_size_attribute(&self, name: &'static str) -> TiledResult<u32> {
+        match self.parse_attribute(name)? {
+            0 => Err(ErrorKind::InvalidAttribute(self.name.clone(), name, "0".to_string()).into()),
+            size => Ok(size),
+        }
+    }
+
+    /// Returns the first child element with a name.
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns all the child elements with a name.
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_with_data(attributes: &str, data: &str) -> TiledResult<TiledMap> {
+        let tmx = format!(
+            r#"<map orientation="orthogonal" width="2" height="2" tilewidth="8" tileheight="8">
+                <tileset firstgid="1" name="a" tilewidth="8" tileheight="8" tilecount="2"/>
+                <tileset firstgid="3" name="b" tilewidth="8" tileheight="8" tilecount="2"/>
+                <layer name="ground" width="2" height="2">
+                    <data {}>{}</data>
+                </layer>
+            </map>"#,
+            attributes, data
+        );
+        TiledMap::from_reader(tmx.as_bytes(), Path::new(""))
+    }
+
+    fn expected_tiles() -> Vec<TiledTile> {
+        vec![
+            TiledTile {
+                point: Point2::new(0, 1),
+                sprite_index: 0,
+                flip: TiledFlip::empty(),
+            },
+            TiledTile {
+                point: Point2::new(0, 0),
+                sprite_index: 1,
+                flip: TiledFlip::HORIZONTAL,
+            },
+            TiledTile {
+                point: Point2::new(1, 0),
+                sprite_index: 2,
+                flip: TiledFlip::empty(),
+            },
+        ]
+    }
+
+    #[test]
+    fn decode_csv() {
+        let map = map_with_data(r#"encoding="csv""#, "1,0,\n2147483650,3").unwrap();
+        assert_eq!(map.layers[0].tiles, expected_tiles());
+        assert_eq!(map.layers[0].kind, LayerKind::Dense);
+    }
+
+    #[test]
+    fn decode_xml() {
+        let map = map_with_data(
+            "",
+            r#"<tile gid="1"/><tile/><tile gid="2147483650"/><tile gid="3"/>"#,
+        )
+        .unwrap();
+        assert_eq!(map.layers[0].tiles, expected_tiles());
+    }
+
+    #[test]
+    fn decode_base64() {
+        let map = map_with_data(r#"encoding="base64""#, "AQAAAAAAAAACAACAAwAAAA==").unwrap();
+        assert_eq!(map.layers[0].tiles, expected_tiles());
+    }
+
+    #[test]
+    fn decode_base64_zlib() {
+        let map = map_with_data(
+            r#"encoding="base64" compression="zlib""#,
+            "eJxjZIAAJgaGBmYgDQACvACH",
+        )
+        .unwrap();
+        assert_eq!(map.layers[0].tiles, expected_tiles());
+    }
+
+    #[test]
+    fn unknown_gid() {
+        assert_eq!(
+            map_with_data(r#"encoding="csv""#, "1,0,0,5")
+                .unwrap_err()
+                .to_string(),
+            "the GID 5 is not in any tileset"
+        );
+    }
+
+    #[test]
+    fn unsupported_compression() {
+        assert_eq!(
+            map_with_data(r#"encoding="base64" compression="zstd""#, "")
+                .unwrap_err()
+                .to_string(),
+            "the `zstd` compression is not supported"
+        );
+    }
+
+    #[test]
+    fn flips_are_kept() {
+        let flips = TiledFlip::HORIZONTAL | TiledFlip::DIAGONAL | TiledFlip::ROTATED_HEX_120;
+        let data = format!(
+            "{},0,0,{}",
+            1 | flips.bits(),
+            2 | TiledFlip::VERTICAL.bits()
+        );
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = map_with_data(r#"encoding="csv""#, &data)
+            .unwrap()
+            .to_tilemap(texture_atlas_handle)
+            .unwrap();
+
+        assert_eq!(
+            tilemap.get_tile((0, 1), 0).unwrap().flip,
+            TileFlip::HORIZONTAL | TileFlip::DIAGONAL
+        );
+        assert_eq!(
+            tilemap.get_tile((1, 0), 0).unwrap().flip,
+            TileFlip::VERTICAL
+        );
+    }
+
+    #[test]
+    fn layer_tint_and_groups() {
+        let tmx = r##"<map orientation="orthogonal" width="2" height="2" tilewidth="8" tileheight="8">
+            <tileset firstgid="1" name="a" tilewidth="8" tileheight="8" tilecount="2"/>
+            <group opacity="0.5" visible="0">
+                <layer name="top" width="2" height="2" tintcolor="#ff0000">
+                    <data encoding="csv">0,0,0,1</data>
+                </layer>
+            </group>
+        </map>"##;
+        let map = TiledMap::from_reader(tmx.as_bytes(), Path::new("")).unwrap();
+        let layer = &map.layers[0];
+        assert_eq!(layer.kind, LayerKind::Sparse);
+        assert!(!layer.visible);
+        assert_eq!(layer.tint, Color::rgba(1.0, 0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn tileset_sizes_are_checked() {
+        let tileset = |attributes: &str, image: &str| {
+            let tmx = format!(
+                r#"<map orientation="orthogonal" width="2" height="2" tilewidth="8" tileheight="8">
+                    <tileset firstgid="1" name="a" {}>{}</tileset>
+                </map>"#,
+                attributes, image
+            );
+            TiledMap::from_reader(tmx.as_bytes(), Path::new(""))
+                .map(|map| map.tilesets[0].tile_count)
+                .map_err(|err| err.to_string())
+        };
+        let image = r#"<image source="a.png" width="32" height="16"/>"#;
+
+        assert_eq!(tileset(r#"tilewidth="8" tileheight="8""#, image), Ok(8));
+        assert_eq!(
+            tileset(r#"tilewidth="0" tileheight="8""#, image),
+            Err(
+                "the `tilewidth` attribute of the `tileset` element has an invalid value `0`"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            tileset(
+                r#"tilewidth="8" tileheight="8" spacing="4294967295""#,
+                image
+            ),
+            Err("the tile count of tileset `a` is too large".to_string())
+        );
+    }
+
+    #[test]
+    fn hexagonal_topology() {
+        let topology = |axis: &str, index: &str, height: u32| {
+            let tmx = format!(
+                r#"<map orientation="hexagonal" staggeraxis="{}" staggerindex="{}"
+                    width="2" height="{}" tilewidth="8" tileheight="8"/>"#,
+                axis, index, height
+            );
+            TiledMap::from_reader(tmx.as_bytes(), Path::new(""))
+                .unwrap()
+                .topology
+        };
+        assert_eq!(topology("x", "odd", 2), GridTopology::HexEvenCols);
+        assert_eq!(topology("x", "odd", 3), GridTopology::HexEvenCols);
+        assert_eq!(topology("x", "even", 2), GridTopology::HexOddCols);
+        assert_eq!(topology("y", "odd", 3), GridTopology::HexOddRows);
+        assert_eq!(topology("y", "odd", 2), GridTopology::HexEvenRows);
+        assert_eq!(topology("y", "even", 2), GridTopology::HexOddRows);
+    }
+}
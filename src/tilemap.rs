@@ -94,29 +94,60 @@
 //! ```
 
 use crate::{
+    animation::{AnimationState, TileAnimation},
+    auto_tile::{AutoTileset, NEIGHBOUR_OFFSETS},
     chunk::{Chunk, LayerKind},
-    entity::{ChunkComponents, DirtyLayer},
+    entity::{ChunkComponents, DirtyLayers},
+    fog::{FogState, FogTints},
+    generator::{ChunkGenerator, Generator, PendingChunks},
     lib::*,
     mesh::ChunkMesh,
     prelude::GridTopology,
     tile::{RawTile, Tile},
+    topology,
+};
+
+#[cfg(feature = "serialize")]
+use crate::{
+    codec::{self, CodecResult, Decoder, Encoder},
+    save::{self, SaveEnvelope, SAVE_VERSION},
+    store::{ChunkStore, Store},
 };
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 /// The kinds of errors that can occur.
-enum ErrorKind {
+pub(crate) enum ErrorKind {
     /// If the coordinate or index is out of bounds.
     DimensionError(DimensionError),
     /// If a layer already exists this error is returned.
     LayerExists(usize),
     /// If a layer does not already exist this error is returned.
     LayerDoesNotExist(usize),
+    /// If a layer is not a fog layer this error is returned.
+    LayerIsNotFog(usize),
+    /// If a layer is a fog layer, which does not hold sprites, this error is
+    /// returned.
+    LayerIsFog(usize),
     /// Texture atlas was not set
     MissingTextureAtlas,
     /// The chunk does not exist.
     MissingChunk,
     /// The chunk already exists.
     ChunkAlreadyExists(Point2),
+    /// The animation does not exist.
+    AnimationDoesNotExist(usize),
+    /// The chunk store failed to read, write or (de)serialize a chunk.
+    #[cfg(feature = "serialize")]
+    ChunkStore(String),
+    /// The save is of a version that is not supported.
+    #[cfg(feature = "serialize")]
+    UnsupportedSaveVersion(u32),
+    /// The save could not be (de)serialized or migrated.
+    #[cfg(feature = "serialize")]
+    InvalidSave(String),
+    /// The tile data could not be (de)serialized.
+    #[cfg(feature = "serialize")]
+    TileData(String),
 }
 
 impl Display for ErrorKind {
@@ -130,6 +161,16 @@ impl Display for ErrorKind {
                 n
             ),
             LayerDoesNotExist(n) => write!(f, "layer {} does not exist, try `add_layer` first", n),
+            LayerIsNotFog(n) => write!(
+                f,
+                "layer {} is not a fog layer, add it with `LayerKind::Fog` first",
+                n
+            ),
+            LayerIsFog(n) => write!(
+                f,
+                "layer {} is a fog layer, try `set_fog_states` instead",
+                n
+            ),
             MissingTextureAtlas => write!(
                 f,
                 "texture atlas is missing, must use `TilemapBuilder::texture_atlas`"
@@ -140,6 +181,23 @@ impl Display for ErrorKind {
                 "the chunk {} already exists, if this was intentional run `remove_chunk` first",
                 p
             ),
+            AnimationDoesNotExist(n) => write!(
+                f,
+                "animation {} does not exist, try `add_animation` first",
+                n
+            ),
+            #[cfg(feature = "serialize")]
+            ChunkStore(err) => write!(f, "the chunk store failed: {}", err),
+            #[cfg(feature = "serialize")]
+            UnsupportedSaveVersion(version) => write!(
+                f,
+                "save version {} is not supported, supported versions are 1 to {}",
+                version, SAVE_VERSION
+            ),
+            #[cfg(feature = "serialize")]
+            InvalidSave(err) => write!(f, "the save is invalid: {}", err),
+            #[cfg(feature = "serialize")]
+            TileData(err) => write!(f, "the tile data is invalid: {}", err),
         }
     }
 }
@@ -168,9 +226,89 @@ impl From<DimensionError> for TilemapError {
     }
 }
 
+#[cfg(feature = "serialize")]
+impl From<bincode::Error> for TilemapError {
+    fn from(err: bincode::Error) -> TilemapError {
+        TilemapError(Box::new(ErrorKind::InvalidSave(err.to_string())))
+    }
+}
+
 /// A map result.
 pub type TilemapResult<T> = Result<T, TilemapError>;
 
+/// A guard for a mutable raw tile returned by [`Tilemap::get_tile_mut`].
+///
+/// If the tile was mutably accessed, the chunk layer it is in is queued to be
+/// re-rendered when the guard is dropped, exactly as it would be with
+/// [`Tilemap::insert_tiles`].
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::prelude::*;
+/// use bevy::asset::HandleId;
+/// use bevy::prelude::*;
+///
+/// // In production use a strong handle from an actual source.
+/// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+///
+/// let mut tilemap = Tilemap::new(texture_atlas_handle);
+///
+/// tilemap.insert_chunk((0, 0)).unwrap();
+/// tilemap.insert_tile(Tile::new((1, 1), 0)).unwrap();
+///
+/// let mut tile = tilemap.get_tile_mut((1, 1), 0).unwrap();
+/// tile.index = 1;
+/// tile.color = Color::RED;
+/// ```
+///
+/// [`Tilemap::get_tile_mut`]: Tilemap::get_tile_mut
+/// [`Tilemap::insert_tiles`]: Tilemap::insert_tiles
+#[derive(Debug)]
+pub struct TileMut<'a> {
+    /// The raw tile that is being accessed.
+    tile: &'a mut RawTile,
+    /// The z order of the tile.
+    z_order: usize,
+    /// The entity of the chunk layer, if it is spawned.
+    entity: Option<Entity>,
+    /// The events of the tilemap to send the modification to.
+    events: &'a mut Events<ChunkEvent>,
+    /// The modified flag of the chunk, set once the tile is dropped if it was
+    /// mutably accessed.
+    chunk_modified: &'a mut bool,
+    /// If the tile was mutably accessed.
+    modified: bool,
+}
+
+impl<'a> Deref for TileMut<'a> {
+    type Target = RawTile;
+
+    fn deref(&self) -> &RawTile {
+        self.tile
+    }
+}
+
+impl<'a> DerefMut for TileMut<'a> {
+    fn deref_mut(&mut self) -> &mut RawTile {
+        self.modified = true;
+        self.tile
+    }
+}
+
+impl<'a> Drop for TileMut<'a> {
+    fn drop(&mut self) {
+        if !self.modified {
+            return;
+        }
+        *self.chunk_modified = true;
+        if let Some(entity) = self.entity {
+            let mut layers = HashMap::default();
+            layers.insert(self.z_order, entity);
+            self.events.send(ChunkEvent::Modified { layers });
+        }
+    }
+}
+
 #[derive(Debug)]
 /// Events that can happen to chunks.
 enum ChunkEvent {
@@ -197,6 +335,7 @@ bitflags! {
         const NONE = 0b0;
         const AUTO_CONFIGURE = 0b0000_0000_0000_0001;
         const AUTO_CHUNK = 0b0000_0000_0000_0010;
+        const DIAGONAL_NEIGHBOURS = 0b0000_0000_0000_0100;
     }
 }
 
@@ -215,6 +354,29 @@ impl Default for AutoFlags {
     }
 }
 
+/// The settings of a tilemap, which are written before its chunks in the
+/// compact binary format.
+#[cfg(feature = "serialize")]
+#[derive(Serialize, Deserialize)]
+struct TilemapSettings {
+    /// The type of grid to use.
+    topology: GridTopology,
+    /// The dimensions of the tilemap in chunks, if any.
+    dimensions: Option<Dimension2>,
+    /// A chunks dimensions in tiles.
+    chunk_dimensions: Dimension2,
+    /// A tiles dimensions in pixels.
+    tile_dimensions: Dimension2,
+    /// The layers of the tilemap.
+    layers: Vec<Option<LayerKind>>,
+    /// Auto flags used for different automated features.
+    auto_flags: AutoFlags,
+    /// The auto tilesets of each terrain.
+    auto_tilesets: HashMap<u32, AutoTileset>,
+    /// The animations of the tilemap, indexed by their ID.
+    animations: Vec<AnimationState>,
+}
+
 /// A Tilemap which maintains chunks and its tiles within.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
@@ -232,12 +394,29 @@ pub struct Tilemap {
     layers: Vec<Option<LayerKind>>,
     /// Auto flags used for different automated features.
     auto_flags: AutoFlags,
+    /// The auto tilesets of each terrain.
+    auto_tilesets: HashMap<u32, AutoTileset>,
+    /// The animations of the tilemap, indexed by their ID.
+    animations: Vec<AnimationState>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The generator which fills new chunks, if any.
+    generator: Option<Generator>,
+    #[cfg(feature = "serialize")]
+    #[serde(skip)]
+    /// The store which chunks are written to and read from, if any.
+    store: Option<Store>,
     #[cfg_attr(feature = "serde", serde(skip))]
     /// The handle of the texture atlas.
     texture_atlas: Handle<TextureAtlas>,
     /// A map of all the chunks at points.
     chunks: HashMap<Point2, Chunk>,
     #[cfg_attr(feature = "serde", serde(skip))]
+    /// The points of all the chunks that are spawned.
+    spawned: HashSet<Point2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The chunks which are being generated in the background.
+    pending: PendingChunks,
+    #[cfg_attr(feature = "serde", serde(skip))]
     /// A map of all currently spawned entities.
     entities: HashMap<usize, Vec<Entity>>,
     #[cfg_attr(feature = "serde", serde(skip))]
@@ -262,6 +441,8 @@ pub struct Tilemap {
 /// can exist on. Default is 20.
 /// - [`texture_atlas`]: specifies the texture atlas handle
 /// to use for the tilemap.
+/// - [`auto_tile`]: specifies an auto tileset for a terrain.
+/// - [`generator`]: specifies a generator which fills new chunks.
 ///
 /// The [`finish`] method will take ownership and consume the builder returning
 /// a [`TilemapResult`] with either an [`TilemapError`] or the [tilemap].
@@ -293,8 +474,10 @@ pub struct Tilemap {
 /// ```
 ///
 /// [`finish`]: TilemapBuilder::finish
+/// [`auto_tile`]: TilemapBuilder::auto_tile
 /// [`chunk_dimensions`]: TilemapBuilder::chunk_dimensions
 /// [`dimensions`]: TilemapBuilder::dimensions
+/// [`generator`]: TilemapBuilder::generator
 /// [`texture_atlas`]: TilemapBuilder::texture_atlas
 /// [`tile_dimensions`]: TilemapBuilder::tile_dimensions
 /// [`z_layers`]: TilemapBuilder::z_layers
@@ -319,6 +502,13 @@ pub struct TilemapBuilder {
     texture_atlas: Option<Handle<TextureAtlas>>,
     /// True if this tilemap will automatically configure.
     auto_flags: AutoFlags,
+    /// The auto tilesets to be set for each terrain.
+    auto_tilesets: HashMap<u32, AutoTileset>,
+    /// The generator which fills new chunks, if any.
+    generator: Option<Generator>,
+    #[cfg(feature = "serialize")]
+    /// The store which chunks are written to and read from, if any.
+    store: Option<Store>,
 }
 
 impl Default for TilemapBuilder {
@@ -332,7 +522,10 @@ impl Default for TilemapBuilder {
             layers: None,
             texture_atlas: None,
             auto_flags: AutoFlags::NONE,
-            // auto_tile: None,
+            auto_tilesets: HashMap::default(),
+            generator: None,
+            #[cfg(feature = "serialize")]
+            store: None,
         }
     }
 }
@@ -530,6 +723,105 @@ impl TilemapBuilder {
         self
     }
 
+    /// Sets if diagonal tiles are neighbours on a square grid.
+    ///
+    /// This changes what [`Tilemap::neighbours`] returns on a
+    /// [`GridTopology::Square`] grid. Hex grids always have 6 neighbours.
+    ///
+    /// By default this is not enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().diagonal_neighbours();
+    /// ```
+    ///
+    /// [`Tilemap::neighbours`]: Tilemap::neighbours
+    /// [`GridTopology::Square`]: crate::render::GridTopology::Square
+    pub fn diagonal_neighbours(mut self) -> Self {
+        self.auto_flags.toggle(AutoFlags::DIAGONAL_NEIGHBOURS);
+        self
+    }
+
+    /// Sets an auto tileset for a terrain.
+    ///
+    /// Tiles that are inserted with this terrain will have their sprite picked
+    /// from the tileset depending on their neighbours. If a tileset is set
+    /// twice for the same terrain, the later will be used.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let terrain = 1;
+    /// let builder = TilemapBuilder::new().auto_tile(terrain, AutoTileset::bitmask4([0; 16]));
+    /// ```
+    pub fn auto_tile(mut self, terrain: u32, tileset: AutoTileset) -> TilemapBuilder {
+        self.auto_tilesets.insert(terrain, tileset);
+        self
+    }
+
+    /// Sets a generator which fills every new chunk with tiles.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::generator::ChunkGenerator;
+    /// use bevy_tilemap_types::{dimension::Dimension2, point::Point2};
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// struct Empty;
+    ///
+    /// impl ChunkGenerator for Empty {
+    ///     fn generate(&self, _: Point2, _: Dimension2, _: &[Option<LayerKind>]) -> Vec<Tile> {
+    ///         Vec::new()
+    ///     }
+    /// }
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .generator(Empty)
+    ///     .finish()
+    ///     .unwrap();
+    /// ```
+    pub fn generator<G: ChunkGenerator>(mut self, generator: G) -> TilemapBuilder {
+        self.generator = Some(Generator(Arc::new(generator)));
+        self
+    }
+
+    /// Sets a store which removed chunks are written to and inserted chunks
+    /// are read from.
+    ///
+    /// Requires the **"serialize"** feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::store::FileChunkStore;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .store(FileChunkStore::new("saves/world"))
+    ///     .finish()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "serialize")]
+    pub fn store<S: ChunkStore>(mut self, store: S) -> TilemapBuilder {
+        self.store = Some(Store(Arc::new(store)));
+        self
+    }
+
     /// Consumes the builder and returns a result.
     ///
     /// If successful a [`TilemapResult`] is return with [tilemap] on
@@ -581,8 +873,15 @@ impl TilemapBuilder {
             tile_dimensions: self.tile_dimensions,
             layers: vec![None; z_layers],
             auto_flags: self.auto_flags,
+            auto_tilesets: self.auto_tilesets,
+            animations: Vec::new(),
+            generator: self.generator,
+            #[cfg(feature = "serialize")]
+            store: self.store,
             texture_atlas,
             chunks: Default::default(),
+            spawned: Default::default(),
+            pending: Default::default(),
             entities: Default::default(),
             events: Default::default(),
         };
@@ -610,8 +909,15 @@ impl Default for Tilemap {
             tile_dimensions: DEFAULT_CHUNK_DIMENSIONS,
             layers: vec![None; DEFAULT_Z_LAYERS],
             auto_flags: AutoFlags::NONE,
+            auto_tilesets: HashMap::default(),
+            animations: Vec::new(),
+            generator: None,
+            #[cfg(feature = "serialize")]
+            store: None,
             texture_atlas: Handle::default(),
             chunks: Default::default(),
+            spawned: Default::default(),
+            pending: Default::default(),
             entities: Default::default(),
             events: Default::default(),
         }
@@ -715,62 +1021,47 @@ impl Tilemap {
         &self.texture_atlas
     }
 
-    /// Constructs a new chunk and stores it at a coordinate position.
+    /// Sets a generator which fills every new chunk with tiles.
     ///
-    /// It requires that you give it either a point. It then automatically sets
-    /// both a sized mesh and chunk for use based on the parameters set in the
-    /// parent tilemap.
+    /// Chunks that already exist are not changed.
     ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::generator::ChunkGenerator;
+    /// use bevy_tilemap_types::{dimension::Dimension2, point::Point2};
     /// use bevy::asset::HandleId;
     /// use bevy::prelude::*;
     ///
+    /// struct Empty;
+    ///
+    /// impl ChunkGenerator for Empty {
+    ///     fn generate(&self, _: Point2, _: Dimension2, _: &[Option<LayerKind>]) -> Vec<Tile> {
+    ///         Vec::new()
+    ///     }
+    /// }
+    ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(3, 3)
-    ///     .finish()
-    ///     .unwrap();
-    ///
-    /// // Add some chunks.
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
-    /// assert!(tilemap.insert_chunk((-2, -2)).is_err());
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
     ///
-    /// assert!(tilemap.contains_chunk((0, 0)));
-    /// assert!(tilemap.contains_chunk((1, 1)));
-    /// assert!(!tilemap.contains_chunk((-2, -2)));
+    /// tilemap.set_generator(Empty);
     /// ```
-    /// # Errors
-    ///
-    /// If the point does not exist in the tilemap, an error is returned. This
-    /// can only be returned if you had set the dimensions on the tilemap.
-    ///
-    /// Also will return an error if the chunk already exists. If this happens
-    /// and was intentional, it is best to remove the chunk first. This is
-    /// simply a fail safe without actually returning the chunk as it is meant
-    /// to be kept internal.
-    pub fn insert_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point: Point2 = point.into();
-        if let Some(dimensions) = &self.dimensions {
-            dimensions.check_point(point)?;
-        }
-        let chunk = Chunk::new(point, &self.layers, self.chunk_dimensions);
-        match self.chunks.insert(point, chunk) {
-            Some(_) => Err(ErrorKind::ChunkAlreadyExists(point).into()),
-            None => Ok(()),
-        }
+    pub fn set_generator<G: ChunkGenerator>(&mut self, generator: G) {
+        self.generator = Some(Generator(Arc::new(generator)));
     }
 
-    /// Returns `true` if the chunk is included in the tilemap.
+    /// Sets a store which removed chunks are written to and inserted chunks
+    /// are read from.
+    ///
+    /// Chunks that already exist are not written until they are removed.
+    /// Requires the **"serialize"** feature.
     ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::store::FileChunkStore;
     /// use bevy::asset::HandleId;
     /// use bevy::prelude::*;
     ///
@@ -779,77 +1070,64 @@ impl Tilemap {
     ///
     /// let mut tilemap = Tilemap::new(texture_atlas_handle);
     ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.contains_chunk((0, 0)));
-    /// assert!(!tilemap.contains_chunk((1, 1)));
+    /// tilemap.set_store(FileChunkStore::new("saves/world"));
     /// ```
-    pub fn contains_chunk<P: Into<Point2>>(&mut self, point: P) -> bool {
-        let point: Point2 = point.into();
-        self.chunks.contains_key(&point)
+    #[cfg(feature = "serialize")]
+    pub fn set_store<S: ChunkStore>(&mut self, store: S) {
+        self.store = Some(Store(Arc::new(store)));
     }
 
-    /// Adds a layer to the tilemap with a specified layer kind.
+    /// Writes every chunk which had been modified since it was created or
+    /// loaded to the store, if there is one.
     ///
-    /// This method takes in a [`LayerKind`] as well as a specified Z layer
-    /// that the layer needs to be set to. If the layer is already the specified
-    /// layer's kind, then nothing happens.
+    /// This is useful to save the whole tilemap, such as when exiting. Chunks
+    /// are otherwise only written when they are removed. Requires the
+    /// **"serialize"** feature.
     ///
     /// # Errors
     ///
-    /// If a layer is set and a different layer already exists at that Z layer
-    /// then an error is returned regarding that. This is done to prevent
-    /// accidental overwrites of a layer.
+    /// Returns an error if a chunk could not be serialized or written.
     ///
     /// # Examples
-    /// ```
-    /// # use bevy_tilemap::prelude::*;
-    /// # use bevy::asset::HandleId;
-    /// # use bevy::prelude::*;
-    /// #
-    /// # // In production use a strong handle from an actual source.
-    /// # let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
-    /// #
-    /// # let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// ```no_run
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::store::FileChunkStore;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
     ///
-    /// let kind = LayerKind::Sparse;
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// assert!(tilemap.add_layer_with_kind(kind, 1).is_ok());
-    /// assert!(tilemap.add_layer_with_kind(kind, 1).is_err());
-    /// ```
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .store(FileChunkStore::new("saves/world"))
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
     ///
-    /// [`LayerKind`]: crate::chunk::LayerKind
-    pub fn add_layer_with_kind(&mut self, kind: LayerKind, z_order: usize) -> TilemapResult<()> {
-        if let Some(some_kind) = self.layers.get_mut(z_order) {
-            if some_kind.is_some() {
-                return Err(ErrorKind::LayerExists(z_order).into());
-            }
-            *some_kind = Some(kind);
-        }
-
-        for chunk in self.chunks.values_mut() {
-            chunk.add_layer(&kind, z_order, self.chunk_dimensions);
+    /// tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+    ///
+    /// tilemap.save_chunks().unwrap();
+    /// ```
+    #[cfg(feature = "serialize")]
+    pub fn save_chunks(&mut self) -> TilemapResult<()> {
+        let points: Vec<Point2> = self.chunks.keys().copied().collect();
+        for point in points.into_iter() {
+            self.store_chunk(point)?;
         }
-
         Ok(())
     }
 
-    /// Adds a layer to the tilemap.
-    ///
-    /// This method creates a layer across all chunks at the specified Z layer.
-    /// For ease of use, it by default makes a layer with a dense
-    /// [`LayerKind`] which is ideal for layers full of sprites.
-    ///
-    /// If you want to use a layer that is more performant and less data heavy,
-    /// use [`add_layer_with_kind`] with [`LayerKind::Sparse`].
+    /// Encodes the tilemap with all of its chunks into the compact binary
+    /// format of the [`codec`] module.
     ///
-    /// If the layer is already the specified layer's kind, then nothing
-    /// happens.
+    /// As with serde, the texture atlas, generator, store and spawned state
+    /// are not included. Requires the **"serialize"** feature.
     ///
     /// # Errors
     ///
-    /// If a layer is set and a different layer already exists at that Z layer
-    /// then an error is returned regarding that. This is done to prevent
-    /// accidental overwrites of a layer.
+    /// Returns an error if the settings of the tilemap could not be
+    /// serialized.
     ///
     /// # Examples
     /// ```
@@ -860,25 +1138,53 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// let mut tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
     ///
-    /// assert!(tilemap.add_layer(1).is_ok());
-    /// assert!(tilemap.add_layer(1).is_err());
+    /// tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+    ///
+    /// let bytes = tilemap.to_bytes().unwrap();
     /// ```
     ///
-    /// [`add_layer_with_kind`]: Tilemap::add_layer_with_kind
-    /// [`LayerKind`]: crate::chunk::LayerKind;
-    /// [`LayerKind::Sparse`]: crate::chunk::LayerKind::Sparse;
-    pub fn add_layer(&mut self, z_layer: usize) -> TilemapResult<()> {
-        self.add_layer_with_kind(LayerKind::Dense, z_layer)
+    /// [`codec`]: crate::codec
+    #[cfg(feature = "serialize")]
+    pub fn to_bytes(&self) -> CodecResult<Vec<u8>> {
+        let settings = TilemapSettings {
+            topology: self.topology,
+            dimensions: self.dimensions,
+            chunk_dimensions: self.chunk_dimensions,
+            tile_dimensions: self.tile_dimensions,
+            layers: self.layers.clone(),
+            auto_flags: self.auto_flags,
+            auto_tilesets: self.auto_tilesets.clone(),
+            animations: self.animations.clone(),
+        };
+        let mut encoder = Encoder::new();
+        encoder.write_bytes(&bincode::serialize(&settings)?);
+
+        let mut points: Vec<&Point2> = self.chunks.keys().collect();
+        points.sort_unstable_by_key(|point| (point.x, point.y));
+        encoder.write_varint(points.len() as u64);
+        for point in points.into_iter() {
+            if let Some(chunk) = self.chunks.get(point) {
+                chunk.encode(&mut encoder);
+            }
+        }
+        Ok(encoder.finish())
     }
 
-    /// Moves a layer from one Z level to another.
+    /// Serializes the tilemap into a save which later versions of this crate
+    /// can load with [`from_save_bytes`].
+    ///
+    /// As with serde, the texture atlas, generator, store and spawned state
+    /// are not included. Requires the **"serialize"** feature.
     ///
     /// # Errors
     ///
-    /// If the destination exists, it will throw an error. Likewise, if the
-    /// origin does not exist, it also will throw an error.
+    /// Returns an error if the tilemap could not be serialized.
     ///
     /// # Examples
     /// ```
@@ -889,47 +1195,76 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .z_layers(3)
-    ///     .finish()
-    ///     .unwrap();
-    ///
-    /// tilemap.add_layer(0).unwrap();
-    /// tilemap.add_layer(3).unwrap();
+    /// let tilemap = Tilemap::new(texture_atlas_handle);
     ///
-    /// // If we moved this to layer 3, it would instead fail.
-    /// assert!(tilemap.move_layer(0, 2).is_ok());
-    /// assert!(tilemap.move_layer(3, 2).is_err());
+    /// let bytes = tilemap.to_save_bytes().unwrap();
     /// ```
-    pub fn move_layer(&mut self, from_z: usize, to_z: usize) -> TilemapResult<()> {
-        if let Some(layer) = self.layers.get(to_z) {
-            if layer.is_some() {
-                return Err(ErrorKind::LayerExists(to_z).into());
-            }
+    ///
+    /// [`from_save_bytes`]: Tilemap::from_save_bytes
+    #[cfg(feature = "serialize")]
+    pub fn to_save_bytes(&self) -> TilemapResult<Vec<u8>> {
+        let envelope = SaveEnvelope {
+            magic: save::MAGIC,
+            version: SAVE_VERSION,
+            data: bincode::serialize(self)
+                .map_err(|err| ErrorKind::InvalidSave(err.to_string()))?,
         };
-        if let Some(layer) = self.layers.get(from_z) {
-            if Some(layer).is_none() {
-                return Err(ErrorKind::LayerDoesNotExist(from_z).into());
-            }
-        }
+        Ok(bincode::serialize(&envelope).map_err(|err| ErrorKind::InvalidSave(err.to_string()))?)
+    }
 
-        self.layers.swap(from_z, to_z);
-        for chunk in self.chunks.values_mut() {
-            chunk.move_layer(from_z, to_z);
+    /// Deserializes a tilemap from a save, upgrading it first if it was made
+    /// with an older version of the save format.
+    ///
+    /// The texture atlas must be set with [`set_texture_atlas`] afterwards.
+    /// Requires the **"serialize"** feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the save is of a version that is not supported, or
+    /// if it could not be deserialized.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle.clone());
+    /// let bytes = tilemap.to_save_bytes().unwrap();
+    ///
+    /// let mut tilemap = Tilemap::from_save_bytes(&bytes).unwrap();
+    /// tilemap.set_texture_atlas(texture_atlas_handle);
+    /// ```
+    ///
+    /// [`set_texture_atlas`]: Tilemap::set_texture_atlas
+    #[cfg(feature = "serialize")]
+    pub fn from_save_bytes(bytes: &[u8]) -> TilemapResult<Tilemap> {
+        let envelope: SaveEnvelope =
+            bincode::deserialize(bytes).map_err(|err| ErrorKind::InvalidSave(err.to_string()))?;
+        if envelope.magic != save::MAGIC {
+            return Err(ErrorKind::InvalidSave("not a tilemap save".to_string()).into());
         }
-
-        Ok(())
+        if envelope.version == 0 || envelope.version > SAVE_VERSION {
+            return Err(ErrorKind::UnsupportedSaveVersion(envelope.version).into());
+        }
+        let data = save::migrate(envelope.version, envelope.data)
+            .map_err(|err| ErrorKind::InvalidSave(err.to_string()))?;
+        Ok(bincode::deserialize(&data).map_err(|err| ErrorKind::InvalidSave(err.to_string()))?)
     }
 
-    /// Removes a layer from the tilemap and inner chunks.
+    /// Decodes a tilemap from the compact binary format of the [`codec`]
+    /// module.
     ///
-    /// **Warning**: This is destructive if you have tiles that exist on that
-    /// layer. If you want to add them back in, better to use the [`move_layer`]
-    /// method instead.
+    /// The texture atlas must be set with [`set_texture_atlas`] afterwards.
+    /// Requires the **"serialize"** feature.
     ///
-    /// This method takes in a Z layer which is then flagged for deletion. If
-    /// the layer already does not exist, it does nothing.
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a tilemap in the compact format,
+    /// or if they were encoded with an unsupported version of it.
     ///
     /// # Examples
     /// ```
@@ -940,33 +1275,369 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
-    ///
-    /// tilemap.add_layer(1);
+    /// let tilemap = Tilemap::new(texture_atlas_handle.clone());
+    /// let bytes = tilemap.to_bytes().unwrap();
     ///
-    /// tilemap.remove_layer(1);
+    /// let mut tilemap = Tilemap::from_bytes(&bytes).unwrap();
+    /// tilemap.set_texture_atlas(texture_atlas_handle);
     /// ```
     ///
-    /// [`move_layer`]: Tilemap::move_layer
-    pub fn remove_layer(&mut self, z: usize) {
-        if let Some(layer) = self.layers.get_mut(z) {
-            *layer = None;
+    /// [`codec`]: crate::codec
+    /// [`set_texture_atlas`]: Tilemap::set_texture_atlas
+    #[cfg(feature = "serialize")]
+    pub fn from_bytes(bytes: &[u8]) -> CodecResult<Tilemap> {
+        let mut decoder = Decoder::new(bytes)?;
+        let settings: TilemapSettings = bincode::deserialize(decoder.read_bytes()?)?;
+        codec::chunk_area(settings.chunk_dimensions)?;
+
+        let mut chunks = HashMap::default();
+        let chunk_count = decoder.read_usize()?;
+        for _ in 0..chunk_count {
+            let chunk = Chunk::decode(&mut decoder, settings.chunk_dimensions)?;
+            chunks.insert(chunk.point(), chunk);
+        }
+        decoder.finish()?;
+
+        Ok(Tilemap {
+            topology: settings.topology,
+            dimensions: settings.dimensions,
+            chunk_dimensions: settings.chunk_dimensions,
+            tile_dimensions: settings.tile_dimensions,
+            layers: settings.layers,
+            auto_flags: settings.auto_flags,
+            auto_tilesets: settings.auto_tilesets,
+            animations: settings.animations,
+            generator: None,
+            store: None,
+            texture_atlas: Default::default(),
+            chunks,
+            spawned: Default::default(),
+            pending: Default::default(),
+            entities: Default::default(),
+            events: Default::default(),
+        })
+    }
+
+    /// Reads a chunk from the store and inserts it, returning `true` if the
+    /// store had the chunk.
+    #[cfg(feature = "serialize")]
+    fn load_chunk(&mut self, point: Point2) -> TilemapResult<bool> {
+        let store = match &self.store {
+            Some(store) => store.clone(),
+            None => return Ok(false),
+        };
+        let bytes = match store
+            .0
+            .read(point)
+            .map_err(|err| ErrorKind::ChunkStore(err.to_string()))?
+        {
+            Some(bytes) => bytes,
+            None => return Ok(false),
+        };
+        // Chunks that were written before the compact format are in bincode.
+        let chunk = if Decoder::is_compact(&bytes) {
+            Decoder::new(&bytes)
+                .and_then(|mut decoder| {
+                    let chunk = Chunk::decode(&mut decoder, self.chunk_dimensions)?;
+                    decoder.finish()?;
+                    Ok(chunk)
+                })
+                .map_err(|err| ErrorKind::ChunkStore(err.to_string()))?
         } else {
-            return;
+            bincode::deserialize(&bytes).map_err(|err| ErrorKind::ChunkStore(err.to_string()))?
+        };
+        self.chunks.insert(point, chunk);
+        Ok(true)
+    }
+
+    /// Without a store, chunks are never loaded.
+    #[cfg(not(feature = "serialize"))]
+    fn load_chunk(&mut self, _point: Point2) -> TilemapResult<bool> {
+        Ok(false)
+    }
+
+    /// Writes a chunk to the store if it had been modified since it was
+    /// created or loaded.
+    #[cfg(feature = "serialize")]
+    fn store_chunk(&mut self, point: Point2) -> TilemapResult<()> {
+        let store = match &self.store {
+            Some(store) => store.clone(),
+            None => return Ok(()),
+        };
+        let chunk = match self.chunks.get_mut(&point) {
+            Some(chunk) if chunk.is_modified() => chunk,
+            _ => return Ok(()),
+        };
+        let mut encoder = Encoder::new();
+        chunk.encode(&mut encoder);
+        store
+            .0
+            .write(point, &encoder.finish())
+            .map_err(|err| ErrorKind::ChunkStore(err.to_string()))?;
+        chunk.set_modified(false);
+        Ok(())
+    }
+
+    /// Without a store, chunks are never written.
+    #[cfg(not(feature = "serialize"))]
+    fn store_chunk(&mut self, _point: Point2) -> TilemapResult<()> {
+        Ok(())
+    }
+
+    /// Constructs a new chunk at a point and fills it with the tiles of the
+    /// generator, if there is one. If the chunk is in the store, it is read
+    /// from it instead.
+    fn create_chunk(&mut self, point: Point2) -> TilemapResult<()> {
+        if self.load_chunk(point)? {
+            return Ok(());
         }
 
-        for chunk in self.chunks.values_mut() {
-            chunk.remove_layer(z);
+        let chunk = Chunk::new(point, &self.layers, self.chunk_dimensions);
+        self.chunks.insert(point, chunk);
+
+        let generator = match &self.generator {
+            Some(generator) => generator.clone(),
+            None => return Ok(()),
+        };
+        let tiles = generator
+            .0
+            .generate(point, self.chunk_dimensions, &self.layers);
+        let tiles = self.generated_tiles_to_global(point, tiles);
+        self.insert_tiles(tiles)?;
+
+        // A generated chunk is the same every time, it only needs to be
+        // stored once it is edited.
+        if let Some(chunk) = self.chunks.get_mut(&point) {
+            chunk.set_modified(false);
         }
+        Ok(())
     }
 
-    /// Spawns a chunk at a given index or coordinate.
+    /// Takes the generated tiles of a chunk, local to the chunk, and returns
+    /// them with global points. Tiles outside of the chunk or with a layer or
+    /// animation that does not exist are discarded.
+    fn generated_tiles_to_global(&self, point: Point2, tiles: Vec<Tile>) -> Vec<Tile> {
+        let width = self.chunk_dimensions.width as i32;
+        let height = self.chunk_dimensions.height as i32;
+        tiles
+            .into_iter()
+            .filter(|tile| {
+                tile.point.x >= 0
+                    && tile.point.x < width
+                    && tile.point.y >= 0
+                    && tile.point.y < height
+                    && self.can_insert(tile)
+            })
+            .map(|tile| Tile {
+                point: Point2::new(
+                    tile.point.x + (width * point.x) - (width / 2),
+                    tile.point.y + (height * point.y) - (height / 2),
+                ),
+                ..tile
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the layer and animation of a tile exist and the layer
+    /// is not a fog layer, which is what must be valid to insert it into an
+    /// existing chunk.
+    fn can_insert(&self, tile: &Tile) -> bool {
+        match self.layers.get(tile.z_order) {
+            Some(Some(LayerKind::Fog(_))) | None => false,
+            Some(_) => tile
+                .animation
+                .map_or(true, |animation| animation < self.animations.len()),
+        }
+    }
+
+    /// Starts generating the requested chunks in the background and inserts
+    /// the chunks that had finished generating.
+    pub(crate) fn update_pending_chunks(&mut self, task_pool: &TaskPool) {
+        if let Some(generator) = &self.generator {
+            self.pending
+                .start(task_pool, generator, self.chunk_dimensions, &self.layers);
+        }
+
+        for generated in self.pending.take_finished().into_iter() {
+            let point = generated.point;
+            let chunk = Chunk::new(point, &self.layers, self.chunk_dimensions);
+            self.chunks.insert(point, chunk);
+
+            // Invalid tiles are dropped before they are inserted, as there is
+            // nobody to return an error to from here.
+            let tiles = self.generated_tiles_to_global(point, generated.tiles);
+            self.insert_tiles(tiles).ok();
+            if let Some(chunk) = self.chunks.get_mut(&point) {
+                chunk.set_modified(false);
+            }
+            let queued_tiles: Vec<Tile> = generated
+                .queued_tiles
+                .into_iter()
+                .filter(|tile| self.can_insert(tile))
+                .collect();
+            self.insert_tiles(queued_tiles).ok();
+
+            if self.spawned.contains(&point) {
+                self.events.send(ChunkEvent::Spawned { point });
+            }
+        }
+    }
+
+    /// Returns `true` if the tilemap has a generator.
+    pub(crate) fn has_generator(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    /// Returns the points of all the chunks that are currently spawned.
+    pub(crate) fn spawned_chunks(&self) -> &HashSet<Point2> {
+        &self.spawned
+    }
+
+    /// Returns a copy of the tilemap without any of its runtime state, such as
+    /// the spawned chunks, their entities and events.
+    #[cfg(feature = "loader")]
+    pub(crate) fn duplicate(&self) -> Tilemap {
+        Tilemap {
+            topology: self.topology,
+            dimensions: self.dimensions,
+            chunk_dimensions: self.chunk_dimensions,
+            tile_dimensions: self.tile_dimensions,
+            layers: self.layers.clone(),
+            auto_flags: self.auto_flags,
+            auto_tilesets: self.auto_tilesets.clone(),
+            animations: self.animations.clone(),
+            generator: self.generator.clone(),
+            store: self.store.clone(),
+            texture_atlas: self.texture_atlas.clone(),
+            chunks: self.chunks.clone(),
+            spawned: Default::default(),
+            pending: Default::default(),
+            entities: Default::default(),
+            events: Default::default(),
+        }
+    }
+
+    /// Applies the changes between an old and a new version of the asset of
+    /// the tilemap, keeping all of its runtime state.
     ///
-    /// Does nothing if the chunk does not exist.
+    /// Only the chunk layers which had changed between the versions are
+    /// replaced and re-rendered. If the topology, dimensions, layers or texture
+    /// atlas had changed, all the chunks are replaced and spawned again.
     ///
     /// # Errors
     ///
-    /// If the coordinate or index is out of bounds.
+    /// If the new version has chunks out of bounds, an error is returned and
+    /// the tilemap is left untouched.
+    #[cfg(feature = "loader")]
+    pub(crate) fn reload(&mut self, old: &Tilemap, new: &Tilemap) -> TilemapResult<()> {
+        let rebuild = old.topology != new.topology
+            || old.dimensions != new.dimensions
+            || old.chunk_dimensions != new.chunk_dimensions
+            || old.tile_dimensions != new.tile_dimensions
+            || old.layers != new.layers
+            || old.texture_atlas != new.texture_atlas;
+
+        // Everything that can fail is checked before anything is changed, so
+        // that the despawns and spawns below can not fail halfway.
+        new.check_chunks()?;
+        if !rebuild {
+            if let Some(dimensions) = &self.dimensions {
+                for point in old.chunks.keys() {
+                    dimensions.check_point(*point)?;
+                }
+                for point in new.chunks.keys() {
+                    dimensions.check_point(*point)?;
+                }
+            }
+        }
+
+        if old.auto_tilesets != new.auto_tilesets {
+            self.auto_tilesets = new.auto_tilesets.clone();
+        }
+        if old.animations != new.animations {
+            self.animations = new.animations.clone();
+        }
+
+        if rebuild {
+            let spawned: Vec<Point2> = self.spawned.iter().copied().collect();
+            for point in spawned.into_iter() {
+                self.despawn_chunk(point)?;
+            }
+            self.topology = new.topology;
+            self.dimensions = new.dimensions;
+            self.chunk_dimensions = new.chunk_dimensions;
+            self.tile_dimensions = new.tile_dimensions;
+            self.layers = new.layers.clone();
+            self.texture_atlas = new.texture_atlas.clone();
+            self.chunks = new.chunks.clone();
+            self.pending = Default::default();
+            return self.spawn_all_chunks();
+        }
+
+        for point in old.chunks.keys() {
+            if !new.chunks.contains_key(point) {
+                self.despawn_chunk(*point)?;
+                self.chunks.remove(point);
+                self.pending.cancel(point);
+            }
+        }
+        for (point, new_chunk) in new.chunks.iter() {
+            let chunk = match self.chunks.get_mut(point) {
+                Some(chunk) => chunk,
+                None => {
+                    self.chunks.insert(*point, new_chunk.clone());
+                    self.spawn_chunk(*point)?;
+                    continue;
+                }
+            };
+            let mut layers = HashMap::default();
+            for z_order in chunk.reload_layers(old.chunks.get(point), new_chunk) {
+                if let Some(entity) = chunk.get_entity(z_order) {
+                    layers.insert(z_order, entity);
+                }
+            }
+            if !layers.is_empty() {
+                self.events.send(ChunkEvent::Modified { layers });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every chunk of the tilemap is within the dimensions of the
+    /// tilemap, which is not guaranteed for a tilemap that was read from a
+    /// file.
+    #[cfg(feature = "loader")]
+    pub(crate) fn check_chunks(&self) -> TilemapResult<()> {
+        if let Some(dimensions) = &self.dimensions {
+            for point in self.chunks.keys() {
+                dimensions.check_point(*point)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Spawns every chunk of the tilemap that is not spawned yet.
+    #[cfg(feature = "loader")]
+    pub(crate) fn spawn_all_chunks(&mut self) -> TilemapResult<()> {
+        let points: Vec<Point2> = self.chunks.keys().copied().collect();
+        for point in points.into_iter() {
+            self.spawn_chunk(point)?;
+        }
+        Ok(())
+    }
+
+    /// Constructs a new chunk and stores it at a coordinate position.
+    ///
+    /// It requires that you give it either a point. It then automatically sets
+    /// both a sized mesh and chunk for use based on the parameters set in the
+    /// parent tilemap.
+    ///
+    /// If the tilemap has a [`ChunkGenerator`], the chunk is generated right
+    /// away on the calling thread. Only [`spawn_chunk`] generates chunks in
+    /// the background.
+    ///
+    /// [`ChunkGenerator`]: crate::generator::ChunkGenerator
+    /// [`spawn_chunk`]: Tilemap::spawn_chunk
     ///
     /// # Examples
     /// ```
@@ -979,36 +1650,42 @@ impl Tilemap {
     ///
     /// let mut tilemap = TilemapBuilder::new()
     ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
+    ///     .dimensions(3, 3)
     ///     .finish()
     ///     .unwrap();
     ///
-    /// tilemap.insert_chunk((0, 0));
-    ///
-    /// // Ideally you should want to set some tiles here else nothing will
-    /// // display in the render...
+    /// // Add some chunks.
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
+    /// assert!(tilemap.insert_chunk((-2, -2)).is_err());
     ///
-    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.spawn_chunk((1, 1)).is_err());
-    /// assert!(tilemap.spawn_chunk((-1, -1)).is_err());
+    /// assert!(tilemap.contains_chunk((0, 0)));
+    /// assert!(tilemap.contains_chunk((1, 1)));
+    /// assert!(!tilemap.contains_chunk((-2, -2)));
     /// ```
-    pub fn spawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+    /// # Errors
+    ///
+    /// If the point does not exist in the tilemap, an error is returned. This
+    /// can only be returned if you had set the dimensions on the tilemap.
+    ///
+    /// Also will return an error if the chunk already exists. If this happens
+    /// and was intentional, it is best to remove the chunk first. This is
+    /// simply a fail safe without actually returning the chunk as it is meant
+    /// to be kept internal.
+    pub fn insert_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
         let point: Point2 = point.into();
         if let Some(dimensions) = &self.dimensions {
             dimensions.check_point(point)?;
         }
-
-        self.events.send(ChunkEvent::Spawned { point });
-
-        Ok(())
+        if self.contains_chunk(point) {
+            return Err(ErrorKind::ChunkAlreadyExists(point).into());
+        }
+        self.create_chunk(point)
     }
 
-    /// Spawns a chunk at a given tile point.
-    ///
-    /// # Errors
+    /// Returns `true` if the chunk is included in the tilemap.
     ///
-    /// If the coordinate or index is out of bounds or if the chunk does not
-    /// exist, an error will be returned.
+    /// A chunk which is still being generated in the background is included.
     ///
     /// # Examples
     /// ```
@@ -1019,35 +1696,1194 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .chunk_dimensions(32, 32)
-    ///     .dimensions(1, 1)
-    ///     .finish()
-    ///     .unwrap();
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
     ///
-    /// let point = (15, 15);
-    /// let index = 0;
-    /// let tile = Tile::new(point, index);
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.contains_chunk((0, 0)));
+    /// assert!(!tilemap.contains_chunk((1, 1)));
+    /// ```
+    pub fn contains_chunk<P: Into<Point2>>(&mut self, point: P) -> bool {
+        let point: Point2 = point.into();
+        self.chunks.contains_key(&point) || self.pending.contains(&point)
+    }
+
+    /// Adds a layer to the tilemap with a specified layer kind.
+    ///
+    /// This method takes in a [`LayerKind`] as well as a specified Z layer
+    /// that the layer needs to be set to. If the layer is already the specified
+    /// layer's kind, then nothing happens.
+    ///
+    /// # Errors
+    ///
+    /// If a layer is set and a different layer already exists at that Z layer
+    /// then an error is returned regarding that. This is done to prevent
+    /// accidental overwrites of a layer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bevy_tilemap::prelude::*;
+    /// # use bevy::asset::HandleId;
+    /// # use bevy::prelude::*;
+    /// #
+    /// # // In production use a strong handle from an actual source.
+    /// # let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// #
+    /// # let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// let kind = LayerKind::Sparse;
+    ///
+    /// assert!(tilemap.add_layer_with_kind(kind, 1).is_ok());
+    /// assert!(tilemap.add_layer_with_kind(kind, 1).is_err());
+    /// ```
+    ///
+    /// [`LayerKind`]: crate::chunk::LayerKind
+    pub fn add_layer_with_kind(&mut self, kind: LayerKind, z_order: usize) -> TilemapResult<()> {
+        if let Some(some_kind) = self.layers.get_mut(z_order) {
+            if some_kind.is_some() {
+                return Err(ErrorKind::LayerExists(z_order).into());
+            }
+            *some_kind = Some(kind);
+        }
+
+        for chunk in self.chunks.values_mut() {
+            chunk.add_layer(&kind, z_order, self.chunk_dimensions);
+        }
+
+        Ok(())
+    }
+
+    /// Adds a layer to the tilemap.
+    ///
+    /// This method creates a layer across all chunks at the specified Z layer.
+    /// For ease of use, it by default makes a layer with a dense
+    /// [`LayerKind`] which is ideal for layers full of sprites.
+    ///
+    /// If you want to use a layer that is more performant and less data heavy,
+    /// use [`add_layer_with_kind`] with [`LayerKind::Sparse`].
+    ///
+    /// If the layer is already the specified layer's kind, then nothing
+    /// happens.
+    ///
+    /// # Errors
+    ///
+    /// If a layer is set and a different layer already exists at that Z layer
+    /// then an error is returned regarding that. This is done to prevent
+    /// accidental overwrites of a layer.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// assert!(tilemap.add_layer(1).is_ok());
+    /// assert!(tilemap.add_layer(1).is_err());
+    /// ```
+    ///
+    /// [`add_layer_with_kind`]: Tilemap::add_layer_with_kind
+    /// [`LayerKind`]: crate::chunk::LayerKind;
+    /// [`LayerKind::Sparse`]: crate::chunk::LayerKind::Sparse;
+    pub fn add_layer(&mut self, z_layer: usize) -> TilemapResult<()> {
+        self.add_layer_with_kind(LayerKind::Dense, z_layer)
+    }
+
+    /// Moves a layer from one Z level to another.
+    ///
+    /// # Errors
+    ///
+    /// If the destination exists, it will throw an error. Likewise, if the
+    /// origin does not exist, it also will throw an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .z_layers(3)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.add_layer(0).unwrap();
+    /// tilemap.add_layer(3).unwrap();
+    ///
+    /// // If we moved this to layer 3, it would instead fail.
+    /// assert!(tilemap.move_layer(0, 2).is_ok());
+    /// assert!(tilemap.move_layer(3, 2).is_err());
+    /// ```
+    pub fn move_layer(&mut self, from_z: usize, to_z: usize) -> TilemapResult<()> {
+        if let Some(layer) = self.layers.get(to_z) {
+            if layer.is_some() {
+                return Err(ErrorKind::LayerExists(to_z).into());
+            }
+        };
+        if let Some(layer) = self.layers.get(from_z) {
+            if Some(layer).is_none() {
+                return Err(ErrorKind::LayerDoesNotExist(from_z).into());
+            }
+        }
+
+        self.layers.swap(from_z, to_z);
+        for chunk in self.chunks.values_mut() {
+            chunk.move_layer(from_z, to_z);
+        }
+
+        Ok(())
+    }
+
+    /// Removes a layer from the tilemap and inner chunks.
+    ///
+    /// **Warning**: This is destructive if you have tiles that exist on that
+    /// layer. If you want to add them back in, better to use the [`move_layer`]
+    /// method instead.
+    ///
+    /// This method takes in a Z layer which is then flagged for deletion. If
+    /// the layer already does not exist, it does nothing.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// tilemap.add_layer(1);
+    ///
+    /// tilemap.remove_layer(1);
+    /// ```
+    ///
+    /// [`move_layer`]: Tilemap::move_layer
+    pub fn remove_layer(&mut self, z: usize) {
+        if let Some(layer) = self.layers.get_mut(z) {
+            *layer = None;
+        } else {
+            return;
+        }
+
+        for chunk in self.chunks.values_mut() {
+            chunk.remove_layer(z);
+        }
+    }
+
+    /// Spawns a chunk at a given index or coordinate.
+    ///
+    /// Does nothing if the chunk does not exist or is already spawned. If the
+    /// tilemap has a [`ChunkGenerator`], a chunk that does not exist is
+    /// generated in the background instead and spawned once it is ready. This
+    /// is the only way a chunk is generated in the background, chunks that are
+    /// inserted with [`insert_chunk`] or automatically by [`insert_tiles`] are
+    /// generated right away.
+    ///
+    /// [`insert_chunk`]: Tilemap::insert_chunk
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// [`ChunkGenerator`]: crate::generator::ChunkGenerator
+    ///
+    /// # Errors
+    ///
+    /// If the coordinate or index is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0));
+    ///
+    /// // Ideally you should want to set some tiles here else nothing will
+    /// // display in the render...
+    ///
+    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.spawn_chunk((1, 1)).is_err());
+    /// assert!(tilemap.spawn_chunk((-1, -1)).is_err());
+    /// ```
+    pub fn spawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point: Point2 = point.into();
+        if let Some(dimensions) = &self.dimensions {
+            dimensions.check_point(point)?;
+        }
+
+        // A chunk that is already spawned must not be spawned twice.
+        if self.spawned.contains(&point) {
+            return Ok(());
+        }
+        if !self.chunks.contains_key(&point) && !self.load_chunk(point)? && self.generator.is_some()
+        {
+            // The chunk is spawned once it is generated.
+            self.spawned.insert(point);
+            self.pending.request(point);
+            return Ok(());
+        }
+        self.spawned.insert(point);
+
+        self.events.send(ChunkEvent::Spawned { point });
+
+        Ok(())
+    }
+
+    /// Spawns a chunk at a given tile point.
+    ///
+    /// # Errors
+    ///
+    /// If the coordinate or index is out of bounds or if the chunk does not
+    /// exist, an error will be returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .chunk_dimensions(32, 32)
+    ///     .dimensions(1, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let point = (15, 15);
+    /// let index = 0;
+    /// let tile = Tile::new(point, index);
+    ///
+    /// tilemap.insert_tile(tile);
+    ///
+    /// assert!(tilemap.spawn_chunk_containing_point(point).is_ok());
+    /// assert!(tilemap.spawn_chunk_containing_point((16, 16)).is_err());
+    /// assert!(tilemap.spawn_chunk_containing_point((-18, -18)).is_err());
+    /// ```
+    pub fn spawn_chunk_containing_point<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point = self.point_to_chunk_point(point);
+        self.spawn_chunk(point)
+    }
+
+    /// De-spawns a spawned chunk at a given index or coordinate.
+    ///
+    /// If the chunk is not spawned this will result in nothing. Once
+    /// de-spawned, the chunk can be spawned again with [`spawn_chunk`].
+    ///
+    /// [`spawn_chunk`]: Tilemap::spawn_chunk
+    ///
+    /// # Errors
+    ///
+    /// If the coordinate or index is out of bounds, an error will be returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    ///
+    /// // Ideally you should want to set some tiles here else nothing will
+    /// // display in the render...
+    ///
+    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    ///
+    /// // Later a frame or more on...
+    ///
+    /// assert!(tilemap.despawn_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.despawn_chunk((-1, -1)).is_err());
+    /// ```
+    pub fn despawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point: Point2 = point.into();
+        if let Some(dimensions) = &self.dimensions {
+            dimensions.check_point(point)?;
+        }
+
+        self.spawned.remove(&point);
+        if let Some(chunk) = self.chunks.get_mut(&point) {
+            let entities = chunk.remove_entities();
+            self.events.send(ChunkEvent::Despawned { entities })
+        }
+
+        Ok(())
+    }
+
+    /// Destructively removes a chunk at a coordinate position and despawns them
+    /// if needed.
+    ///
+    /// Internally, this sends an event to the tilemap's system flagging which
+    /// chunks must be removed by index and entity. A chunk is not recoverable
+    /// if this action is done.
+    ///
+    /// Does nothing if the chunk does not exist.
+    ///
+    /// # Errors
+    ///
+    /// If the coordinate or index is out of bounds, an error will be returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(3, 3)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // Add some chunks.
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
+    ///
+    /// assert!(tilemap.remove_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.remove_chunk((1, 1)).is_ok());
+    /// assert!(tilemap.remove_chunk((-2, -2)).is_err());
+    /// ```
+    pub fn remove_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point = point.into();
+        self.despawn_chunk(point)?;
+        self.store_chunk(point)?;
+
+        self.chunks.remove(&point);
+        self.pending.cancel(&point);
+
+        Ok(())
+    }
+
+    /// Takes a tile point and changes it into a chunk point.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// let tile_point = (15, 15);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((0, 0), chunk_point);
+    ///
+    /// let tile_point = (16, 16);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((1, 1), chunk_point);
+    ///
+    /// let tile_point = (-16, -16);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((-0, -0), chunk_point);
+    ///
+    /// let tile_point = (-17, -17);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((-1, -1), chunk_point);
+    /// ```
+    pub fn point_to_chunk_point<P: Into<Point2>>(&self, point: P) -> (i32, i32) {
+        let point: Point2 = point.into();
+        let width = self.chunk_dimensions.width as f32;
+        let height = self.chunk_dimensions.height as f32;
+        let x = ((point.x as f32 + width / 2.0) / width).floor() as i32;
+        let y = ((point.y as f32 + height / 2.0) / height).floor() as i32;
+        (x, y)
+    }
+
+    /// Returns the world position of the center of a tile, as it is rendered
+    /// for the topology of the tilemap.
+    ///
+    /// The transform is the `Transform` of the tilemap entity.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .tile_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let transform = Transform::from_translation(Vec3::new(100.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(tilemap.point_to_world(&transform, (0, 0)), Vec2::new(116.0, 16.0));
+    /// assert_eq!(tilemap.point_to_world(&transform, (-1, 2)), Vec2::new(84.0, 80.0));
+    /// ```
+    pub fn point_to_world<P: Into<Point2>>(&self, transform: &Transform, point: P) -> Vec2 {
+        let (x, y) = self.tile_center(point.into());
+        let position = transform.mul_vec3(Vec3::new(x, y, 0.0));
+        Vec2::new(position.x(), position.y())
+    }
+
+    /// Returns the points of the tiles that are adjacent to a point, as they
+    /// are rendered for the topology of the tilemap.
+    ///
+    /// A square grid has 4 neighbours, or 8 if diagonal neighbours are set.
+    /// A hex grid has 6 neighbours, which depend on whether the row or column
+    /// of the point is even or odd for the even and odd hex topologies.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// assert_eq!(tilemap.neighbours((0, 0)).len(), 4);
+    ///
+    /// tilemap.set_diagonal_neighbours(true);
+    /// assert_eq!(tilemap.neighbours((0, 0)).len(), 8);
+    /// ```
+    pub fn neighbours<P: Into<Point2>>(&self, point: P) -> Vec<Point2> {
+        topology::neighbours(self.topology, point.into(), self.diagonal_neighbours())
+    }
+
+    /// Sets if diagonal tiles are neighbours on a square grid.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// tilemap.set_diagonal_neighbours(true);
+    /// assert!(tilemap.diagonal_neighbours());
+    /// ```
+    pub fn set_diagonal_neighbours(&mut self, diagonals: bool) {
+        self.auto_flags
+            .set(AutoFlags::DIAGONAL_NEIGHBOURS, diagonals);
+    }
+
+    /// Returns `true` if diagonal tiles are neighbours on a square grid.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .diagonal_neighbours()
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert!(tilemap.diagonal_neighbours());
+    /// ```
+    pub fn diagonal_neighbours(&self) -> bool {
+        self.auto_flags.contains(AutoFlags::DIAGONAL_NEIGHBOURS)
+    }
+
+    /// Returns the point of the tile at a world position, as it is rendered
+    /// for the topology of the tilemap.
+    ///
+    /// The transform is the `Transform` of the tilemap entity. The tile whose
+    /// center is the closest to the position is returned, which for hex tiles
+    /// is the hex that the position is in.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .tile_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let transform = Transform::from_translation(Vec3::new(100.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(tilemap.world_to_point(&transform, Vec2::new(101.0, 1.0)), Point2::new(0, 0));
+    /// assert_eq!(tilemap.world_to_point(&transform, Vec2::new(99.0, 70.0)), Point2::new(-1, 2));
+    /// ```
+    pub fn world_to_point(&self, transform: &Transform, position: Vec2) -> Point2 {
+        let position = Vec3::new(position.x(), position.y(), 0.0) - transform.translation;
+        let local = transform.rotation.conjugate() * (position / transform.scale);
+        let (x, y) = (local.x(), local.y());
+
+        let chunk_width = self.chunk_dimensions.width as i32;
+        let chunk_height = self.chunk_dimensions.height as i32;
+        let pixel_width = (chunk_width * self.tile_dimensions.width as i32) as f32;
+        let pixel_height = (chunk_height * self.tile_dimensions.height as i32) as f32;
+        let center_chunk = Point2::new(
+            (x / pixel_width).round() as i32,
+            (y / pixel_height).round() as i32,
+        );
+
+        // An estimate of the tile from the centers of tiles in the chunk, which
+        // is at most a tile off for hex grids with alternating offsets.
+        let center = |tile_x: i32, tile_y: i32| {
+            rendered_tile_center(
+                self.topology,
+                self.chunk_dimensions,
+                self.tile_dimensions,
+                Point2::new(tile_x, tile_y),
+            )
+        };
+        let origin = center(0, 0);
+        let step_x = center(2, 0);
+        let step_x = ((step_x.0 - origin.0) / 2.0, (step_x.1 - origin.1) / 2.0);
+        let step_y = center(0, 2);
+        let step_y = ((step_y.0 - origin.0) / 2.0, (step_y.1 - origin.1) / 2.0);
+        let determinant = step_x.0 * step_y.1 - step_x.1 * step_y.0;
+
+        // Chunks of hex grids are skewed, so the neighbouring chunks are
+        // searched as well.
+        let mut closest = (f32::INFINITY, Point2::new(0, 0));
+        for chunk_y in center_chunk.y - 1..=center_chunk.y + 1 {
+            for chunk_x in center_chunk.x - 1..=center_chunk.x + 1 {
+                let chunk_point = Point2::new(chunk_x, chunk_y);
+                let (translation_x, translation_y) = self.chunk_translation(chunk_point);
+                let dx = x - translation_x - origin.0;
+                let dy = y - translation_y - origin.1;
+                let estimate_x = ((dx * step_y.1 - dy * step_y.0) / determinant).round() as i32;
+                let estimate_y = ((step_x.0 * dy - step_x.1 * dx) / determinant).round() as i32;
+                let estimate_x = estimate_x.max(0).min(chunk_width - 1);
+                let estimate_y = estimate_y.max(0).min(chunk_height - 1);
+
+                for tile_y in (estimate_y - 1).max(0)..=(estimate_y + 1).min(chunk_height - 1) {
+                    for tile_x in (estimate_x - 1).max(0)..=(estimate_x + 1).min(chunk_width - 1) {
+                        let (center_x, center_y) = center(tile_x, tile_y);
+                        let distance_x = x - translation_x - center_x;
+                        let distance_y = y - translation_y - center_y;
+                        let distance = distance_x * distance_x + distance_y * distance_y;
+                        if distance < closest.0 {
+                            let point = Point2::new(
+                                tile_x + chunk_width * chunk_x - chunk_width / 2,
+                                tile_y + chunk_height * chunk_y - chunk_height / 2,
+                            );
+                            closest = (distance, point);
+                        }
+                    }
+                }
+            }
+        }
+        closest.1
+    }
+
+    /// Returns the translation of a chunk from the tilemap.
+    fn chunk_translation(&self, chunk_point: Point2) -> (f32, f32) {
+        (
+            (chunk_point.x * self.tile_dimensions.width as i32 * self.chunk_dimensions.width as i32)
+                as f32,
+            (chunk_point.y
+                * self.tile_dimensions.height as i32
+                * self.chunk_dimensions.height as i32) as f32,
+        )
+    }
+
+    /// Returns the position of the center of a tile from the tilemap.
+    fn tile_center(&self, point: Point2) -> (f32, f32) {
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let (translation_x, translation_y) = self.chunk_translation(chunk_point);
+        let (x, y) = rendered_tile_center(
+            self.topology,
+            self.chunk_dimensions,
+            self.tile_dimensions,
+            self.point_to_tile_point(point),
+        );
+        (x + translation_x, y + translation_y)
+    }
+
+    /// Sets many tiles, creating new chunks if needed.
+    ///
+    /// If setting a single tile is more preferable, then use the [`insert_tile`]
+    /// method instead.
+    ///
+    /// If the chunk does not yet exist, it will create a new one automatically.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given coordinate or index is out of bounds, the
+    /// layer or chunk does not exist. If either the layer or chunk error occurs
+    /// then creating what is missing will resolve it. Tiles can not be
+    /// inserted into a fog layer, which also returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::tile::RawTile;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let mut tiles = vec![
+    ///     Tile::new((1, 1), 0),
+    ///     Tile::new((2, 2), 1),
+    ///     Tile::new((3, 3), 2)
+    /// ];
+    ///
+    /// // Set multiple tiles and unwrap the result
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((1, 1), 0), Some(&RawTile { index: 0, color: Color::WHITE, flip: TileFlip::empty() }));
+    /// assert_eq!(tilemap.get_tile((2, 2), 0), Some(&RawTile { index: 1, color: Color::WHITE, flip: TileFlip::empty() }));
+    /// assert_eq!(tilemap.get_tile((3, 3), 0), Some(&RawTile { index: 2, color: Color::WHITE, flip: TileFlip::empty() }));
+    /// assert_eq!(tilemap.get_tile((4, 4), 0), None);
+    /// ```
+    ///
+    /// [`insert_tile`]: Tilemap::insert_tile
+    pub fn insert_tiles<I>(&mut self, tiles: I) -> TilemapResult<()>
+    where
+        I: IntoIterator<Item = Tile>,
+    {
+        let width = self.chunk_dimensions.width as i32;
+        let height = self.chunk_dimensions.height as i32;
+
+        let mut chunk_map: HashMap<Point2, Vec<(Point2, Tile)>> = HashMap::default();
+        for tile in tiles.into_iter() {
+            let global_tile_point: Point2 = tile.point;
+            let chunk_point: Point2 = self.point_to_chunk_point(global_tile_point).into();
+
+            if let Some(layer) = self.layers.get(tile.z_order as usize) {
+                match layer {
+                    None => self.add_layer(tile.z_order as usize)?,
+                    Some(LayerKind::Fog(_)) => {
+                        return Err(ErrorKind::LayerIsFog(tile.z_order).into())
+                    }
+                    Some(_) => {}
+                }
+            } else {
+                return Err(ErrorKind::LayerDoesNotExist(tile.z_order).into());
+            }
+
+            if let Some(animation) = tile.animation {
+                if animation >= self.animations.len() {
+                    return Err(ErrorKind::AnimationDoesNotExist(animation).into());
+                }
+            }
+
+            if self.pending.contains(&chunk_point) {
+                self.pending.queue_tile(chunk_point, tile);
+                continue;
+            }
+
+            let tile_point = Point2::new(
+                global_tile_point.x - (width * chunk_point.x) + (width / 2),
+                global_tile_point.y - (height * chunk_point.y) + (height / 2),
+            );
+
+            let chunk_tile: Tile = Tile {
+                point: tile_point,
+                ..tile
+            };
+            if let Some(tiles) = chunk_map.get_mut(&chunk_point) {
+                tiles.push((global_tile_point, chunk_tile));
+            } else {
+                let tiles = vec![(global_tile_point, chunk_tile)];
+                chunk_map.insert(chunk_point, tiles);
+            }
+        }
+
+        if self.auto_flags.contains(AutoFlags::AUTO_CHUNK) {
+            for point in chunk_map.keys() {
+                if !self.chunks.contains_key(point) {
+                    self.create_chunk(*point)?;
+                }
+            }
+        }
+
+        let mut auto_tile_points = Vec::new();
+        for (point, tiles) in chunk_map.into_iter() {
+            let chunk = match self.chunks.get_mut(&point) {
+                Some(c) => c,
+                None => return Err(ErrorKind::MissingChunk.into()),
+            };
+
+            let mut layers = HashMap::default();
+            for (global_tile_point, tile) in tiles.into_iter() {
+                let index = self.chunk_dimensions.encode_point_unchecked(tile.point);
+                let sprite_index = tile
+                    .animation
+                    .and_then(|animation| self.animations[animation].sprite_index())
+                    .unwrap_or(tile.sprite_index);
+                let raw_tile = RawTile {
+                    index: sprite_index,
+                    color: tile.tint,
+                    flip: tile.flip,
+                };
+                chunk.set_raw_tile(tile.z_order, index, raw_tile);
+                chunk.set_animation(tile.z_order, index, tile.animation);
+                let previous_terrain = chunk.set_terrain(tile.z_order, index, tile.terrain);
+                if previous_terrain.is_some() || tile.terrain.is_some() {
+                    auto_tile_points.push((global_tile_point, tile.z_order));
+                }
+                if let Some(entity) = chunk.get_entity(tile.z_order) {
+                    layers.entry(tile.z_order).or_insert(entity);
+                }
+            }
+
+            self.events.send(ChunkEvent::Modified { layers });
+        }
+
+        if !auto_tile_points.is_empty() {
+            self.update_auto_tiles(auto_tile_points);
+        }
+
+        Ok(())
+    }
+
+    /// Updates the sprites of the auto tiles at the points given and all of
+    /// their neighbours, even if they are in another chunk.
+    fn update_auto_tiles(&mut self, points: Vec<(Point2, usize)>) {
+        let mut to_update: HashSet<(Point2, usize)> = HashSet::default();
+        for (point, z_order) in points.into_iter() {
+            to_update.insert((point, z_order));
+            for &((x, y), _) in NEIGHBOUR_OFFSETS.iter() {
+                to_update.insert((Point2::new(point.x + x, point.y + y), z_order));
+            }
+        }
+
+        let mut modified_chunks: HashMap<Point2, HashMap<usize, Entity>> = HashMap::default();
+        for (point, z_order) in to_update.into_iter() {
+            let terrain = match self.get_terrain(point, z_order) {
+                Some(terrain) => terrain,
+                None => continue,
+            };
+            let sprite = match self.auto_tilesets.get(&terrain) {
+                Some(tileset) => tileset.sprite(self.terrain_mask(point, z_order, terrain)),
+                None => continue,
+            };
+
+            let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+            let tile_point = self.point_to_tile_point(point);
+            let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+            let chunk = match self.chunks.get_mut(&chunk_point) {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            // Only flagged as modified if the sprite changes.
+            if let Some((tile, modified)) = chunk.get_tile_mut(z_order, index) {
+                if tile.index == sprite {
+                    continue;
+                }
+                tile.index = sprite;
+                *modified = true;
+                if let Some(entity) = chunk.get_entity(z_order) {
+                    modified_chunks
+                        .entry(chunk_point)
+                        .or_insert_with(HashMap::default)
+                        .insert(z_order, entity);
+                }
+            }
+        }
+
+        for (_, layers) in modified_chunks.into_iter() {
+            self.events.send(ChunkEvent::Modified { layers });
+        }
+    }
+
+    /// Returns the 8-bit mask of all the neighbours of a point which share the
+    /// same terrain.
+    fn terrain_mask(&self, point: Point2, z_order: usize, terrain: u32) -> u8 {
+        let mut mask = 0;
+        for &((x, y), bit) in NEIGHBOUR_OFFSETS.iter() {
+            let neighbour = Point2::new(point.x + x, point.y + y);
+            if self.get_terrain(neighbour, z_order) == Some(terrain) {
+                mask |= bit;
+            }
+        }
+        mask
+    }
+
+    /// Inserts an auto tileset for a terrain, returning the previous tileset
+    /// if there was one.
+    ///
+    /// Tiles that already exist with the terrain will only be updated once they
+    /// or one of their neighbours change.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// let terrain = 1;
+    /// assert!(tilemap.insert_auto_tileset(terrain, AutoTileset::bitmask4([0; 16])).is_none());
+    /// assert!(tilemap.insert_auto_tileset(terrain, AutoTileset::blob47([0; 47])).is_some());
+    /// ```
+    pub fn insert_auto_tileset(
+        &mut self,
+        terrain: u32,
+        tileset: AutoTileset,
+    ) -> Option<AutoTileset> {
+        self.auto_tilesets.insert(terrain, tileset)
+    }
+
+    /// Gets the terrain of a tile from a given point and z order, if it has
+    /// one.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let terrain = 1;
+    /// tilemap.insert_tile(Tile::with_terrain((2, 2), terrain, 0)).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_terrain((2, 2), 0), Some(terrain));
+    /// assert_eq!(tilemap.get_terrain((3, 3), 0), None);
+    /// ```
+    pub fn get_terrain<P: Into<Point2>>(&self, point: P, z_order: usize) -> Option<u32> {
+        let point: Point2 = point.into();
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let chunk = self.chunks.get(&chunk_point)?;
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        chunk.get_terrain(z_order, index)
+    }
+
+    /// Adds an animation to the tilemap and returns its ID.
+    ///
+    /// Tiles use the ID to be animated with [`Tile::with_animation`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// let water = tilemap.add_animation(TileAnimation::new(vec![(4, 0.5), (5, 0.5)]));
+    /// let lava = tilemap.add_animation(TileAnimation::new(vec![(6, 1.0), (7, 1.0)]));
+    ///
+    /// assert_eq!(water, 0);
+    /// assert_eq!(lava, 1);
+    /// ```
+    ///
+    /// [`Tile::with_animation`]: crate::tile::Tile::with_animation
+    pub fn add_animation(&mut self, animation: TileAnimation) -> usize {
+        self.animations.push(AnimationState::new(animation));
+        self.animations.len() - 1
+    }
+
+    /// Returns the definition of an animation by its ID, if it exists.
+    pub(crate) fn get_animation(&self, animation: usize) -> Option<&TileAnimation> {
+        self.animations
+            .get(animation)
+            .map(AnimationState::animation)
+    }
+
+    /// Returns the ID of an animation of the tilemap which is the same as the
+    /// given animation, or adds it if there is none.
+    pub(crate) fn find_or_add_animation(&mut self, animation: TileAnimation) -> usize {
+        match self
+            .animations
+            .iter()
+            .position(|state| state.animation() == &animation)
+        {
+            Some(id) => id,
+            None => self.add_animation(animation),
+        }
+    }
+
+    /// Advances all the animations by a delta in seconds and updates the
+    /// sprites of every animated tile whose animation changed frame.
+    pub(crate) fn update_animations(&mut self, delta: f32) {
+        let mut sprites: HashMap<usize, usize> = HashMap::default();
+        for (id, state) in self.animations.iter_mut().enumerate() {
+            if state.tick(delta) {
+                if let Some(sprite_index) = state.sprite_index() {
+                    sprites.insert(id, sprite_index);
+                }
+            }
+        }
+        if sprites.is_empty() {
+            return;
+        }
+
+        for chunk in self.chunks.values_mut() {
+            let mut layers = HashMap::default();
+            for z_order in chunk.update_animations(&sprites) {
+                if let Some(entity) = chunk.get_entity(z_order) {
+                    layers.insert(z_order, entity);
+                }
+            }
+            if !layers.is_empty() {
+                self.events.send(ChunkEvent::Modified { layers });
+            }
+        }
+    }
+
+    /// Sets a single tile at a coordinate position, creating a chunk if necessary.
+    ///
+    /// If you are setting more than one tile at a time, it is highly
+    /// recommended not to run this method! If that is preferred, do use
+    /// [`insert_tiles`] instead. Every single tile that is created creates a new
+    /// event. With bulk tiles, it creates 1 event for all.
+    ///
+    /// If the chunk does not yet exist, it will create a new one automatically.
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::tile::RawTile;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let point = (9, 3);
+    /// let sprite_index = 3;
+    /// let tile = Tile::new(point, sprite_index);
+    ///
+    /// assert!(tilemap.insert_tile(tile).is_ok());
+    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE, flip: TileFlip::empty() }))
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given coordinate or index is out of bounds.
+    pub fn insert_tile(&mut self, tile: Tile) -> TilemapResult<()> {
+        let tiles = vec![tile];
+        self.insert_tiles(tiles)
+    }
+
+    /// Clears the tiles at the specified points from the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::tile::RawTile;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    ///
+    /// let mut tiles = vec![
+    ///     Tile::new((1, 1), 0),
+    ///     Tile::new((2, 2), 0),
+    ///     Tile::new((3, 3), 0)
+    /// ];
+    ///
+    /// // Set multiple tiles and unwrap the result
+    /// assert!(tilemap.insert_tiles(tiles.clone()).is_ok());
+    ///
+    /// // Then later on... Do note that if this done in the same frame, the
+    /// // tiles will not even exist at all.
+    /// let mut to_remove = vec![
+    ///     ((1, 1), 0),
+    ///     ((2, 2), 0),
+    /// ];
+    ///
+    /// tilemap.clear_tiles(to_remove).unwrap();
+    /// assert_eq!(tilemap.get_tile((1, 1), 0), None);
+    /// assert_eq!(tilemap.get_tile((2, 2), 0), None);
+    /// assert_eq!(tilemap.get_tile((3, 3), 0), Some(&RawTile { index: 0, color: Color::WHITE, flip: TileFlip::empty() } ));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error can occure if the point is outside of the tilemap. This can
+    /// only happen if the tilemap has dimensions.
+    pub fn clear_tiles<P, I>(&mut self, points: I) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = (P, usize)>,
+    {
+        let mut tiles = Vec::new();
+        for (point, z_order) in points {
+            tiles.push(Tile::with_z_order_and_tint(
+                point,
+                0,
+                z_order,
+                Color::rgba(0.0, 0.0, 0.0, 0.0),
+            ));
+        }
+        self.insert_tiles(tiles)?;
+        Ok(())
+    }
+
+    /// Takes a global tile point and returns a tile point in a chunk.
+    fn point_to_tile_point(&self, point: Point2) -> Point2 {
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let width = self.chunk_dimensions.width as i32;
+        let height = self.chunk_dimensions.height as i32;
+        Point2::new(
+            point.x - (width * chunk_point.x) + (width / 2),
+            point.y - (height * chunk_point.y) + (height / 2),
+        )
+    }
+
+    /// Clear a single tile at the specified point from the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::tile::RawTile;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    ///
+    /// let point = (3, 1);
+    /// let sprite_index = 1;
+    /// let tile = Tile::new(point, sprite_index);
+    ///
+    /// // Set a single tile and unwrap the result
+    /// assert!(tilemap.insert_tile(tile).is_ok());
+    ///
+    /// // Later on...
+    /// assert!(tilemap.clear_tile(point, 0).is_ok());
+    /// assert_eq!(tilemap.get_tile((3, 1), 0), None);
+    /// ```
+    ///
+    /// # Errors
     ///
-    /// tilemap.insert_tile(tile);
+    /// An error can occure if the point is outside of the tilemap. This can
+    /// only happen if the tilemap has dimensions.
+    pub fn clear_tile<P>(&mut self, point: P, z_order: usize) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+    {
+        let points = vec![(point, z_order)];
+        self.clear_tiles(points)
+    }
+
+    /// Inserts copies of a tile at every point with a single call to
+    /// [`insert_tiles`] and returns the points that were changed.
     ///
-    /// assert!(tilemap.spawn_chunk_containing_point(point).is_ok());
-    /// assert!(tilemap.spawn_chunk_containing_point((16, 16)).is_err());
-    /// assert!(tilemap.spawn_chunk_containing_point((-18, -18)).is_err());
-    /// ```
-    pub fn spawn_chunk_containing_point<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point = self.point_to_chunk_point(point);
-        self.spawn_chunk(point)
+    /// Points which already have the same sprite, tint, flip, terrain and
+    /// animation are skipped, points in chunks that do not exist are not.
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    fn insert_tile_at_points(
+        &mut self,
+        points: HashSet<Point2>,
+        tile: Tile,
+    ) -> TilemapResult<HashSet<Point2>> {
+        let raw_tile = RawTile {
+            index: tile.sprite_index,
+            color: tile.tint,
+            flip: tile.flip,
+        };
+        let z_order = tile.z_order;
+        let points: HashSet<Point2> = points
+            .into_iter()
+            .filter(|point| {
+                self.chunk_and_index(*point).map_or(true, |(chunk, index)| {
+                    chunk.get_tile(z_order, index) != Some(&raw_tile)
+                        || chunk.get_terrain(z_order, index) != tile.terrain
+                        || chunk.get_animation(z_order, index) != tile.animation
+                })
+            })
+            .collect();
+        self.insert_tiles(points.iter().map(|point| Tile {
+            point: *point,
+            ..tile
+        }))?;
+        Ok(points)
     }
 
-    /// De-spawns a spawned chunk at a given index or coordinate.
+    /// Fills the rectangle between two corners, both included, with copies of
+    /// a tile and returns the points that were changed.
     ///
-    /// If the chunk is not spawned this will result in nothing.
+    /// The point of the tile is ignored, everything else such as the Z order
+    /// and tint is kept. All the tiles are inserted at once, so every chunk is
+    /// only re-rendered once.
     ///
     /// # Errors
     ///
-    /// If the coordinate or index is out of bounds, an error will be returned.
+    /// Returns an error if the tiles could not be inserted, see
+    /// [`insert_tiles`].
     ///
     /// # Examples
     /// ```
@@ -1058,50 +2894,46 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
-    ///     .finish()
-    ///     .unwrap();
-    ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    ///
-    /// // Ideally you should want to set some tiles here else nothing will
-    /// // display in the render...
-    ///
-    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// // Later a frame or more on...
+    /// let floor = Tile::new((0, 0), 2);
+    /// let points = tilemap.fill_rect((-2, -1), (2, 1), floor).unwrap();
     ///
-    /// assert!(tilemap.despawn_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.despawn_chunk((-1, -1)).is_err());
+    /// assert_eq!(points.len(), 15);
+    /// assert_eq!(tilemap.get_tile((2, -1), 0).unwrap().index, 2);
+    /// assert_eq!(tilemap.get_tile((3, -1), 0), None);
     /// ```
-    pub fn despawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point: Point2 = point.into();
-        if let Some(dimensions) = &self.dimensions {
-            dimensions.check_point(point)?;
-        }
-
-        if let Some(chunk) = self.chunks.get_mut(&point) {
-            let entities = chunk.get_entities();
-            self.events.send(ChunkEvent::Despawned { entities })
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn fill_rect<P: Into<Point2>>(
+        &mut self,
+        corner: P,
+        opposite_corner: P,
+        tile: Tile,
+    ) -> TilemapResult<HashSet<Point2>> {
+        let (a, b): (Point2, Point2) = (corner.into(), opposite_corner.into());
+        let mut points = HashSet::default();
+        for y in a.y.min(b.y)..=a.y.max(b.y) {
+            for x in a.x.min(b.x)..=a.x.max(b.x) {
+                points.insert(Point2::new(x, y));
+            }
         }
-
-        Ok(())
+        self.insert_tile_at_points(points, tile)
     }
 
-    /// Destructively removes a chunk at a coordinate position and despawns them
-    /// if needed.
-    ///
-    /// Internally, this sends an event to the tilemap's system flagging which
-    /// chunks must be removed by index and entity. A chunk is not recoverable
-    /// if this action is done.
+    /// Draws a line between two points, both included, with copies of a tile
+    /// and returns the points that were changed.
     ///
-    /// Does nothing if the chunk does not exist.
+    /// The line follows the topology of the tilemap, see [`topology::line`].
+    /// The point of the tile is ignored, everything else such as the Z order
+    /// and tint is kept. All the tiles are inserted at once, so every chunk is
+    /// only re-rendered once.
     ///
     /// # Errors
     ///
-    /// If the coordinate or index is out of bounds, an error will be returned.
+    /// Returns an error if the tiles could not be inserted, see
+    /// [`insert_tiles`].
     ///
     /// # Examples
     /// ```
@@ -1112,30 +2944,42 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(3, 3)
-    ///     .finish()
-    ///     .unwrap();
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// // Add some chunks.
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
+    /// let wall = Tile::new((0, 0), 1);
+    /// let points = tilemap.draw_line((0, 0), (4, 1), wall).unwrap();
     ///
-    /// assert!(tilemap.remove_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.remove_chunk((1, 1)).is_ok());
-    /// assert!(tilemap.remove_chunk((-2, -2)).is_err());
+    /// assert_eq!(points.len(), 5);
+    /// assert!(points.contains(&Point2::new(2, 1)));
+    /// assert_eq!(tilemap.get_tile((4, 1), 0).unwrap().index, 1);
     /// ```
-    pub fn remove_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point = point.into();
-        self.despawn_chunk(point)?;
-
-        self.chunks.remove(&point);
-
-        Ok(())
+    ///
+    /// [`topology::line`]: crate::topology::line
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn draw_line<P: Into<Point2>>(
+        &mut self,
+        from: P,
+        to: P,
+        tile: Tile,
+    ) -> TilemapResult<HashSet<Point2>> {
+        let points = topology::line(self.topology, from.into(), to.into());
+        self.insert_tile_at_points(points.into_iter().collect(), tile)
     }
 
-    /// Takes a tile point and changes it into a chunk point.
+    /// Draws a circle around a center point with copies of a tile and returns
+    /// the points that were changed.
+    ///
+    /// The circle follows the topology of the tilemap, see
+    /// [`topology::circle`]. If `filled` is not set only its edge is drawn.
+    /// The point of the tile is ignored, everything else such as the Z order
+    /// and tint is kept. All the tiles are inserted at once, so every chunk is
+    /// only re-rendered once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tiles could not be inserted, see
+    /// [`insert_tiles`].
     ///
     /// # Examples
     /// ```
@@ -1147,50 +2991,48 @@ impl Tilemap {
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
     /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let tile_point = (15, 15);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((0, 0), chunk_point);
-    ///
-    /// let tile_point = (16, 16);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((1, 1), chunk_point);
-    ///
-    /// let tile_point = (-16, -16);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((-0, -0), chunk_point);
+    /// let water = Tile::new((0, 0), 4);
+    /// let points = tilemap.draw_circle((0, 0), 3, water, false).unwrap();
     ///
-    /// let tile_point = (-17, -17);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((-1, -1), chunk_point);
+    /// assert!(points.contains(&Point2::new(3, 0)));
+    /// assert!(!points.contains(&Point2::new(0, 0)));
+    /// assert_eq!(tilemap.get_tile((0, 0), 0), None);
     /// ```
-    pub fn point_to_chunk_point<P: Into<Point2>>(&self, point: P) -> (i32, i32) {
-        let point: Point2 = point.into();
-        let width = self.chunk_dimensions.width as f32;
-        let height = self.chunk_dimensions.height as f32;
-        let x = ((point.x as f32 + width / 2.0) / width).floor() as i32;
-        let y = ((point.y as f32 + height / 2.0) / height).floor() as i32;
-        (x, y)
+    ///
+    /// [`topology::circle`]: crate::topology::circle
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn draw_circle<P: Into<Point2>>(
+        &mut self,
+        center: P,
+        radius: u32,
+        tile: Tile,
+        filled: bool,
+    ) -> TilemapResult<HashSet<Point2>> {
+        let points = topology::circle(self.topology, center.into(), radius, filled);
+        self.insert_tile_at_points(points.into_iter().collect(), tile)
     }
 
-    /// Sets many tiles, creating new chunks if needed.
-    ///
-    /// If setting a single tile is more preferable, then use the [`insert_tile`]
-    /// method instead.
+    /// Fills the area around a point with copies of a tile and returns the
+    /// points that were changed.
     ///
-    /// If the chunk does not yet exist, it will create a new one automatically.
+    /// The area is every point connected to the start through neighbours, see
+    /// [`topology::neighbours`], that has the same tile as the start in the
+    /// layer of the tile, including no tile at all. The area never extends
+    /// into chunks that do not exist. Nothing is changed if the start already
+    /// has the same sprite, tint and flip as the tile. The point of the tile
+    /// is ignored, everything else such as the Z order is kept. All the tiles
+    /// are inserted at once, so every chunk is only re-rendered once.
     ///
     /// # Errors
     ///
-    /// Returns an error if the given coordinate or index is out of bounds, the
-    /// layer or chunk does not exist. If either the layer or chunk error occurs
-    /// then creating what is missing will resolve it.
+    /// Returns an error if the chunk of the start does not exist or if the
+    /// tiles could not be inserted, see [`insert_tiles`].
     ///
     /// # Examples
-    ///
     /// ```
     /// use bevy_tilemap::prelude::*;
-    /// use bevy_tilemap::tile::RawTile;
     /// use bevy::asset::HandleId;
     /// use bevy::prelude::*;
     ///
@@ -1199,254 +3041,309 @@ impl Tilemap {
     ///
     /// let mut tilemap = TilemapBuilder::new()
     ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
+    ///     .chunk_dimensions(8, 8)
     ///     .finish()
     ///     .unwrap();
-    ///
     /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let mut tiles = vec![
-    ///     Tile::new((1, 1), 0),
-    ///     Tile::new((2, 2), 1),
-    ///     Tile::new((3, 3), 2)
-    /// ];
+    /// // A wall splits the chunk into a left and a right side.
+    /// let wall = Tile::new((0, 0), 1);
+    /// tilemap.draw_line((0, -4), (0, 3), wall).unwrap();
     ///
-    /// // Set multiple tiles and unwrap the result
-    /// tilemap.insert_tiles(tiles).unwrap();
+    /// let grass = Tile::new((0, 0), 2);
+    /// let points = tilemap.flood_fill((-1, 0), grass).unwrap();
     ///
-    /// assert_eq!(tilemap.get_tile((1, 1), 0), Some(&RawTile { index: 0, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((2, 2), 0), Some(&RawTile { index: 1, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((3, 3), 0), Some(&RawTile { index: 2, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((4, 4), 0), None);
+    /// assert_eq!(points.len(), 32);
+    /// assert_eq!(tilemap.get_tile((-4, -4), 0).unwrap().index, 2);
+    /// assert_eq!(tilemap.get_tile((1, 0), 0), None);
     /// ```
     ///
-    /// [`insert_tile`]: Tilemap::insert_tile
-    pub fn insert_tiles<I>(&mut self, tiles: I) -> TilemapResult<()>
-    where
-        I: IntoIterator<Item = Tile>,
-    {
-        let width = self.chunk_dimensions.width as i32;
-        let height = self.chunk_dimensions.height as i32;
-
-        let mut chunk_map: HashMap<Point2, Vec<Tile>> = HashMap::default();
-        for tile in tiles.into_iter() {
-            let global_tile_point: Point2 = tile.point;
-            let chunk_point: Point2 = self.point_to_chunk_point(global_tile_point).into();
-
-            if let Some(layer) = self.layers.get(tile.z_order as usize) {
-                if layer.as_ref().is_none() {
-                    self.add_layer(tile.z_order as usize)?;
-                }
-            } else {
-                return Err(ErrorKind::LayerDoesNotExist(tile.z_order).into());
-            }
-
-            let tile_point = Point2::new(
-                global_tile_point.x - (width * chunk_point.x) + (width / 2),
-                global_tile_point.y - (height * chunk_point.y) + (height / 2),
-            );
-
-            let chunk_tile: Tile = Tile {
-                point: tile_point,
-                z_order: tile.z_order,
-                sprite_index: tile.sprite_index,
-                tint: tile.tint,
-            };
-            if let Some(tiles) = chunk_map.get_mut(&chunk_point) {
-                tiles.push(chunk_tile);
-            } else {
-                let tiles = vec![chunk_tile];
-                chunk_map.insert(chunk_point, tiles);
-            }
+    /// [`topology::neighbours`]: crate::topology::neighbours
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn flood_fill<P: Into<Point2>>(
+        &mut self,
+        point: P,
+        tile: Tile,
+    ) -> TilemapResult<HashSet<Point2>> {
+        let start: Point2 = point.into();
+        let z_order = tile.z_order;
+        if let Some(Some(LayerKind::Fog(_))) = self.layers.get(z_order) {
+            return Err(ErrorKind::LayerIsFog(z_order).into());
+        }
+        let target = match self.chunk_and_index(start) {
+            Some((chunk, index)) => chunk.get_tile(z_order, index).cloned(),
+            None => return Err(ErrorKind::MissingChunk.into()),
+        };
+        let replacement = RawTile {
+            index: tile.sprite_index,
+            color: tile.tint,
+            flip: tile.flip,
+        };
+        if target == Some(replacement) {
+            return Ok(HashSet::default());
         }
 
-        for (point, tiles) in chunk_map.into_iter() {
-            // Is there a better way to do this? Clippy hates if I don't do it
-            // like this talking about constructing regardless yet, here it is,
-            // copying stuff regardless because it doesn't like self in the
-            // `FnOnce`.
-            let layers = self.layers.clone();
-            let chunk_dimensions = self.chunk_dimensions;
-            let chunk = if self.auto_flags.contains(AutoFlags::AUTO_CHUNK) {
-                self.chunks
-                    .entry(point)
-                    .or_insert_with(|| Chunk::new(point, &layers, chunk_dimensions))
-            } else {
-                match self.chunks.get_mut(&point) {
-                    Some(c) => c,
-                    None => return Err(ErrorKind::MissingChunk.into()),
+        let mut points = HashSet::default();
+        points.insert(start);
+        let mut to_visit = vec![start];
+        while let Some(point) = to_visit.pop() {
+            for neighbour in topology::neighbours(self.topology, point, false) {
+                if points.contains(&neighbour) {
+                    continue;
                 }
-            };
-
-            let mut layers = HashMap::default();
-            for tile in tiles.into_iter() {
-                let index = self.chunk_dimensions.encode_point_unchecked(tile.point);
-                let raw_tile = RawTile {
-                    index: tile.sprite_index,
-                    color: tile.tint,
-                };
-                chunk.set_raw_tile(tile.z_order, index, raw_tile);
-                if let Some(entity) = chunk.get_entity(tile.z_order) {
-                    layers.entry(tile.z_order).or_insert(entity);
+                let same = self
+                    .chunk_and_index(neighbour)
+                    .map_or(false, |(chunk, index)| {
+                        chunk.get_tile(z_order, index) == target.as_ref()
+                    });
+                if same {
+                    points.insert(neighbour);
+                    to_visit.push(neighbour);
                 }
             }
-
-            self.events.send(ChunkEvent::Modified { layers });
         }
-
-        Ok(())
+        self.insert_tile_at_points(points, tile)
     }
 
-    /// Sets a single tile at a coordinate position, creating a chunk if necessary.
-    ///
-    /// If you are setting more than one tile at a time, it is highly
-    /// recommended not to run this method! If that is preferred, do use
-    /// [`insert_tiles`] instead. Every single tile that is created creates a new
-    /// event. With bulk tiles, it creates 1 event for all.
-    ///
-    /// If the chunk does not yet exist, it will create a new one automatically.
+    /// Sets the fog of war states of many tiles in a fog layer.
     ///
-    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// Only the chunks in which a state changed are re-rendered. Points in
+    /// chunks that do not exist are ignored.
     ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
-    /// use bevy_tilemap::tile::RawTile;
     /// use bevy::asset::HandleId;
     /// use bevy::prelude::*;
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// let mut tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .add_layer(LayerKind::Fog(FogTints::default()), 3)
+    ///     .finish()
+    ///     .unwrap();
     ///
     /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let point = (9, 3);
-    /// let sprite_index = 3;
-    /// let tile = Tile::new(point, sprite_index);
+    /// let states = vec![((1, 1), FogState::Explored), ((2, 1), FogState::Visible)];
+    /// tilemap.set_fog_states(3, states).unwrap();
     ///
-    /// assert!(tilemap.insert_tile(tile).is_ok());
-    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE }))
+    /// assert_eq!(tilemap.get_fog((1, 1), 3), Some(FogState::Explored));
+    /// assert_eq!(tilemap.get_fog((2, 1), 3), Some(FogState::Visible));
+    /// assert!(tilemap.set_fog_states(0, vec![((1, 1), FogState::Visible)]).is_err());
     /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if the given coordinate or index is out of bounds.
-    pub fn insert_tile(&mut self, tile: Tile) -> TilemapResult<()> {
-        let tiles = vec![tile];
-        self.insert_tiles(tiles)
+    /// Returns an error if the layer is not a fog layer.
+    pub fn set_fog_states<P, I>(&mut self, z_order: usize, states: I) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = (P, FogState)>,
+    {
+        self.check_fog_layer(z_order)?;
+        let mut modified_chunks = HashSet::default();
+        for (point, state) in states.into_iter() {
+            let point: Point2 = point.into();
+            let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+            let tile_point = self.point_to_tile_point(point);
+            let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+            let fog = match self
+                .chunks
+                .get_mut(&chunk_point)
+                .and_then(|chunk| chunk.get_fog_layer_mut(z_order))
+            {
+                Some(fog) => fog,
+                None => continue,
+            };
+            if fog.set_state(index, state) {
+                modified_chunks.insert(chunk_point);
+            }
+        }
+        self.fog_modified(z_order, modified_chunks);
+        Ok(())
     }
 
-    /// Clears the tiles at the specified points from the tilemap.
+    /// Updates a fog layer with the points that can be seen now.
+    ///
+    /// The given points become visible, and every other visible tile becomes
+    /// explored. This takes the points of a field of view as they are. Only
+    /// the chunks in which a state changed are re-rendered.
     ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
-    /// use bevy_tilemap::tile::RawTile;
     /// use bevy::asset::HandleId;
     /// use bevy::prelude::*;
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
+    /// let mut tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .add_layer(LayerKind::Fog(FogTints::default()), 3)
+    ///     .finish()
+    ///     .unwrap();
     ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let mut tiles = vec![
-    ///     Tile::new((1, 1), 0),
-    ///     Tile::new((2, 2), 0),
-    ///     Tile::new((3, 3), 0)
-    /// ];
+    /// tilemap.update_fog(3, vec![(1, 1), (2, 1)]).unwrap();
+    /// tilemap.update_fog(3, vec![(2, 1), (3, 1)]).unwrap();
     ///
-    /// // Set multiple tiles and unwrap the result
-    /// assert!(tilemap.insert_tiles(tiles.clone()).is_ok());
+    /// assert_eq!(tilemap.get_fog((0, 1), 3), Some(FogState::Unexplored));
+    /// assert_eq!(tilemap.get_fog((1, 1), 3), Some(FogState::Explored));
+    /// assert_eq!(tilemap.get_fog((2, 1), 3), Some(FogState::Visible));
+    /// assert_eq!(tilemap.get_fog((3, 1), 3), Some(FogState::Visible));
+    /// ```
     ///
-    /// // Then later on... Do note that if this done in the same frame, the
-    /// // tiles will not even exist at all.
-    /// let mut to_remove = vec![
-    ///     ((1, 1), 0),
-    ///     ((2, 2), 0),
-    /// ];
+    /// # Errors
+    ///
+    /// Returns an error if the layer is not a fog layer.
+    pub fn update_fog<P, I>(&mut self, z_order: usize, visible: I) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        self.check_fog_layer(z_order)?;
+        let mut visible_indexes: HashMap<Point2, HashSet<usize>> = HashMap::default();
+        for point in visible.into_iter() {
+            let point: Point2 = point.into();
+            let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+            let tile_point = self.point_to_tile_point(point);
+            let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+            visible_indexes
+                .entry(chunk_point)
+                .or_insert_with(HashSet::default)
+                .insert(index);
+        }
+
+        let no_indexes = HashSet::default();
+        let mut modified_chunks = HashSet::default();
+        for (chunk_point, chunk) in self.chunks.iter_mut() {
+            let fog = match chunk.get_fog_layer_mut(z_order) {
+                Some(fog) => fog,
+                None => continue,
+            };
+            let indexes = visible_indexes.get(chunk_point).unwrap_or(&no_indexes);
+            let mut changed = fog.hide_visible(indexes);
+            for index in indexes.iter() {
+                changed |= fog.set_state(*index, FogState::Visible);
+            }
+            if changed {
+                modified_chunks.insert(*chunk_point);
+            }
+        }
+        self.fog_modified(z_order, modified_chunks);
+        Ok(())
+    }
+
+    /// Gets the fog of war state of a tile in a fog layer.
+    ///
+    /// Returns `None` if the layer is not a fog layer or the chunk does not
+    /// exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy::asset::HandleId;
+    /// use bevy::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// tilemap.clear_tiles(to_remove).unwrap();
-    /// assert_eq!(tilemap.get_tile((1, 1), 0), None);
-    /// assert_eq!(tilemap.get_tile((2, 2), 0), None);
-    /// assert_eq!(tilemap.get_tile((3, 3), 0), Some(&RawTile { index: 0, color: Color::WHITE} ));
-    /// ```
+    /// let mut tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .add_layer(LayerKind::Fog(FogTints::default()), 3)
+    ///     .finish()
+    ///     .unwrap();
     ///
-    /// # Errors
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// An error can occure if the point is outside of the tilemap. This can
-    /// only happen if the tilemap has dimensions.
-    pub fn clear_tiles<P, I>(&mut self, points: I) -> TilemapResult<()>
+    /// assert_eq!(tilemap.get_fog((1, 1), 3), Some(FogState::Unexplored));
+    /// assert_eq!(tilemap.get_fog((1, 1), 0), None);
+    /// assert_eq!(tilemap.get_fog((100, 1), 3), None);
+    /// ```
+    pub fn get_fog<P>(&self, point: P, z_order: usize) -> Option<FogState>
     where
         P: Into<Point2>,
-        I: IntoIterator<Item = (P, usize)>,
     {
-        let mut tiles = Vec::new();
-        for (point, z_order) in points {
-            tiles.push(Tile::with_z_order_and_tint(
-                point,
-                0,
-                z_order,
-                Color::rgba(0.0, 0.0, 0.0, 0.0),
-            ));
-        }
-        self.insert_tiles(tiles)?;
-        Ok(())
-    }
-
-    /// Takes a global tile point and returns a tile point in a chunk.
-    fn point_to_tile_point(&self, point: Point2) -> Point2 {
-        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
-        let width = self.chunk_dimensions.width as i32;
-        let height = self.chunk_dimensions.height as i32;
-        Point2::new(
-            point.x - (width * chunk_point.x) + (width / 2),
-            point.y - (height * chunk_point.y) + (height / 2),
-        )
+        let (chunk, index) = self.chunk_and_index(point.into())?;
+        chunk.get_fog_layer(z_order)?.get_state(index)
     }
 
-    /// Clear a single tile at the specified point from the tilemap.
+    /// Sets the tints that the states of a fog layer are rendered with.
+    ///
+    /// Every chunk of the layer is re-rendered.
     ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
-    /// use bevy_tilemap::tile::RawTile;
     /// use bevy::asset::HandleId;
     /// use bevy::prelude::*;
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle);
-    ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// let mut tilemap = Tilemap::builder()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .add_layer(LayerKind::Fog(FogTints::default()), 3)
+    ///     .finish()
+    ///     .unwrap();
     ///
-    /// let point = (3, 1);
-    /// let sprite_index = 1;
-    /// let tile = Tile::new(point, sprite_index);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// // Set a single tile and unwrap the result
-    /// assert!(tilemap.insert_tile(tile).is_ok());
+    /// let tints = FogTints::new(
+    ///     Color::rgb(0.1, 0.1, 0.2),
+    ///     Color::rgba(0.1, 0.1, 0.2, 0.5),
+    ///     Color::rgba(0.0, 0.0, 0.0, 0.0),
+    /// );
     ///
-    /// // Later on...
-    /// assert!(tilemap.clear_tile(point, 0).is_ok());
-    /// assert_eq!(tilemap.get_tile((3, 1), 0), None);
+    /// assert!(tilemap.set_fog_tints(3, tints).is_ok());
+    /// assert!(tilemap.set_fog_tints(0, tints).is_err());
     /// ```
     ///
     /// # Errors
     ///
-    /// An error can occure if the point is outside of the tilemap. This can
-    /// only happen if the tilemap has dimensions.
-    pub fn clear_tile<P>(&mut self, point: P, z_order: usize) -> TilemapResult<()>
-    where
-        P: Into<Point2>,
-    {
-        let points = vec![(point, z_order)];
-        self.clear_tiles(points)
+    /// Returns an error if the layer is not a fog layer.
+    pub fn set_fog_tints(&mut self, z_order: usize, tints: FogTints) -> TilemapResult<()> {
+        self.check_fog_layer(z_order)?;
+        self.layers[z_order] = Some(LayerKind::Fog(tints));
+        let mut modified_chunks = HashSet::default();
+        for (chunk_point, chunk) in self.chunks.iter_mut() {
+            if let Some(fog) = chunk.get_fog_layer_mut(z_order) {
+                fog.set_tints(tints);
+                modified_chunks.insert(*chunk_point);
+            }
+        }
+        self.fog_modified(z_order, modified_chunks);
+        Ok(())
+    }
+
+    /// Returns an error if the layer at a z order is not a fog layer.
+    fn check_fog_layer(&self, z_order: usize) -> TilemapResult<()> {
+        match self.layers.get(z_order) {
+            Some(Some(LayerKind::Fog(_))) => Ok(()),
+            _ => Err(ErrorKind::LayerIsNotFog(z_order).into()),
+        }
+    }
+
+    /// Marks the chunks whose fog layer changed as modified and re-renders
+    /// the layer in the ones that are spawned.
+    fn fog_modified(&mut self, z_order: usize, chunk_points: HashSet<Point2>) {
+        for chunk_point in chunk_points.into_iter() {
+            let chunk = match self.chunks.get_mut(&chunk_point) {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            chunk.set_modified(true);
+            if let Some(entity) = chunk.get_entity(z_order) {
+                let mut layers = HashMap::default();
+                layers.insert(z_order, entity);
+                self.events.send(ChunkEvent::Modified { layers });
+            }
+        }
     }
 
     /// Gets a raw tile from a given point and z order.
@@ -1475,7 +3372,7 @@ impl Tilemap {
     /// let tile = Tile::new(point, sprite_index);
     ///
     /// assert!(tilemap.insert_tile(tile).is_ok());
-    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE }));
+    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE, flip: TileFlip::empty() }));
     /// assert_eq!(tilemap.get_tile((10, 4), 0), None);
     /// ```
     pub fn get_tile<P>(&mut self, point: P, z_order: usize) -> Option<&RawTile>
@@ -1490,12 +3387,56 @@ impl Tilemap {
         chunk.get_tile(z_order, index)
     }
 
+    /// Returns the highest z order that has a tile at a point, if any.
+    pub(crate) fn top_z_order(&self, point: Point2) -> Option<usize> {
+        let (chunk, index) = self.chunk_and_index(point)?;
+        (0..self.layers.len())
+            .rev()
+            .find(|z_order| chunk.get_tile(*z_order, index).is_some())
+    }
+
+    /// Returns the loaded chunk that a point is in and the index of the point
+    /// in the chunk.
+    pub(crate) fn chunk_and_index(&self, point: Point2) -> Option<(&Chunk, usize)> {
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let chunk = self.chunks.get(&chunk_point)?;
+        let tile_point = self.point_to_tile_point(point);
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        Some((chunk, index))
+    }
+
+    /// Returns `true` if a chunk point is within the dimensions of the
+    /// tilemap, or if the tilemap has no dimensions.
+    pub(crate) fn chunk_in_bounds(&self, chunk_point: Point2) -> bool {
+        self.dimensions.map_or(true, |dimensions| {
+            dimensions.check_point(chunk_point).is_ok()
+        })
+    }
+
+    /// Returns the amount of layers in the tilemap.
+    pub(crate) fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if the layer at a z order exists and holds sprites.
+    pub(crate) fn is_sprite_layer(&self, z_order: usize) -> bool {
+        match self.layers.get(z_order) {
+            Some(Some(LayerKind::Fog(_))) | Some(None) | None => false,
+            Some(Some(_)) => true,
+        }
+    }
+
     /// Gets a mutable raw tile from a given point and z order.
     ///
     /// This is different thant he usual [`Tile`] struct in that it only
     /// contains the sprite index and the tint.
     ///
+    /// The tile is wrapped in a [`TileMut`] guard. If the tile was mutably
+    /// accessed through the guard, the chunk layer it is in is re-rendered
+    /// once the guard is dropped.
+    ///
     /// [`Tile`]: crate::tile::Tile
+    /// [`TileMut`]: TileMut
     ///
     /// # Examples
     /// ```
@@ -1516,10 +3457,16 @@ impl Tilemap {
     /// let tile = Tile::new(point, sprite_index);
     ///
     /// assert!(tilemap.insert_tile(tile).is_ok());
-    /// assert_eq!(tilemap.get_tile_mut((2, 5), 0), Some(&mut RawTile { index: 2, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile_mut((1, 4), 0), None);
+    /// assert_eq!(tilemap.get_tile_mut((2, 5), 0).as_deref(), Some(&RawTile { index: 2, color: Color::WHITE, flip: TileFlip::empty() }));
+    /// assert!(tilemap.get_tile_mut((1, 4), 0).is_none());
+    ///
+    /// // The chunk is updated when the guard is dropped.
+    /// if let Some(mut tile) = tilemap.get_tile_mut((2, 5), 0) {
+    ///     tile.index = 3;
+    /// }
+    /// assert_eq!(tilemap.get_tile((2, 5), 0), Some(&RawTile { index: 3, color: Color::WHITE, flip: TileFlip::empty() }));
     /// ```
-    pub fn get_tile_mut<P>(&mut self, point: P, z_order: usize) -> Option<&mut RawTile>
+    pub fn get_tile_mut<P>(&mut self, point: P, z_order: usize) -> Option<TileMut<'_>>
     where
         P: Into<Point2>,
     {
@@ -1528,7 +3475,16 @@ impl Tilemap {
         let tile_point = self.point_to_tile_point(point);
         let chunk = self.chunks.get_mut(&chunk_point)?;
         let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
-        chunk.get_tile_mut(z_order, index)
+        let entity = chunk.get_entity(z_order);
+        let (tile, chunk_modified) = chunk.get_tile_mut(z_order, index)?;
+        Some(TileMut {
+            tile,
+            z_order,
+            entity,
+            events: &mut self.events,
+            chunk_modified,
+            modified: false,
+        })
     }
 
     /// Returns the center tile, if the tilemap has dimensions.
@@ -1776,6 +3732,86 @@ impl Tilemap {
     }
 }
 
+/// Returns the position of a vertex of a tile in a chunk, exactly as the
+/// vertex shader of the topology places it.
+///
+/// The vertex is in tiles relative to the center of the chunk and the local
+/// index is the index of the vertex in the quad of the tile.
+fn rendered_vertex(
+    topology: GridTopology,
+    tile_dimensions: Dimension2,
+    vertex: (f32, f32),
+    local_index: usize,
+) -> (f32, f32) {
+    use GridTopology::*;
+    let width = tile_dimensions.width as f32;
+    let height = tile_dimensions.height as f32;
+    let mut x = vertex.0 * width;
+    let mut y = vertex.1 * height;
+
+    match topology {
+        Square => {}
+        HexY | HexEvenRows | HexOddRows => {
+            let mut row = (vertex.1 + 0.01).floor() as i32;
+            if local_index == 0 || local_index == 3 {
+                row += 1;
+            }
+            let quarter = (0.25 * width).floor();
+            match topology {
+                HexY => x += (0.5 * width).floor() * (row as f32 - 0.5),
+                HexEvenRows if row % 2 == 0 => x -= quarter,
+                HexEvenRows => x += quarter,
+                _ if row % 2 == 0 => x += quarter,
+                _ => x -= quarter,
+            }
+            y -= (row as f32 - 0.5) * (0.25 * height).ceil();
+        }
+        HexX | HexEvenCols | HexOddCols => {
+            let mut col = (vertex.0 + 0.01).floor() as i32;
+            if local_index == 0 || local_index == 1 {
+                col += 1;
+            }
+            let quarter = (0.25 * height).floor();
+            match topology {
+                HexX => y += (0.5 * height).floor() * (col as f32 - 0.5),
+                HexEvenCols if col % 2 == 0 => y -= quarter,
+                HexEvenCols => y += quarter,
+                _ if col % 2 == 0 => y += quarter,
+                _ => y -= quarter,
+            }
+            x -= (col as f32 - 0.5) * (0.25 * width).ceil();
+        }
+    }
+
+    (x.ceil(), y.ceil())
+}
+
+/// Returns the position of the center of a tile relative to the center of its
+/// chunk, as it is rendered.
+fn rendered_tile_center(
+    topology: GridTopology,
+    chunk_dimensions: Dimension2,
+    tile_dimensions: Dimension2,
+    tile_point: Point2,
+) -> (f32, f32) {
+    let x0 = tile_point.x as f32 - chunk_dimensions.width as f32 / 2.0;
+    let y0 = tile_point.y as f32 - chunk_dimensions.height as f32 / 2.0;
+    // The same order of vertices as in the mesh of a chunk.
+    let vertices = [
+        (x0, y0),
+        (x0, y0 + 1.0),
+        (x0 + 1.0, y0 + 1.0),
+        (x0 + 1.0, y0),
+    ];
+    let (mut x, mut y) = (0.0, 0.0);
+    for (local_index, vertex) in vertices.iter().enumerate() {
+        let (vertex_x, vertex_y) = rendered_vertex(topology, tile_dimensions, *vertex, local_index);
+        x += vertex_x;
+        y += vertex_y;
+    }
+    (x / 4.0, y / 4.0)
+}
+
 /// Automatically configures all tilemaps that need to be configured.
 pub(crate) fn tilemap_auto_configure(
     texture_atlases: Res<Assets<TextureAtlas>>,
@@ -1837,9 +3873,11 @@ pub(crate) fn tilemap_auto_configure(
 pub(crate) fn tilemap_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
+    task_pool: Res<AsyncComputeTaskPool>,
     mut query: Query<(Entity, &mut Tilemap)>,
 ) {
     for (map_entity, mut map) in query.iter_mut() {
+        map.update_pending_chunks(&task_pool);
         map.events.update();
 
         let mut modified_chunks = Vec::new();
@@ -1865,14 +3903,14 @@ pub(crate) fn tilemap_system(
         for point in spawned_chunks.into_iter() {
             let layers_len = map.layers.len();
             let chunk_dimensions = map.chunk_dimensions;
-            let tile_dimensions = map.tile_dimensions;
             let texture_atlas = map.texture_atlas().clone_weak();
             let pipeline_handle = map.topology.to_pipeline_handle();
+            let (translation_x, translation_y) = map.chunk_translation(point);
             let chunk = map.chunks.get_mut(&point).expect("`Chunk` is missing.");
             let mut entities = Vec::with_capacity(capacity);
             for z in 0..layers_len {
                 let mut mesh = Mesh::from(&ChunkMesh::new(chunk_dimensions));
-                let (indexes, colors) =
+                let (indexes, colors, flips) =
                     if let Some(parts) = chunk.tiles_to_renderer_parts(z, chunk_dimensions) {
                         parts
                     } else {
@@ -1880,17 +3918,11 @@ pub(crate) fn tilemap_system(
                     };
                 mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, indexes.into());
                 mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, colors.into());
+                mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_FLIP, flips.into());
                 let mesh_handle = meshes.add(mesh);
                 chunk.set_mesh(z, mesh_handle.clone());
 
-                let translation = Vec3::new(
-                    (chunk.point().x * tile_dimensions.width as i32 * chunk_dimensions.width as i32)
-                        as f32,
-                    (chunk.point().y
-                        * tile_dimensions.height as i32
-                        * chunk_dimensions.height as i32) as f32,
-                    z as f32,
-                );
+                let translation = Vec3::new(translation_x, translation_y, z as f32);
                 let pipeline = RenderPipeline::specialized(
                     pipeline_handle.clone_weak(),
                     PipelineSpecialization {
@@ -1927,11 +3959,20 @@ pub(crate) fn tilemap_system(
             commands.push_children(map_entity, &entities);
         }
 
+        // Several modifications can happen to the same chunk layer entity in
+        // a single frame, all of them are gathered so that none are lost.
+        let mut dirty_layers: HashMap<Entity, HashSet<usize>> = HashMap::default();
         for layers in modified_chunks.into_iter() {
             for (layer, entity) in layers.into_iter() {
-                commands.insert_one(entity, DirtyLayer(layer));
+                dirty_layers
+                    .entry(entity)
+                    .or_insert_with(HashSet::default)
+                    .insert(layer);
             }
         }
+        for (entity, layers) in dirty_layers.into_iter() {
+            commands.insert_one(entity, DirtyLayers(layers));
+        }
 
         for entities in despawned_chunks.into_iter() {
             for entity in entities {
@@ -1944,6 +3985,14 @@ pub(crate) fn tilemap_system(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        chunk::chunk_update_system,
+        entity::TilemapComponents,
+        lib::bevy::{
+            app::App, asset::AssetPlugin, core::CorePlugin, render::mesh::VertexAttributeValues,
+        },
+        stage,
+    };
 
     // fn new_tilemap_no_auto() -> Tilemap {
     //     let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
@@ -1967,4 +4016,601 @@ mod tests {
         tilemap.insert_chunk(Point2::new(1, 1)).unwrap();
         tilemap.insert_chunk(Point2::new(-1, -1)).unwrap();
     }
+
+    #[test]
+    fn update_many_dirty_layers() {
+        let mut app = App::build();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_stage(stage::TILEMAP)
+            .add_system_to_stage(stage::TILEMAP, tilemap_system.system())
+            .add_stage_after(stage::TILEMAP, "draw")
+            .add_system_to_stage("draw", chunk_update_system.system());
+
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .add_layer(LayerKind::Dense, 0)
+            .add_layer(LayerKind::Dense, 1)
+            .add_layer(LayerKind::Sparse, 2)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.spawn_chunk((0, 0)).unwrap();
+
+        let map_entity = app.app.world.spawn(TilemapComponents {
+            tilemap,
+            transform: Default::default(),
+            global_transform: Default::default(),
+        });
+        app.app.update();
+
+        let tiles = vec![
+            Tile::with_z_order((0, 0), 1, 0),
+            Tile::with_z_order((0, 0), 2, 1),
+            Tile::with_z_order((0, 0), 3, 2),
+        ];
+        app.app
+            .world
+            .get_mut::<Tilemap>(map_entity)
+            .unwrap()
+            .insert_tiles(tiles)
+            .unwrap();
+        app.app.update();
+
+        let tilemap = app.app.world.get::<Tilemap>(map_entity).unwrap();
+        let meshes = app.app.resources.get::<Assets<Mesh>>().unwrap();
+        let chunk = tilemap.get_chunk(&Point2::new(0, 0)).unwrap();
+        let tile_point = tilemap.point_to_tile_point(Point2::new(0, 0));
+        let vertex = tilemap.chunk_dimensions.encode_point_unchecked(tile_point) * 4;
+        for &(z_order, sprite_index) in [(0, 1), (1, 2), (2, 3)].iter() {
+            let mesh = meshes.get(chunk.get_mesh(z_order).unwrap()).unwrap();
+            match mesh.attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX) {
+                Some(VertexAttributeValues::Float(indexes)) => {
+                    assert_eq!(indexes[vertex], sprite_index as f32)
+                }
+                _ => panic!("tile indexes are missing from the mesh"),
+            }
+        }
+    }
+
+    fn new_tilemap_with_topology(topology: GridTopology) -> Tilemap {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .topology(topology)
+            .chunk_dimensions(4, 4)
+            .tile_dimensions(16, 16)
+            .finish()
+            .unwrap()
+    }
+
+    #[test]
+    fn point_to_world_known_points() {
+        use GridTopology::*;
+        let transform = Transform::default();
+        let cases = [
+            (Square, (0, 0), (8.0, 8.0)),
+            (Square, (-3, 5), (-40.0, 88.0)),
+            (HexY, (0, 0), (12.0, 6.0)),
+            (HexY, (0, 1), (20.0, 18.0)),
+            (HexX, (0, 0), (6.0, 12.0)),
+            (HexEvenRows, (0, 0), (12.0, 6.0)),
+            (HexEvenRows, (0, 1), (4.0, 18.0)),
+            (HexOddRows, (0, 0), (4.0, 6.0)),
+            (HexEvenCols, (0, 0), (6.0, 12.0)),
+            (HexOddCols, (0, 0), (6.0, 4.0)),
+        ];
+        for &(topology, point, (x, y)) in cases.iter() {
+            let tilemap = new_tilemap_with_topology(topology);
+            assert_eq!(
+                tilemap.point_to_world(&transform, point),
+                Vec2::new(x, y),
+                "{:?} {:?}",
+                topology,
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn point_to_world_with_transform() {
+        let tilemap = new_tilemap_with_topology(GridTopology::Square);
+        let mut transform = Transform::from_translation(Vec3::new(100.0, -50.0, 0.0));
+        transform.scale = Vec3::new(2.0, 2.0, 1.0);
+
+        assert_eq!(
+            tilemap.point_to_world(&transform, (1, 1)),
+            Vec2::new(148.0, -2.0)
+        );
+        assert_eq!(
+            tilemap.world_to_point(&transform, Vec2::new(148.0, -2.0)),
+            Point2::new(1, 1)
+        );
+        assert_eq!(
+            tilemap.world_to_point(&transform, Vec2::new(99.0, -51.0)),
+            Point2::new(-1, -1)
+        );
+    }
+
+    #[test]
+    fn world_to_point_round_trip() {
+        use GridTopology::*;
+        let transform = Transform::from_translation(Vec3::new(-7.0, 3.0, 0.0));
+        for &topology in [
+            Square,
+            HexY,
+            HexX,
+            HexEvenRows,
+            HexOddRows,
+            HexEvenCols,
+            HexOddCols,
+        ]
+        .iter()
+        {
+            let tilemap = new_tilemap_with_topology(topology);
+            for y in -10..10 {
+                for x in -10..10 {
+                    let point = Point2::new(x, y);
+                    let world = tilemap.point_to_world(&transform, point);
+                    // Slightly off center, still within the tile.
+                    let world = world + Vec2::new(2.0, -2.0);
+                    assert_eq!(
+                        tilemap.world_to_point(&transform, world),
+                        point,
+                        "{:?}",
+                        topology
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn neighbours_are_the_nearest_tiles() {
+        use GridTopology::*;
+        let transform = Transform::default();
+        for &topology in [HexY, HexX, HexEvenRows, HexOddRows, HexEvenCols, HexOddCols].iter() {
+            let tilemap = new_tilemap_with_topology(topology);
+            for y in -3..3 {
+                for x in -3..3 {
+                    let center = tilemap.point_to_world(&transform, (x, y));
+                    let mut nearest = Vec::new();
+                    for ny in y - 2..=y + 2 {
+                        for nx in x - 2..=x + 2 {
+                            if (nx, ny) == (x, y) {
+                                continue;
+                            }
+                            let distance =
+                                (tilemap.point_to_world(&transform, (nx, ny)) - center).length();
+                            nearest.push((distance, Point2::new(nx, ny)));
+                        }
+                    }
+                    nearest.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                    let mut expected: Vec<Point2> =
+                        nearest.iter().take(6).map(|(_, point)| *point).collect();
+                    let mut neighbours = tilemap.neighbours((x, y));
+                    expected.sort_by_key(|point| (point.x, point.y));
+                    neighbours.sort_by_key(|point| (point.x, point.y));
+                    assert_eq!(neighbours, expected, "{:?} ({}, {})", topology, x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn square_neighbours() {
+        let mut tilemap = new_tilemap_with_topology(GridTopology::Square);
+        let mut neighbours = tilemap.neighbours((-1, 2));
+        neighbours.sort_by_key(|point| (point.x, point.y));
+        assert_eq!(
+            neighbours,
+            vec![
+                Point2::new(-2, 2),
+                Point2::new(-1, 1),
+                Point2::new(-1, 3),
+                Point2::new(0, 2)
+            ]
+        );
+
+        tilemap.set_diagonal_neighbours(true);
+        assert_eq!(tilemap.neighbours((-1, 2)).len(), 8);
+        assert!(tilemap.neighbours((-1, 2)).contains(&Point2::new(-2, 1)));
+    }
+
+    #[test]
+    fn fog_only_modifies_changed_chunks() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .add_layer(LayerKind::Dense, 0)
+            .add_layer(LayerKind::Fog(FogTints::default()), 1)
+            .finish()
+            .unwrap();
+        for chunk_point in [(0, 0), (1, 0), (0, 1)].iter() {
+            tilemap.insert_chunk(*chunk_point).unwrap();
+        }
+        let reset = |tilemap: &mut Tilemap| {
+            for chunk in tilemap.chunks.values_mut() {
+                chunk.set_modified(false);
+            }
+        };
+        let modified = |tilemap: &Tilemap| {
+            let mut points: Vec<Point2> = tilemap
+                .chunks
+                .iter()
+                .filter(|(_, chunk)| chunk.is_modified())
+                .map(|(point, _)| *point)
+                .collect();
+            points.sort();
+            points
+        };
+
+        reset(&mut tilemap);
+        tilemap.update_fog(1, vec![(0, 0), (1, 0)]).unwrap();
+        assert_eq!(modified(&tilemap), vec![Point2::new(0, 0)]);
+
+        // Seeing the same points again changes nothing.
+        reset(&mut tilemap);
+        tilemap.update_fog(1, vec![(0, 0), (1, 0)]).unwrap();
+        assert!(modified(&tilemap).is_empty());
+
+        reset(&mut tilemap);
+        tilemap.update_fog(1, vec![(1, 0), (2, 0)]).unwrap();
+        assert_eq!(
+            modified(&tilemap),
+            vec![Point2::new(0, 0), Point2::new(1, 0)]
+        );
+        assert_eq!(tilemap.get_fog((0, 0), 1), Some(FogState::Explored));
+        assert_eq!(tilemap.get_fog((1, 0), 1), Some(FogState::Visible));
+        assert_eq!(tilemap.get_fog((2, 0), 1), Some(FogState::Visible));
+        assert_eq!(tilemap.get_fog((0, 2), 1), Some(FogState::Unexplored));
+
+        reset(&mut tilemap);
+        tilemap
+            .set_fog_states(
+                1,
+                vec![((0, 2), FogState::Explored), ((9, 9), FogState::Visible)],
+            )
+            .unwrap();
+        assert_eq!(modified(&tilemap), vec![Point2::new(0, 1)]);
+        assert_eq!(tilemap.get_fog((0, 2), 1), Some(FogState::Explored));
+
+        assert!(tilemap.update_fog(0, vec![(0, 0)]).is_err());
+        assert_eq!(tilemap.get_fog((0, 0), 0), None);
+    }
+
+    #[test]
+    fn pending_chunks_are_inserted_once_generated() {
+        struct Grass;
+
+        impl ChunkGenerator for Grass {
+            fn generate(
+                &self,
+                _: Point2,
+                dimensions: Dimension2,
+                _: &[Option<LayerKind>],
+            ) -> Vec<Tile> {
+                let mut tiles = vec![Tile::new((0, 0), 1), Tile::with_z_order((0, 0), 1, 99)];
+                tiles.push(Tile::new((dimensions.width as i32, 0), 1));
+                tiles
+            }
+        }
+
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .generator(Grass)
+            .finish()
+            .unwrap();
+        let point = Point2::new(1, 0);
+        tilemap.spawn_chunk(point).unwrap();
+        assert!(!tilemap.chunks.contains_key(&point));
+
+        // A tile inserted into the pending chunk is queued.
+        tilemap.insert_tile(Tile::new((3, 1), 9)).unwrap();
+        assert!(!tilemap.chunks.contains_key(&point));
+
+        let task_pool = TaskPool::new();
+        for _ in 0..1000 {
+            tilemap.update_pending_chunks(&task_pool);
+            if tilemap.chunks.contains_key(&point) {
+                break;
+            }
+            ::std::thread::sleep(::std::time::Duration::from_millis(1));
+        }
+
+        assert_eq!(tilemap.get_tile((2, -2), 0).unwrap().index, 1);
+        assert_eq!(tilemap.get_tile((3, 1), 0).unwrap().index, 9);
+        assert_eq!(tilemap.get_tile((2, -2), 99), None);
+    }
+
+    #[cfg(feature = "loader")]
+    #[test]
+    fn check_chunks_out_of_bounds() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .dimensions(1, 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        assert!(tilemap.check_chunks().is_ok());
+
+        // As if a chunk was added to a tilemap file by hand.
+        let chunk = tilemap.chunks[&Point2::new(0, 0)].clone();
+        tilemap.chunks.insert(Point2::new(4, 0), chunk);
+        assert!(tilemap.check_chunks().is_err());
+    }
+
+    #[cfg(feature = "loader")]
+    #[test]
+    fn reload_changed_layers() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut old = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .add_layer(LayerKind::Sparse, 0)
+            .add_layer(LayerKind::Sparse, 1)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        let tiles = vec![
+            Tile::new((0, 0), 1),
+            Tile::with_z_order((0, 0), 2, 1),
+            Tile::new((2, 0), 3),
+        ];
+        old.insert_tiles(tiles).unwrap();
+
+        let mut tilemap = old.duplicate();
+        tilemap.spawn_all_chunks().unwrap();
+        let entities = [Entity::new(1), Entity::new(2), Entity::new(3)];
+        let chunk = tilemap.chunks.get_mut(&Point2::new(0, 0)).unwrap();
+        chunk.add_entity(0, entities[0]);
+        chunk.add_entity(1, entities[1]);
+        let chunk = tilemap.chunks.get_mut(&Point2::new(1, 0)).unwrap();
+        chunk.add_entity(0, entities[2]);
+        tilemap
+            .insert_tile(Tile::with_z_order((1, 1), 7, 1))
+            .unwrap();
+
+        let mut new = old.duplicate();
+        new.insert_tile(Tile::new((0, 0), 5)).unwrap();
+        new.remove_chunk((1, 0)).unwrap();
+        let mut reader = tilemap.events.get_reader_current();
+        tilemap.reload(&old, &new).unwrap();
+
+        // The changed layer is replaced, the unchanged layer keeps its edit.
+        assert_eq!(tilemap.get_tile((0, 0), 0).unwrap().index, 5);
+        assert_eq!(tilemap.get_tile((0, 0), 1).unwrap().index, 2);
+        assert_eq!(tilemap.get_tile((1, 1), 1).unwrap().index, 7);
+        assert!(!tilemap.chunks.contains_key(&Point2::new(1, 0)));
+        assert!(!tilemap.spawned.contains(&Point2::new(1, 0)));
+
+        let mut modified = Vec::new();
+        let mut despawned = Vec::new();
+        for event in reader.iter(&tilemap.events) {
+            match event {
+                ChunkEvent::Modified { layers } => modified.push(layers.clone()),
+                ChunkEvent::Despawned { entities } => despawned.push(entities.clone()),
+                ChunkEvent::Spawned { .. } => {}
+            }
+        }
+        let mut layers = HashMap::default();
+        layers.insert(0, entities[0]);
+        assert_eq!(modified, vec![layers]);
+        assert_eq!(despawned, vec![vec![entities[2]]]);
+    }
+
+    #[cfg(feature = "loader")]
+    #[test]
+    fn failed_reload_leaves_tilemap_untouched() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut old = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .dimensions(2, 2)
+            .chunk_dimensions(4, 4)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        old.insert_tile(Tile::new((0, 0), 1)).unwrap();
+
+        let mut tilemap = old.duplicate();
+        tilemap.spawn_all_chunks().unwrap();
+
+        let mut new = old.duplicate();
+        new.insert_tile(Tile::new((0, 0), 5)).unwrap();
+        new.add_animation(TileAnimation::new(vec![(1, 0.5), (2, 0.5)]));
+        let chunk = new.chunks.get(&Point2::new(0, 0)).unwrap().clone();
+        new.chunks.insert(Point2::new(5, 5), chunk);
+        let mut reader = tilemap.events.get_reader_current();
+
+        assert!(tilemap.reload(&old, &new).is_err());
+        assert_eq!(tilemap.get_tile((0, 0), 0).unwrap().index, 1);
+        assert!(tilemap.animations.is_empty());
+        assert!(tilemap.spawned.contains(&Point2::new(0, 0)));
+        assert!(reader.iter(&tilemap.events).next().is_none());
+    }
+
+    #[test]
+    fn sprites_are_rejected_by_fog_layers() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .add_layer(LayerKind::Fog(FogTints::default()), 1)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        let fog = Tile::with_z_order((0, 0), 1, 1);
+        let expected: TilemapError = ErrorKind::LayerIsFog(1).into();
+
+        assert_eq!(tilemap.insert_tile(fog).unwrap_err(), expected);
+        assert_eq!(
+            tilemap.fill_rect((0, 0), (1, 1), fog).unwrap_err(),
+            expected
+        );
+        tilemap.insert_tile(Tile::new((0, 0), 1)).unwrap();
+        assert_eq!(tilemap.flood_fill((0, 0), fog).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn auto_tiles_only_modify_changed_chunks() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut sprites = [0; 16];
+        for (mask, sprite) in sprites.iter_mut().enumerate() {
+            *sprite = 10 + mask;
+        }
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .chunk_dimensions(4, 4)
+            .auto_tile(1, AutoTileset::bitmask4(sprites))
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((1, 0)).unwrap();
+        let walls = vec![
+            Tile::with_terrain((2, 0), 1, 0),
+            Tile::with_terrain((3, 0), 1, 0),
+        ];
+        tilemap.insert_tiles(walls).unwrap();
+        for chunk in tilemap.chunks.values_mut() {
+            chunk.set_modified(false);
+        }
+
+        // The new wall is only a diagonal neighbour of the wall in the other
+        // chunk, which keeps its sprite.
+        tilemap
+            .insert_tile(Tile::with_terrain((1, 1), 1, 0))
+            .unwrap();
+        assert!(tilemap.chunks[&Point2::new(0, 0)].is_modified());
+        assert!(!tilemap.chunks[&Point2::new(1, 0)].is_modified());
+        assert_eq!(tilemap.get_tile((2, 0), 0).unwrap().index, 12);
+    }
+
+    #[test]
+    fn reading_through_tile_mut_keeps_chunks_unmodified() {
+        let mut tilemap = new_tilemap_with_topology(GridTopology::Square);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+        tilemap
+            .chunks
+            .get_mut(&Point2::new(0, 0))
+            .unwrap()
+            .set_modified(false);
+
+        assert_eq!(tilemap.get_tile_mut((1, 1), 0).unwrap().index, 3);
+        assert!(!tilemap.chunks[&Point2::new(0, 0)].is_modified());
+
+        tilemap.get_tile_mut((1, 1), 0).unwrap().index = 4;
+        assert!(tilemap.chunks[&Point2::new(0, 0)].is_modified());
+    }
+
+    #[test]
+    fn flood_fill_stays_in_existing_chunks() {
+        let mut tilemap = new_tilemap_with_topology(GridTopology::HexEvenRows);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((1, 0)).unwrap();
+
+        let grass = Tile::new((0, 0), 2);
+        let points = tilemap.flood_fill((0, 0), grass).unwrap();
+        assert_eq!(points.len(), 32);
+        assert!(points.iter().all(|point| point.x >= -2 && point.x < 6));
+        assert!(points.iter().all(|point| point.y >= -2 && point.y < 2));
+        assert_eq!(tilemap.get_tile((5, 1), 0).unwrap().index, 2);
+
+        // Filling with the same tile changes nothing.
+        assert!(tilemap.flood_fill((3, -1), grass).unwrap().is_empty());
+
+        // A wall across the chunks splits the area in two.
+        let wall = Tile::new((0, 0), 1);
+        let points = tilemap.fill_rect((1, -2), (1, 1), wall).unwrap();
+        assert_eq!(points.len(), 4);
+        let water = Tile::new((0, 0), 3);
+        let points = tilemap.flood_fill((0, 0), water).unwrap();
+        assert_eq!(points.len(), 12);
+        assert_eq!(tilemap.get_tile((2, 0), 0).unwrap().index, 2);
+
+        // Only the points that were not a wall yet are changed.
+        let points = tilemap.fill_rect((1, -2), (2, 1), wall).unwrap();
+        assert_eq!(points.len(), 4);
+        assert!(points.iter().all(|point| point.x == 2));
+        assert!(tilemap.fill_rect((1, -2), (1, 1), wall).unwrap().is_empty());
+
+        assert!(tilemap.flood_fill((9, 9), water).is_err());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn compact_bytes_round_trip() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::builder()
+            .texture_atlas(texture_atlas_handle)
+            .add_layer(LayerKind::Dense, 0)
+            .add_layer(LayerKind::Sparse, 2)
+            .add_layer(LayerKind::Fog(FogTints::default()), 3)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        let water = tilemap.add_animation(TileAnimation::new(vec![(4, 0.5), (5, 0.5)]));
+        tilemap.insert_auto_tileset(1, AutoTileset::bitmask4([0; 16]));
+        let mut tiles = Vec::new();
+        for y in -40..40 {
+            for x in -40..40 {
+                tiles.push(Tile::new((x, y), 1));
+            }
+        }
+        tiles.push(Tile::with_tint((3, -7), 2, Color::rgba(0.1, 0.2, 0.3, 0.4)));
+        tiles.push(Tile::with_terrain((5, 5), 1, 0));
+        tiles.push(Tile::with_animation((-20, 6), water, 0));
+        tiles.push(Tile::with_z_order((39, 39), 6, 2));
+        tiles.push(Tile::with_z_order((-40, 12), 7, 2));
+        tilemap.insert_tiles(tiles).unwrap();
+        tilemap.update_fog(3, (-10..10).map(|x| (x, 3))).unwrap();
+
+        let bytes = tilemap.to_bytes().unwrap();
+        let decoded = Tilemap::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.topology, tilemap.topology);
+        assert_eq!(decoded.dimensions, tilemap.dimensions);
+        assert_eq!(decoded.chunk_dimensions, tilemap.chunk_dimensions);
+        assert_eq!(decoded.tile_dimensions, tilemap.tile_dimensions);
+        assert_eq!(decoded.layers, tilemap.layers);
+        assert_eq!(decoded.auto_flags, tilemap.auto_flags);
+        assert_eq!(decoded.auto_tilesets, tilemap.auto_tilesets);
+        assert_eq!(decoded.animations, tilemap.animations);
+        assert_eq!(decoded.chunks, tilemap.chunks);
+        assert!(bytes.len() * 10 < bincode::serialize(&tilemap).unwrap().len());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn compact_bytes_are_checked() {
+        assert!(Tilemap::from_bytes(b"").is_err());
+
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_tile(Tile::new((1, 1), 3)).unwrap();
+        let bytes = tilemap.to_bytes().unwrap();
+        for len in 0..bytes.len() {
+            assert!(Tilemap::from_bytes(&bytes[..len]).is_err());
+        }
+
+        // Chunk dimensions in a crafted header that are empty, overflow or
+        // would allocate huge chunks.
+        for &(width, height) in [(0, 4), (1 << 16, 1 << 16), (1 << 12, 1 << 12)].iter() {
+            tilemap.chunk_dimensions = Dimension2::new(width, height);
+            let bytes = tilemap.to_bytes().unwrap();
+            let err = Tilemap::from_bytes(&bytes).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "invalid data: chunk dimensions out of bounds"
+            );
+        }
+    }
 }
@@ -0,0 +1,380 @@
+//! Helpers for the layouts of the grid topologies.
+//!
+//! The points of a tilemap are laid out differently for every
+//! [`GridTopology`]. Square grids have 4 neighbours, or 8 with diagonals. Hex
+//! grids always have 6 neighbours, and which points those are depends on the
+//! variant of hex grid and, for the even and odd variants, on whether the
+//! row or column of the point is even or odd. The neighbours here are the
+//! tiles that are adjacent to each other as they are rendered.
+//!
+//! [`GridTopology`]: crate::render::GridTopology
+
+use crate::{lib::*, render::GridTopology};
+
+/// The offsets of the neighbours on a square grid, without diagonals.
+const SQUARE_OFFSETS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// The offsets of the neighbours on a square grid, with diagonals.
+const SQUARE_DIAGONAL_OFFSETS: [(i32, i32); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// The offsets of the neighbours on a hex grid with rows offset.
+const HEX_Y_OFFSETS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// The offsets of the neighbours on a hex grid with columns offset.
+const HEX_X_OFFSETS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// The offsets of the neighbours in a row or column which is shifted forward,
+/// towards positive coordinates, compared to the rows or columns next to it.
+const HEX_SHIFTED_ROW_OFFSETS: [(i32, i32); 6] =
+    [(1, 0), (1, 1), (0, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// The offsets of the neighbours in a row or column which is shifted back,
+/// towards negative coordinates, compared to the rows or columns next to it.
+const HEX_UNSHIFTED_ROW_OFFSETS: [(i32, i32); 6] =
+    [(1, 0), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1)];
+
+/// Returns the points that are adjacent to a point in a grid topology.
+///
+/// Square grids have 4 neighbours, or 8 if `diagonals` is set. Hex grids have
+/// 6 neighbours and ignore `diagonals`. The neighbours are always returned in
+/// the same order for the same topology and parity of the point.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::prelude::*;
+/// use bevy_tilemap::topology::neighbours;
+/// use bevy_tilemap_types::point::Point2;
+///
+/// let square = neighbours(GridTopology::Square, Point2::new(0, 0), false);
+/// assert_eq!(
+///     square,
+///     vec![Point2::new(1, 0), Point2::new(0, 1), Point2::new(-1, 0), Point2::new(0, -1)]
+/// );
+///
+/// let square = neighbours(GridTopology::Square, Point2::new(0, 0), true);
+/// assert_eq!(square.len(), 8);
+///
+/// let hex = neighbours(GridTopology::HexEvenRows, Point2::new(0, 0), false);
+/// assert_eq!(hex.len(), 6);
+/// assert!(hex.contains(&Point2::new(1, 1)));
+/// assert!(!hex.contains(&Point2::new(-1, 1)));
+/// ```
+pub fn neighbours(topology: GridTopology, point: Point2, diagonals: bool) -> Vec<Point2> {
+    use GridTopology::*;
+    let even_x = point.x % 2 == 0;
+    let even_y = point.y % 2 == 0;
+    let offsets: &[(i32, i32)] = match topology {
+        Square if diagonals => &SQUARE_DIAGONAL_OFFSETS,
+        Square => &SQUARE_OFFSETS,
+        HexY => &HEX_Y_OFFSETS,
+        HexX => &HEX_X_OFFSETS,
+        // Even rows are shifted right compared to odd rows and the other way
+        // around.
+        HexEvenRows | HexOddRows => {
+            if even_y == (topology == HexEvenRows) {
+                &HEX_SHIFTED_ROW_OFFSETS
+            } else {
+                &HEX_UNSHIFTED_ROW_OFFSETS
+            }
+        }
+        // Even columns are shifted up compared to odd columns and the other
+        // way around.
+        HexEvenCols | HexOddCols => {
+            let offsets = if even_x == (topology == HexEvenCols) {
+                &HEX_SHIFTED_ROW_OFFSETS
+            } else {
+                &HEX_UNSHIFTED_ROW_OFFSETS
+            };
+            // The columns are the rows with the axes swapped.
+            return offsets
+                .iter()
+                .map(|(y, x)| Point2::new(point.x + x, point.y + y))
+                .collect();
+        }
+    };
+    offsets
+        .iter()
+        .map(|(x, y)| Point2::new(point.x + x, point.y + y))
+        .collect()
+}
+
+/// Converts a point of a hex grid into axial coordinates, in which the
+/// neighbours of every point are the same as on a [`GridTopology::HexY`] grid.
+///
+/// Square grids are returned as they are.
+///
+/// [`GridTopology::HexY`]: crate::render::GridTopology::HexY
+pub(crate) fn to_axial(topology: GridTopology, point: Point2) -> (i32, i32) {
+    use GridTopology::*;
+    let (x, y) = (point.x, point.y);
+    match topology {
+        Square | HexY | HexX => (x, y),
+        HexEvenRows => (x - (y + (y & 1)) / 2, y),
+        HexOddRows => (x - (y - (y & 1)) / 2, y),
+        HexEvenCols => (y - (x + (x & 1)) / 2, x),
+        HexOddCols => (y - (x - (x & 1)) / 2, x),
+    }
+}
+
+/// Converts axial coordinates back into a point of a hex grid.
+pub(crate) fn from_axial(topology: GridTopology, (q, r): (i32, i32)) -> Point2 {
+    use GridTopology::*;
+    match topology {
+        Square | HexY | HexX => Point2::new(q, r),
+        HexEvenRows => Point2::new(q + (r + (r & 1)) / 2, r),
+        HexOddRows => Point2::new(q + (r - (r & 1)) / 2, r),
+        HexEvenCols => Point2::new(r, q + (r + (r & 1)) / 2),
+        HexOddCols => Point2::new(r, q + (r - (r & 1)) / 2),
+    }
+}
+
+/// Returns the least amount of steps between two points in a grid topology.
+///
+/// On square grids the steps are only to the 4 direct neighbours, unless
+/// `diagonals` is set.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::prelude::*;
+/// use bevy_tilemap::topology::distance;
+///
+/// let (a, b) = (Point2::new(0, 0), Point2::new(3, -2));
+///
+/// assert_eq!(distance(GridTopology::Square, false, a, b), 5);
+/// assert_eq!(distance(GridTopology::Square, true, a, b), 3);
+/// assert_eq!(distance(GridTopology::HexY, false, a, b), 3);
+/// ```
+pub fn distance(topology: GridTopology, diagonals: bool, a: Point2, b: Point2) -> u32 {
+    let (aq, ar) = to_axial(topology, a);
+    let (bq, br) = to_axial(topology, b);
+    let (dq, dr) = (bq - aq, br - ar);
+    let distance = match topology {
+        GridTopology::Square if diagonals => dq.abs().max(dr.abs()),
+        GridTopology::Square => dq.abs() + dr.abs(),
+        _ => (dq.abs() + dr.abs() + (dq + dr).abs()) / 2,
+    };
+    distance as u32
+}
+
+/// Returns the points of a line between two points in a grid topology, both
+/// included.
+///
+/// Lines on square grids are drawn with Bresenham's algorithm, lines on hex
+/// grids step through the hexes the straight line between the centers passes.
+/// Every point of a line on a hex grid is a neighbour of the point before it.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::prelude::*;
+/// use bevy_tilemap::topology::line;
+///
+/// let points = line(GridTopology::Square, Point2::new(0, 0), Point2::new(4, 1));
+///
+/// assert_eq!(
+///     points,
+///     vec![
+///         Point2::new(0, 0),
+///         Point2::new(1, 0),
+///         Point2::new(2, 1),
+///         Point2::new(3, 1),
+///         Point2::new(4, 1),
+///     ]
+/// );
+/// ```
+pub fn line(topology: GridTopology, from: Point2, to: Point2) -> Vec<Point2> {
+    if topology == GridTopology::Square {
+        return square_line(from, to);
+    }
+    let (aq, ar) = to_axial(topology, from);
+    let (bq, br) = to_axial(topology, to);
+    let steps = distance(topology, false, from, to);
+    let mut points = Vec::with_capacity(steps as usize + 1);
+    points.push(from);
+    for step in 1..=steps {
+        let t = step as f64 / steps as f64;
+        // Nudged so that lines along the edges of hexes always pick the same
+        // side.
+        let q = aq as f64 + (bq - aq) as f64 * t + 1e-6;
+        let r = ar as f64 + (br - ar) as f64 * t + 2e-6;
+        points.push(from_axial(topology, round_axial(q, r)));
+    }
+    points
+}
+
+/// Returns the points of a line on a square grid with Bresenham's algorithm.
+fn square_line(from: Point2, to: Point2) -> Vec<Point2> {
+    let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut points = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    let (mut x, mut y) = (from.x, from.y);
+    let mut err = dx + dy;
+    loop {
+        points.push(Point2::new(x, y));
+        if x == to.x && y == to.y {
+            return points;
+        }
+        let err2 = 2 * err;
+        if err2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if err2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Returns the points of a circle around a center point in a grid topology.
+///
+/// Circles on square grids are the points within the radius of the center,
+/// with a little slack so that the edges look round. Circles on hex grids are
+/// hexagons of the points within the radius in steps. If `filled` is not set,
+/// only the points on the edge of the circle are returned, which are the ones
+/// that have a neighbour outside of it. The points are sorted by row.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::prelude::*;
+/// use bevy_tilemap::topology::circle;
+///
+/// let center = Point2::new(0, 0);
+///
+/// assert_eq!(circle(GridTopology::Square, center, 0, true), vec![center]);
+/// assert_eq!(circle(GridTopology::Square, center, 1, true).len(), 9);
+/// assert_eq!(circle(GridTopology::Square, center, 1, false).len(), 8);
+/// assert_eq!(circle(GridTopology::HexY, center, 1, true).len(), 7);
+/// assert_eq!(circle(GridTopology::HexY, center, 2, false).len(), 12);
+/// ```
+pub fn circle(topology: GridTopology, center: Point2, radius: u32, filled: bool) -> Vec<Point2> {
+    let r = radius as i64;
+    let inside = |point: Point2| match topology {
+        GridTopology::Square => {
+            let (dx, dy) = ((point.x - center.x) as i64, (point.y - center.y) as i64);
+            dx * dx + dy * dy <= r * r + r
+        }
+        _ => distance(topology, false, center, point) <= radius,
+    };
+
+    // Points of hex grids with offset rows or columns can be up to one and a
+    // half times the radius away on the other axis.
+    let span = 2 * radius as i32;
+    let mut points = Vec::new();
+    for y in center.y - span..=center.y + span {
+        for x in center.x - span..=center.x + span {
+            let point = Point2::new(x, y);
+            if !inside(point) {
+                continue;
+            }
+            if filled
+                || neighbours(topology, point, false)
+                    .into_iter()
+                    .any(|neighbour| !inside(neighbour))
+            {
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+/// Rounds fractional axial coordinates to the hex they are in.
+fn round_axial(q: f64, r: f64) -> (i32, i32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq as i32, rr as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEXES: [GridTopology; 6] = [
+        GridTopology::HexY,
+        GridTopology::HexX,
+        GridTopology::HexEvenRows,
+        GridTopology::HexOddRows,
+        GridTopology::HexEvenCols,
+        GridTopology::HexOddCols,
+    ];
+
+    #[test]
+    fn axial_round_trip() {
+        for &topology in HEXES.iter() {
+            for y in -5..5 {
+                for x in -5..5 {
+                    let point = Point2::new(x, y);
+                    assert_eq!(from_axial(topology, to_axial(topology, point)), point);
+                    for neighbour in neighbours(topology, point, false) {
+                        assert_eq!(distance(topology, false, point, neighbour), 1);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hex_lines_are_connected() {
+        for &topology in HEXES.iter() {
+            let from = Point2::new(-3, 2);
+            for &to in [Point2::new(4, -1), Point2::new(-3, -6), Point2::new(5, 5)].iter() {
+                let points = line(topology, from, to);
+                assert_eq!(points.first(), Some(&from));
+                assert_eq!(points.last(), Some(&to));
+                assert_eq!(points.len() as u32, distance(topology, false, from, to) + 1);
+                for pair in points.windows(2) {
+                    assert!(neighbours(topology, pair[0], false).contains(&pair[1]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn circle_edges_are_inside_the_fill() {
+        let center = Point2::new(3, -2);
+        for &topology in HEXES.iter().chain([GridTopology::Square].iter()) {
+            for radius in 0..6 {
+                let filled = circle(topology, center, radius, true);
+                let edge = circle(topology, center, radius, false);
+                assert!(filled.contains(&center));
+                assert!(edge.iter().all(|point| filled.contains(point)));
+                if topology != GridTopology::Square {
+                    assert_eq!(filled.len() as u32, 3 * radius * (radius + 1) + 1);
+                    assert_eq!(edge.len() as u32, (6 * radius).max(1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn square_lines() {
+        let from = Point2::new(2, 1);
+        for &to in [Point2::new(-3, 4), Point2::new(2, -5), Point2::new(9, 8)].iter() {
+            let points = line(GridTopology::Square, from, to);
+            assert_eq!(points.first(), Some(&from));
+            assert_eq!(points.last(), Some(&to));
+            assert_eq!(
+                points.len() as u32,
+                distance(GridTopology::Square, true, from, to) + 1
+            );
+            for pair in points.windows(2) {
+                assert!(neighbours(GridTopology::Square, pair[0], true).contains(&pair[1]));
+            }
+        }
+    }
+}